@@ -0,0 +1,313 @@
+//! Compression of the payload before concealing.
+//!
+//! Every concealed byte costs a few lines of the cover text, so it pays off to squeeze the payload.
+//! Two codecs are available:
+//! * [`Codec::Deflate`] - general-purpose codec, good for binary data and longer messages,
+//! * [`Codec::EnglishText`] - static Huffman code tuned for short English messages, which are too
+//!   short for Deflate to learn anything about them.
+//!
+//! [`compress`] tries all of them and picks the smallest output, falling back to
+//! [`Codec::Stored`] when nothing helps.
+//!
+//! # Examples
+//!
+//! ```
+//! use ptero_common::compression::{compress, decompress, Codec};
+//!
+//! let message = b"meet me at the old bridge at noon";
+//! let (codec, compressed) = compress(message);
+//!
+//! assert_eq!(codec, Codec::EnglishText);
+//! assert!(compressed.len() < message.len());
+//! assert_eq!(decompress(codec, &compressed), Ok(message.to_vec()));
+//! ```
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+
+use bitvec::prelude::*;
+use miniz_oxide::deflate::compress_to_vec;
+use miniz_oxide::inflate::{decompress_to_vec_with_limit, TINFLStatus};
+use snafu::Snafu;
+
+use crate::frame::{decode_varint, encode_varint};
+
+/// Upper bound of the decompressed payload size, protects from decompression bombs.
+pub const MAX_DECOMPRESSED_LENGTH: usize = 1 << 24;
+
+const DEFLATE_LEVEL: u8 = 10;
+const SYMBOL_COUNT: usize = 256;
+/// Weight of the bytes not listed in [`ENGLISH_TEXT_WEIGHTS`], they still have to be encodable.
+const DEFAULT_WEIGHT: u32 = 1;
+/// Approximate frequencies of characters in short English messages (per ~10 000 characters).
+const ENGLISH_TEXT_WEIGHTS: &[(u8, u32)] = &[
+    (b' ', 1800),
+    (b'e', 1000),
+    (b't', 750),
+    (b'a', 650),
+    (b'o', 620),
+    (b'i', 570),
+    (b'n', 560),
+    (b's', 530),
+    (b'h', 500),
+    (b'r', 490),
+    (b'd', 340),
+    (b'l', 330),
+    (b'c', 230),
+    (b'u', 230),
+    (b'm', 200),
+    (b'w', 190),
+    (b'f', 180),
+    (b'g', 160),
+    (b'y', 160),
+    (b'p', 150),
+    (b'b', 120),
+    (b'v', 80),
+    (b'k', 60),
+    (b'.', 60),
+    (b',', 60),
+    (b'I', 40),
+    (b'T', 40),
+    (b'\n', 30),
+    (b'A', 25),
+    (b'S', 20),
+    (b'\'', 20),
+    (b'H', 15),
+    (b'W', 15),
+    (b'M', 15),
+    (b'B', 12),
+    (b'C', 12),
+    (b'N', 10),
+    (b'O', 10),
+    (b'P', 10),
+    (b'D', 10),
+    (b'E', 10),
+    (b'L', 10),
+    (b'R', 10),
+    (b'G', 8),
+    (b'F', 8),
+    (b'Y', 8),
+    (b'J', 6),
+    (b'U', 5),
+    (b'K', 5),
+    (b'V', 4),
+    (b'Q', 2),
+    (b'X', 2),
+    (b'Z', 2),
+    (b'j', 10),
+    (b'x', 10),
+    (b'q', 8),
+    (b'z', 6),
+    (b'0', 12),
+    (b'1', 12),
+    (b'2', 10),
+    (b'3', 8),
+    (b'4', 8),
+    (b'5', 8),
+    (b'6', 6),
+    (b'7', 6),
+    (b'8', 6),
+    (b'9', 6),
+    (b'!', 12),
+    (b'?', 12),
+    (b'"', 10),
+    (b'-', 10),
+    (b':', 6),
+    (b'@', 6),
+    (b'#', 6),
+    (b'/', 6),
+    (b';', 3),
+    (b'(', 3),
+    (b')', 3),
+    (b'&', 2),
+    (b'%', 2),
+    (b'$', 2),
+    (b'*', 2),
+    (b'_', 2),
+];
+
+/// Codec used to compress the payload.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Codec {
+    /// Payload is not compressed.
+    Stored,
+    /// Raw Deflate stream (RFC 1951).
+    Deflate,
+    /// Payload length (varint) followed by the static Huffman code of English text.
+    EnglishText,
+}
+
+/// Compresses the payload with every codec and returns the smallest result.
+pub fn compress(payload: &[u8]) -> (Codec, Vec<u8>) {
+    [Codec::Deflate, Codec::EnglishText]
+        .iter()
+        .map(|codec| (*codec, compress_with(*codec, payload)))
+        .fold((Codec::Stored, payload.to_vec()), |best, candidate| {
+            if candidate.1.len() < best.1.len() {
+                candidate
+            } else {
+                best
+            }
+        })
+}
+
+/// Compresses the payload with given codec.
+pub fn compress_with(codec: Codec, payload: &[u8]) -> Vec<u8> {
+    match codec {
+        Codec::Stored => payload.to_vec(),
+        Codec::Deflate => compress_to_vec(payload, DEFLATE_LEVEL),
+        Codec::EnglishText => HuffmanCode::english_text().encode(payload),
+    }
+}
+
+/// Restores the payload compressed with given codec.
+///
+/// Fails with [`CompressionError::Corrupted`] if the data cannot be decompressed or with
+/// [`CompressionError::TooLarge`] if the result would exceed [`MAX_DECOMPRESSED_LENGTH`].
+pub fn decompress(codec: Codec, data: &[u8]) -> Result<Vec<u8>, CompressionError> {
+    match codec {
+        Codec::Stored => Ok(data.to_vec()),
+        Codec::Deflate => {
+            decompress_to_vec_with_limit(data, MAX_DECOMPRESSED_LENGTH).map_err(|error| match error
+                .status
+            {
+                TINFLStatus::HasMoreOutput => CompressionError::TooLarge,
+                _ => CompressionError::Corrupted,
+            })
+        }
+        Codec::EnglishText => HuffmanCode::english_text().decode(data),
+    }
+}
+
+/// Canonical Huffman code built from static symbol weights.
+struct HuffmanCode {
+    lengths: [u8; SYMBOL_COUNT],
+    codes: [u32; SYMBOL_COUNT],
+    /// Symbols ordered by code length, then by value - the canonical order.
+    sorted_symbols: Vec<u8>,
+}
+
+impl HuffmanCode {
+    fn english_text() -> Self {
+        let mut weights = [DEFAULT_WEIGHT; SYMBOL_COUNT];
+        for (symbol, weight) in ENGLISH_TEXT_WEIGHTS {
+            weights[*symbol as usize] = *weight;
+        }
+        HuffmanCode::from_weights(&weights)
+    }
+
+    fn from_weights(weights: &[u32; SYMBOL_COUNT]) -> Self {
+        // Leaves are nodes `0..SYMBOL_COUNT`, every merge creates a new node
+        let mut parents = vec![0usize; 2 * SYMBOL_COUNT - 1];
+        let mut heap = weights
+            .iter()
+            .enumerate()
+            .map(|(node, weight)| Reverse((*weight as u64, node)))
+            .collect::<BinaryHeap<_>>();
+        let mut next_node = SYMBOL_COUNT;
+        while let (Some(Reverse((first_weight, first))), Some(Reverse((second_weight, second)))) =
+            (heap.pop(), heap.pop())
+        {
+            parents[first] = next_node;
+            parents[second] = next_node;
+            heap.push(Reverse((first_weight + second_weight, next_node)));
+            next_node += 1;
+        }
+        let root = next_node - 1;
+
+        let mut lengths = [0u8; SYMBOL_COUNT];
+        for (symbol, length) in lengths.iter_mut().enumerate() {
+            let mut node = symbol;
+            while node != root {
+                node = parents[node];
+                *length += 1;
+            }
+        }
+
+        let mut sorted_symbols = (0..=u8::MAX).collect::<Vec<u8>>();
+        sorted_symbols.sort_by_key(|symbol| (lengths[*symbol as usize], *symbol));
+
+        let mut codes = [0u32; SYMBOL_COUNT];
+        let mut code = 0u32;
+        let mut previous_length = lengths[sorted_symbols[0] as usize];
+        for symbol in &sorted_symbols {
+            let length = lengths[*symbol as usize];
+            code <<= length - previous_length;
+            codes[*symbol as usize] = code;
+            code += 1;
+            previous_length = length;
+        }
+
+        HuffmanCode {
+            lengths,
+            codes,
+            sorted_symbols,
+        }
+    }
+
+    fn encode(&self, payload: &[u8]) -> Vec<u8> {
+        let mut bits: BitVec<Msb0, u8> = BitVec::new();
+        for symbol in payload {
+            let length = self.lengths[*symbol as usize] as usize;
+            let code = self.codes[*symbol as usize];
+            bits.extend_from_bitslice(&code.view_bits::<Msb0>()[32 - length..]);
+        }
+        let mut encoded = encode_varint(payload.len());
+        encoded.extend(bits.into_vec());
+        encoded
+    }
+
+    fn decode(&self, data: &[u8]) -> Result<Vec<u8>, CompressionError> {
+        let length_end = data
+            .iter()
+            .position(|byte| byte & 0b1000_0000 == 0)
+            .ok_or(CompressionError::Corrupted)?;
+        let length =
+            decode_varint(&data[..=length_end]).map_err(|_| CompressionError::Corrupted)?;
+        if length > MAX_DECOMPRESSED_LENGTH {
+            return Err(CompressionError::TooLarge);
+        }
+
+        let max_length = *self.lengths.iter().max().unwrap_or(&0) as usize;
+        let mut count_by_length = vec![0u32; max_length + 1];
+        for length in self.lengths.iter() {
+            count_by_length[*length as usize] += 1;
+        }
+
+        let mut bits = data[length_end + 1..].view_bits::<Msb0>().iter();
+        let mut decoded = Vec::with_capacity(length);
+        while decoded.len() < length {
+            // Walk the canonical code: `first_code` is the code of the first symbol of given length
+            let mut code = 0u32;
+            let mut first_code = 0u32;
+            let mut first_index = 0usize;
+            let mut symbol = None;
+            for count in count_by_length.iter().skip(1) {
+                let bit = bits.next().ok_or(CompressionError::Corrupted)?;
+                code = (code << 1) | *bit as u32;
+                first_code <<= 1;
+                if code < first_code + count {
+                    symbol = Some(self.sorted_symbols[first_index + (code - first_code) as usize]);
+                    break;
+                }
+                first_code += count;
+                first_index += *count as usize;
+            }
+            decoded.push(symbol.ok_or(CompressionError::Corrupted)?);
+        }
+        Ok(decoded)
+    }
+}
+
+/// Describes possible errors while decompressing the payload.
+#[derive(Debug, PartialEq, Snafu)]
+pub enum CompressionError {
+    /// Compressed data is not valid for the codec.
+    #[snafu(display("Compressed payload is corrupted"))]
+    Corrupted,
+    /// Decompressed data would exceed [`MAX_DECOMPRESSED_LENGTH`].
+    #[snafu(display(
+        "Decompressed payload would exceed the limit of '{}' bytes",
+        MAX_DECOMPRESSED_LENGTH
+    ))]
+    TooLarge,
+}
@@ -1,13 +1,156 @@
-use rand::RngCore;
+use std::error::Error;
 
+use bitvec::prelude::*;
+use bitvec::slice::Iter;
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::{RngCore, SeedableRng};
+use rand_chacha::ChaCha20Rng;
+use snafu::Snafu;
+
+use crate::ecc::EccLevel;
+use crate::encryption::derive_key;
+use crate::frame::{frame_payload, unframe_payload, FrameOptions, RevealError};
 use crate::method::MethodProgressStatus;
 use crate::observer::EventNotifier;
+use crate::public_key::{Identity, Recipient};
+
+/// Prefix of the salt used to derive the seed of the keyed random number generator.
+/// Keeps the seed different from the keys derived for other purposes with the same passphrase.
+pub const KEY_SALT_PREFIX: &[u8] = b"ptero method rng";
+
+/// Creates the cryptographically secure random number generator (ChaCha20) seeded from the shared key.
+///
+/// The seed is derived from the passphrase and the salt with Argon2id, so the same pair
+/// always gives the same sequence of numbers.
+///
+/// # Examples
+/// ```
+/// use rand::Rng;
+/// use ptero_common::config::keyed_rng;
+///
+/// let mut rng = keyed_rng("bamboo", "forest");
+/// let mut same_rng = keyed_rng("bamboo", "forest");
+///
+/// assert_eq!(rng.gen::<u64>(), same_rng.gen::<u64>());
+/// ```
+pub fn keyed_rng(passphrase: impl AsRef<[u8]>, salt: impl AsRef<[u8]>) -> ChaCha20Rng {
+    let salt = [KEY_SALT_PREFIX, salt.as_ref()].concat();
+    ChaCha20Rng::from_seed(derive_key(passphrase.as_ref(), &salt).into())
+}
+
+/// Salt prefix separating the permutation of the set from other uses of the same key.
+const KEYED_SET_SALT_PREFIX: &[u8] = b"character set";
+
+/// Character set with the characters of another set shuffled by the permutation derived from
+/// the shared key (see [`keyed_rng`]). The value concealed by a character cannot be read
+/// without the key, even when the underlying set is known.
+///
+/// The permutation depends on both the passphrase and the salt, so using a different salt
+/// for every message (e.g. its identifier) gives a different mapping per message.
+/// The same pair has to be used to reveal the data.
+///
+/// The character set traits of the methods are implemented for it, so it can be used
+/// in place of any of their sets.
+///
+/// # Examples
+/// ```
+/// use ptero_common::config::KeyedCharacterSet;
+///
+/// let characters = ['\u{2000}', '\u{2001}', '\u{2002}', '\u{2003}'];
+/// let set = KeyedCharacterSet::new(&characters, "bamboo", "forest");
+/// let same_set = KeyedCharacterSet::new(&characters, "bamboo", "forest");
+///
+/// assert_eq!(set.characters(), same_set.characters());
+/// assert_eq!(set.characters().len(), characters.len());
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct KeyedCharacterSet {
+    characters: Vec<char>,
+}
+
+impl KeyedCharacterSet {
+    pub fn new(
+        characters: &[char],
+        passphrase: impl AsRef<[u8]>,
+        salt: impl AsRef<[u8]>,
+    ) -> Self {
+        let salt = [KEYED_SET_SALT_PREFIX, salt.as_ref()].concat();
+        let mut characters = characters.to_vec();
+        characters.shuffle(&mut keyed_rng(passphrase, salt));
+        KeyedCharacterSet { characters }
+    }
+
+    /// Returns the shuffled characters.
+    pub fn characters(&self) -> &[char] {
+        &self.characters
+    }
+}
 
 impl CommonMethodConfigBuilder {
     pub fn with_rng<T>(mut self, rng: T) -> Self where T: RngCore + 'static {
         self.rng = Some(Box::new(rng));
         self
     }
+
+    /// Uses the random number generator derived from the shared key instead of the entropy,
+    /// see [`keyed_rng`]. Every random choice of the method is then tied to the key and salt,
+    /// so concealing the same data in the same cover text gives the same stego text.
+    pub fn with_key(self, passphrase: impl AsRef<[u8]>, salt: impl AsRef<[u8]>) -> Self {
+        self.with_rng(keyed_rng(passphrase, salt))
+    }
+
+    /// Enables or disables CRC32 checksum of the framed payload.
+    pub fn with_checksum(mut self, checksum: bool) -> Self {
+        self.frame_options
+            .get_or_insert_with(FrameOptions::default)
+            .checksum = checksum;
+        self
+    }
+
+    /// Encrypts the framed payload with the key derived from given passphrase.
+    /// The same passphrase is required to reveal the payload.
+    pub fn with_passphrase(mut self, passphrase: impl AsRef<[u8]>) -> Self {
+        self.frame_options
+            .get_or_insert_with(FrameOptions::default)
+            .passphrase = Some(passphrase.as_ref().to_vec());
+        self
+    }
+
+    /// Encrypts the framed payload to the recipient's public key, see [`crate::public_key`].
+    /// The recipient's identity is required to reveal the payload.
+    pub fn with_recipient(mut self, recipient: Recipient) -> Self {
+        self.frame_options
+            .get_or_insert_with(FrameOptions::default)
+            .recipient = Some(recipient);
+        self
+    }
+
+    /// Decrypts the framed payload encrypted to the public key of given identity.
+    pub fn with_identity(mut self, identity: Identity) -> Self {
+        self.frame_options
+            .get_or_insert_with(FrameOptions::default)
+            .identity = Some(identity);
+        self
+    }
+
+    /// Enables or disables compression of the framed payload.
+    /// The codec giving the smallest result is picked and recorded in the frame.
+    pub fn with_compression(mut self, compression: bool) -> Self {
+        self.frame_options
+            .get_or_insert_with(FrameOptions::default)
+            .compression = compression;
+        self
+    }
+
+    /// Protects the framed payload with the error correcting code of given level.
+    /// The same level is required to reveal the payload.
+    pub fn with_error_correction(mut self, level: EccLevel) -> Self {
+        self.frame_options
+            .get_or_insert_with(FrameOptions::default)
+            .error_correction = Some(level);
+        self
+    }
 }
 
 /// Common configuration for all steganographic methods.
@@ -19,9 +162,18 @@ pub struct CommonMethodConfig {
     #[builder(setter(into, prefix = "with"), default)]
     pub notifier: EventNotifier<MethodProgressStatus>,
     /// Random number generator used by methods.
-    /// By default populated with [`StdRng::from_entropy`].
+    /// Populated with [`StdRng::from_entropy`] by [`CommonMethodConfig::builder_with_entropy`].
     #[builder(private)]
-    pub rng: Box<dyn RngCore>
+    pub rng: Box<dyn RngCore>,
+    /// Whether the payload is framed with its length before concealing.
+    /// Framing lets revealing return exactly the hidden payload, without the trailing noise.
+    /// Enabled by default.
+    #[builder(setter(prefix = "with"), default = "true")]
+    pub framing: bool,
+    /// Describes what is put into the frame along with the payload.
+    /// Used only when the framing is enabled.
+    #[builder(private, default)]
+    pub frame_options: FrameOptions,
 }
 
 impl CommonMethodConfig {
@@ -70,4 +222,141 @@ impl CommonMethodConfig {
     pub fn builder() -> CommonMethodConfigBuilder {
         CommonMethodConfigBuilder::default()
     }
-}
\ No newline at end of file
+
+    /// Provides builder with the RNG already populated with [`StdRng::from_entropy`].
+    /// Meant for the methods which don't need any randomness or don't need it to be reproducible.
+    ///
+    /// # Examples
+    /// ```
+    /// use ptero_common::config::CommonMethodConfig;
+    ///
+    /// assert!(CommonMethodConfig::builder_with_entropy().build().is_ok());
+    /// assert!(CommonMethodConfig::builder().build().is_err());
+    /// ```
+    pub fn builder_with_entropy() -> CommonMethodConfigBuilder {
+        Self::builder().with_rng(StdRng::from_entropy())
+    }
+
+    /// Prepares the data for concealing. Unless the framing is disabled, wraps it into a frame -
+    /// see [`crate::frame`]. Data is padded with zeroes to the byte-size boundary.
+    pub fn wrap_payload<Order, Type>(&self, data: &mut Iter<Order, Type>) -> BitVec<Msb0, u8>
+    where
+        Order: BitOrder,
+        Type: BitStore,
+    {
+        let mut bits = data.collect::<BitVec<Msb0, u8>>();
+        if !self.framing {
+            return bits;
+        }
+        bits.resize(bits.len().div_ceil(8) * 8, false);
+        BitVec::from_vec(frame_payload(bits.as_raw_slice(), &self.frame_options))
+    }
+
+    /// Extracts the payload from the revealed data, dropping everything after the frame.
+    /// Returns revealed data untouched when the framing is disabled.
+    pub fn unwrap_payload<Order, Type>(
+        &self,
+        revealed_data: BitVec<Order, Type>,
+    ) -> Result<BitVec<Order, Type>, RevealError>
+    where
+        Order: BitOrder,
+        Type: BitStore,
+    {
+        if !self.framing {
+            return Ok(revealed_data);
+        }
+        let mut bits = revealed_data.iter().collect::<BitVec<Msb0, u8>>();
+        // Incomplete byte at the end can't be a part of the payload
+        bits.truncate(bits.len() / 8 * 8);
+        let payload = unframe_payload(bits.as_raw_slice(), &self.frame_options)?;
+        Ok(payload.view_bits::<Msb0>().iter().collect())
+    }
+}
+
+/// Gives the method builder wrapping [`CommonMethodConfigBuilder`] the options of the common configuration.
+///
+/// Implementing [`Self::map_config_builder`] is enough, the setters are forwarded by the default methods.
+pub trait ConfigurableBuilder: Sized {
+    /// Replaces the wrapped configuration builder with the one returned by `update`.
+    fn map_config_builder<F>(self, update: F) -> Self
+    where
+        F: FnOnce(CommonMethodConfigBuilder) -> CommonMethodConfigBuilder;
+
+    /// Enables or disables framing of the payload (enabled by default).
+    ///
+    /// With framing, the payload length is concealed along with the data, so revealing
+    /// returns exactly the concealed payload. Disable it to conceal a raw stream of bits.
+    fn with_framing(self, framing: bool) -> Self {
+        self.map_config_builder(|builder| builder.with_framing(framing))
+    }
+
+    /// Enables or disables CRC32 checksum of the payload (disabled by default).
+    ///
+    /// With checksum, revealing from corrupted stego text fails with
+    /// [`RevealError::ChecksumMismatch`] instead of returning garbage.
+    /// Has no effect when the framing is disabled.
+    fn with_checksum(self, checksum: bool) -> Self {
+        self.map_config_builder(|builder| builder.with_checksum(checksum))
+    }
+
+    /// Encrypts the payload with the key derived from given passphrase, see [`crate::encryption`].
+    ///
+    /// Revealing requires the same passphrase and fails with [`RevealError::Decryption`]
+    /// if it's wrong or the stego text has been tampered with.
+    /// Has no effect when the framing is disabled.
+    fn with_passphrase(self, passphrase: impl AsRef<[u8]>) -> Self {
+        self.map_config_builder(|builder| builder.with_passphrase(passphrase))
+    }
+
+    /// Encrypts the payload to the recipient's public key, see [`crate::public_key`].
+    ///
+    /// Revealing requires the recipient's identity (see [`Self::with_identity`]) and fails with
+    /// [`RevealError::Decryption`] if it's another one or the stego text has been tampered with.
+    /// Has no effect when the framing is disabled.
+    fn with_recipient(self, recipient: Recipient) -> Self {
+        self.map_config_builder(|builder| builder.with_recipient(recipient))
+    }
+
+    /// Decrypts the payload encrypted to the public key of given identity when revealing.
+    /// Has no effect when the framing is disabled.
+    fn with_identity(self, identity: Identity) -> Self {
+        self.map_config_builder(|builder| builder.with_identity(identity))
+    }
+
+    /// Enables or disables compression of the payload (disabled by default), see [`crate::compression`].
+    ///
+    /// Short English messages are compressed with a static Huffman code, anything else with Deflate.
+    /// The payload is stored as is if compression doesn't make it smaller.
+    /// Has no effect when the framing is disabled.
+    fn with_compression(self, compression: bool) -> Self {
+        self.map_config_builder(|builder| builder.with_compression(compression))
+    }
+
+    /// Protects the payload with Reed–Solomon code of given level (disabled by default), see [`crate::ecc`].
+    ///
+    /// Every 16 bytes of the payload get `2 * level` parity bytes, which let revealing fix up to
+    /// `level` corrupted bytes per block, e.g. caused by a few lines mangled by the social media.
+    /// Revealing requires the same level.
+    /// Has no effect when the framing is disabled.
+    fn with_error_correction(self, level: EccLevel) -> Self {
+        self.map_config_builder(|builder| builder.with_error_correction(level))
+    }
+}
+
+/// Error returned when the method cannot be built from its configuration.
+#[derive(Debug, Snafu)]
+#[snafu(display("Couldn't finish building {}: {}", method, source))]
+pub struct BuilderError {
+    method: &'static str,
+    source: Box<dyn Error>,
+}
+
+impl BuilderError {
+    /// Creates the error of the method with given name.
+    pub fn new(method: &'static str, source: impl Into<Box<dyn Error>>) -> Self {
+        BuilderError {
+            method,
+            source: source.into(),
+        }
+    }
+}
@@ -0,0 +1,346 @@
+//! Forward error correction of the concealed bytes.
+//!
+//! Social media sometimes trim or normalise single lines of the stego text, which flips the bits
+//! hidden in them. To survive that, the framed payload can be protected with Reed–Solomon code
+//! over GF(2^8). Bytes are split into blocks of [`BLOCK_DATA_LENGTH`] bytes (the last one is padded
+//! with zeroes) and every block gets `2 * level` parity bytes, so up to `level` corrupted bytes
+//! per block can be corrected.
+//!
+//! The level is not stored in the stego text - the same level has to be used when revealing.
+//!
+//! # Examples
+//!
+//! ```
+//! use ptero_common::ecc::{EccLevel, ReedSolomon, BLOCK_DATA_LENGTH};
+//!
+//! let level = EccLevel::new(2).unwrap();
+//! let codec = ReedSolomon::new(level);
+//! let mut encoded = codec.encode(b"Hello, panda!");
+//! assert_eq!(encoded.len(), BLOCK_DATA_LENGTH + 4);
+//!
+//! encoded[0] ^= 0xff;
+//! encoded[7] = 0;
+//! let decoded = codec.decode_block(&encoded).unwrap();
+//! assert_eq!(&decoded[..13], b"Hello, panda!");
+//! ```
+use snafu::Snafu;
+
+/// Amount of payload bytes protected by a single block.
+pub const BLOCK_DATA_LENGTH: usize = 16;
+/// The highest supported level of error correction.
+pub const MAX_ECC_LEVEL: u8 = 8;
+
+/// Primitive polynomial x^8 + x^4 + x^3 + x^2 + 1 generating GF(2^8).
+const PRIMITIVE_POLYNOMIAL: u16 = 0x11d;
+const FIELD_SIZE: usize = 256;
+
+/// Level of error correction - the number of corrupted bytes that can be corrected in every block.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct EccLevel(u8);
+
+impl EccLevel {
+    /// Creates the level, returns `None` if it's not within `1..=MAX_ECC_LEVEL`.
+    pub fn new(level: u8) -> Option<Self> {
+        if (1..=MAX_ECC_LEVEL).contains(&level) {
+            Some(EccLevel(level))
+        } else {
+            None
+        }
+    }
+
+    /// Returns the level as number.
+    pub fn value(&self) -> u8 {
+        self.0
+    }
+
+    /// Amount of parity bytes appended to every block.
+    pub fn parity_length(&self) -> usize {
+        2 * self.0 as usize
+    }
+
+    /// Length of the whole block - data and parity.
+    pub fn block_length(&self) -> usize {
+        BLOCK_DATA_LENGTH + self.parity_length()
+    }
+}
+
+/// Systematic Reed–Solomon codec with the parity length given by [`EccLevel`].
+#[derive(Debug)]
+pub struct ReedSolomon {
+    level: EccLevel,
+    exp: [u8; 2 * FIELD_SIZE],
+    log: [u8; FIELD_SIZE],
+    generator: Vec<u8>,
+}
+
+impl ReedSolomon {
+    pub fn new(level: EccLevel) -> Self {
+        let mut exp = [0u8; 2 * FIELD_SIZE];
+        let mut log = [0u8; FIELD_SIZE];
+        let mut value: u16 = 1;
+        for (power, exp_value) in exp.iter_mut().take(FIELD_SIZE - 1).enumerate() {
+            *exp_value = value as u8;
+            log[value as usize] = power as u8;
+            value <<= 1;
+            if value & 0x100 != 0 {
+                value ^= PRIMITIVE_POLYNOMIAL;
+            }
+        }
+        // Duplicated table lets multiplication skip the modulo
+        exp.copy_within(0..FIELD_SIZE - 1, FIELD_SIZE - 1);
+
+        let mut codec = ReedSolomon {
+            level,
+            exp,
+            log,
+            generator: vec![1],
+        };
+        for power in 0..level.parity_length() {
+            codec.generator = codec.poly_mul(&codec.generator, &[1, codec.pow(power)]);
+        }
+        codec
+    }
+
+    pub fn level(&self) -> EccLevel {
+        self.level
+    }
+
+    /// Splits the data into blocks, pads the last one with zeroes and appends parity to each of them.
+    pub fn encode(&self, data: &[u8]) -> Vec<u8> {
+        let mut encoded = Vec::new();
+        for chunk in data.chunks(BLOCK_DATA_LENGTH) {
+            let mut block = chunk.to_vec();
+            block.resize(BLOCK_DATA_LENGTH, 0);
+            encoded.extend(self.encode_block(&block));
+        }
+        encoded
+    }
+
+    /// Decodes all the complete blocks, ignoring anything that follows them.
+    pub fn decode(&self, data: &[u8]) -> Result<Vec<u8>, EccError> {
+        let mut decoded = Vec::new();
+        for block in data.chunks_exact(self.level.block_length()) {
+            decoded.extend(self.decode_block(block)?);
+        }
+        Ok(decoded)
+    }
+
+    /// Corrects the errors in a single block and returns its data part.
+    ///
+    /// Fails with [`EccError::InvalidBlockLength`] if the block is not [`EccLevel::block_length`] bytes long
+    /// or with [`EccError::TooManyErrors`] if the block is damaged beyond repair.
+    pub fn decode_block(&self, block: &[u8]) -> Result<Vec<u8>, EccError> {
+        let expected = self.level.block_length();
+        if block.len() != expected {
+            return Err(EccError::InvalidBlockLength {
+                length: block.len(),
+                expected,
+            });
+        }
+        let data_length = BLOCK_DATA_LENGTH;
+        let syndromes = self.syndromes(block);
+        if syndromes.iter().all(|syndrome| *syndrome == 0) {
+            return Ok(block[..data_length].to_vec());
+        }
+
+        let error_locator = self.error_locator(&syndromes)?;
+        let error_positions = self.error_positions(&error_locator, block.len())?;
+        let corrected = self.correct_errors(block, &syndromes, &error_positions)?;
+
+        if self
+            .syndromes(&corrected)
+            .iter()
+            .any(|syndrome| *syndrome != 0)
+        {
+            return Err(EccError::TooManyErrors);
+        }
+        Ok(corrected[..data_length].to_vec())
+    }
+
+    fn encode_block(&self, data: &[u8]) -> Vec<u8> {
+        let mut block = data.to_vec();
+        block.resize(data.len() + self.level.parity_length(), 0);
+        for index in 0..data.len() {
+            let coefficient = block[index];
+            if coefficient != 0 {
+                for (offset, generator_coefficient) in self.generator.iter().enumerate().skip(1) {
+                    block[index + offset] ^= self.mul(*generator_coefficient, coefficient);
+                }
+            }
+        }
+        block[..data.len()].copy_from_slice(data);
+        block
+    }
+
+    /// Syndromes of the block, prefixed with zero for easier indexing.
+    fn syndromes(&self, block: &[u8]) -> Vec<u8> {
+        let mut syndromes = vec![0];
+        for power in 0..self.level.parity_length() {
+            syndromes.push(self.poly_eval(block, self.pow(power)));
+        }
+        syndromes
+    }
+
+    /// Berlekamp–Massey algorithm.
+    fn error_locator(&self, syndromes: &[u8]) -> Result<Vec<u8>, EccError> {
+        let mut locator = vec![1];
+        let mut old_locator = vec![1];
+        for index in 1..syndromes.len() {
+            let mut delta = syndromes[index];
+            for offset in 1..locator.len() {
+                delta ^= self.mul(
+                    locator[locator.len() - 1 - offset],
+                    syndromes[index - offset],
+                );
+            }
+            old_locator.push(0);
+            if delta != 0 {
+                if old_locator.len() > locator.len() {
+                    let new_locator = self.poly_scale(&old_locator, delta);
+                    old_locator = self.poly_scale(&locator, self.inverse(delta));
+                    locator = new_locator;
+                }
+                locator = self.poly_add(&locator, &self.poly_scale(&old_locator, delta));
+            }
+        }
+
+        let leading_zeroes = locator
+            .iter()
+            .take_while(|coefficient| **coefficient == 0)
+            .count();
+        let locator = locator[leading_zeroes..].to_vec();
+        if locator.is_empty() || (locator.len() - 1) * 2 > self.level.parity_length() {
+            return Err(EccError::TooManyErrors);
+        }
+        Ok(locator)
+    }
+
+    /// Chien search, returns indices of the corrupted bytes.
+    fn error_positions(&self, locator: &[u8], length: usize) -> Result<Vec<usize>, EccError> {
+        let reversed_locator = locator.iter().rev().copied().collect::<Vec<u8>>();
+        let positions = (0..length)
+            .filter(|power| self.poly_eval(&reversed_locator, self.pow(*power)) == 0)
+            .map(|power| length - 1 - power)
+            .collect::<Vec<usize>>();
+        if positions.len() != locator.len() - 1 {
+            return Err(EccError::TooManyErrors);
+        }
+        Ok(positions)
+    }
+
+    /// Forney algorithm.
+    fn correct_errors(
+        &self,
+        block: &[u8],
+        syndromes: &[u8],
+        positions: &[usize],
+    ) -> Result<Vec<u8>, EccError> {
+        let powers = positions
+            .iter()
+            .map(|position| block.len() - 1 - position)
+            .collect::<Vec<usize>>();
+
+        let mut locator = vec![1];
+        for power in &powers {
+            locator = self.poly_mul(&locator, &[self.pow(*power), 1]);
+        }
+        let reversed_syndromes = syndromes.iter().rev().copied().collect::<Vec<u8>>();
+        let product = self.poly_mul(&reversed_syndromes, &locator);
+        let evaluator = &product[product.len() - locator.len()..];
+
+        let locations = powers
+            .iter()
+            .map(|power| self.pow(*power))
+            .collect::<Vec<u8>>();
+        let mut corrected = block.to_vec();
+        for (index, location) in locations.iter().enumerate() {
+            let location_inverse = self.inverse(*location);
+            let locator_derivative = locations
+                .iter()
+                .enumerate()
+                .filter(|(other_index, _)| *other_index != index)
+                .fold(1, |derivative, (_, other)| {
+                    self.mul(derivative, 1 ^ self.mul(location_inverse, *other))
+                });
+            if locator_derivative == 0 {
+                return Err(EccError::TooManyErrors);
+            }
+            let value = self.mul(*location, self.poly_eval(evaluator, location_inverse));
+            corrected[positions[index]] ^= self.div(value, locator_derivative);
+        }
+        Ok(corrected)
+    }
+
+    fn pow(&self, power: usize) -> u8 {
+        self.exp[power % (FIELD_SIZE - 1)]
+    }
+
+    fn mul(&self, left: u8, right: u8) -> u8 {
+        if left == 0 || right == 0 {
+            return 0;
+        }
+        self.exp[self.log[left as usize] as usize + self.log[right as usize] as usize]
+    }
+
+    fn div(&self, dividend: u8, divisor: u8) -> u8 {
+        if dividend == 0 {
+            return 0;
+        }
+        self.exp[self.log[dividend as usize] as usize + (FIELD_SIZE - 1)
+            - self.log[divisor as usize] as usize]
+    }
+
+    fn inverse(&self, value: u8) -> u8 {
+        self.exp[(FIELD_SIZE - 1) - self.log[value as usize] as usize]
+    }
+
+    // Polynomials are stored from the highest degree coefficient
+    fn poly_scale(&self, poly: &[u8], scale: u8) -> Vec<u8> {
+        poly.iter()
+            .map(|coefficient| self.mul(*coefficient, scale))
+            .collect()
+    }
+
+    fn poly_add(&self, left: &[u8], right: &[u8]) -> Vec<u8> {
+        let length = left.len().max(right.len());
+        let mut sum = vec![0; length];
+        for (index, coefficient) in left.iter().enumerate() {
+            sum[index + length - left.len()] = *coefficient;
+        }
+        for (index, coefficient) in right.iter().enumerate() {
+            sum[index + length - right.len()] ^= *coefficient;
+        }
+        sum
+    }
+
+    fn poly_mul(&self, left: &[u8], right: &[u8]) -> Vec<u8> {
+        let mut product = vec![0; left.len() + right.len() - 1];
+        for (right_index, right_coefficient) in right.iter().enumerate() {
+            for (left_index, left_coefficient) in left.iter().enumerate() {
+                product[left_index + right_index] ^=
+                    self.mul(*left_coefficient, *right_coefficient);
+            }
+        }
+        product
+    }
+
+    fn poly_eval(&self, poly: &[u8], x: u8) -> u8 {
+        poly.iter()
+            .fold(0, |value, coefficient| self.mul(value, x) ^ coefficient)
+    }
+}
+
+/// Describes possible errors while correcting the revealed data.
+#[derive(Debug, PartialEq, Snafu)]
+pub enum EccError {
+    /// Block has more corrupted bytes than the level of error correction allows to fix.
+    #[snafu(display("Stego text is damaged beyond repair, too many errors to correct"))]
+    TooManyErrors,
+    /// Block doesn't have the length given by the level of error correction.
+    #[snafu(display(
+        "Error correction block has '{}' bytes, expected '{}'",
+        length,
+        expected
+    ))]
+    InvalidBlockLength { length: usize, expected: usize },
+}
@@ -0,0 +1,197 @@
+//! Passphrase-based authenticated encryption of the payload.
+//!
+//! The key is derived from the passphrase with Argon2id (memory-hard KDF) and a random salt,
+//! then the payload is sealed with ChaCha20-Poly1305. The result is laid out as:
+//!
+//! | salt (16 bytes) | nonce (12 bytes) | ciphertext | authentication tag (16 bytes) |
+//!
+//! Salt and nonce are generated from the OS entropy source on every sealing, so concealing
+//! the same payload twice gives different ciphertexts.
+//!
+//! # Examples
+//!
+//! ```
+//! use ptero_common::encryption::{open, seal, EncryptionError, SEALING_OVERHEAD};
+//!
+//! let sealed = seal(b"Hi", b"panda");
+//! assert_eq!(sealed.len(), 2 + SEALING_OVERHEAD);
+//! assert_eq!(open(&sealed, b"panda"), Ok(b"Hi".to_vec()));
+//! assert_eq!(open(&sealed, b"koala"), Err(EncryptionError::AuthenticationFailed));
+//! ```
+//!
+//! The sealed payload doesn't tell where it ends, so it has to be stored on its own. When it's
+//! followed by other data, use [`seal_delimited`] instead - the length is sealed separately
+//! right after the nonce, so the whole output including the length looks random:
+//!
+//! | salt (16 bytes) | nonce (12 bytes) | sealed length (4 + 16 bytes) | ciphertext | authentication tag (16 bytes) |
+//!
+//! ```
+//! use ptero_common::encryption::{open_delimited, seal_delimited, DELIMITED_SEALING_OVERHEAD};
+//!
+//! let mut sealed = seal_delimited(b"Hi", b"panda");
+//! assert_eq!(sealed.len(), 2 + DELIMITED_SEALING_OVERHEAD);
+//!
+//! // Trailing data is ignored
+//! sealed.extend_from_slice(b"bamboo");
+//! assert_eq!(open_delimited(&sealed, b"panda"), Ok(b"Hi".to_vec()));
+//! ```
+use std::convert::TryFrom;
+
+use argon2::{Algorithm, Argon2, Params, Version};
+use chacha20poly1305::aead::{Aead, KeyInit};
+use chacha20poly1305::{ChaCha20Poly1305, Key, Nonce};
+use rand::rngs::OsRng;
+use rand::RngCore;
+use snafu::Snafu;
+
+/// Length of the random salt used by the key derivation.
+pub const SALT_LENGTH: usize = 16;
+/// Length of the random nonce used by the cipher.
+pub const NONCE_LENGTH: usize = 12;
+/// Length of the authentication tag appended to the ciphertext.
+pub const TAG_LENGTH: usize = 16;
+/// Amount of bytes the sealing adds to the payload.
+pub const SEALING_OVERHEAD: usize = SALT_LENGTH + NONCE_LENGTH + TAG_LENGTH;
+/// Length of the payload length sealed by [`seal_delimited`].
+pub const SEALED_LENGTH_LENGTH: usize = 4 + TAG_LENGTH;
+/// Amount of bytes the [`seal_delimited`] adds to the payload.
+pub const DELIMITED_SEALING_OVERHEAD: usize = SEALING_OVERHEAD + SEALED_LENGTH_LENGTH;
+
+const KEY_LENGTH: usize = 32;
+// Argon2id parameters recommended by OWASP - 19 MiB of memory, 2 iterations, 1 lane.
+// They are part of the format, changing them makes older stego texts unreadable.
+const KDF_MEMORY_COST: u32 = 19 * 1024;
+const KDF_TIME_COST: u32 = 2;
+const KDF_PARALLELISM: u32 = 1;
+// Flipped in the nonce of the payload, so it never repeats the nonce of the sealed length
+const PAYLOAD_NONCE_FLAG: u8 = 0b1000_0000;
+
+/// Encrypts and authenticates the payload with the key derived from `passphrase`.
+///
+/// Returns salt, nonce and ciphertext concatenated together.
+pub fn seal(payload: &[u8], passphrase: &[u8]) -> Vec<u8> {
+    let mut salt = [0; SALT_LENGTH];
+    let mut nonce = [0; NONCE_LENGTH];
+    OsRng.fill_bytes(&mut salt);
+    OsRng.fill_bytes(&mut nonce);
+
+    let cipher = ChaCha20Poly1305::new(&derive_key(passphrase, &salt));
+    let ciphertext = cipher
+        .encrypt(Nonce::from_slice(&nonce), payload)
+        .expect("Payload is too long to be encrypted");
+
+    let mut sealed = Vec::with_capacity(payload.len() + SEALING_OVERHEAD);
+    sealed.extend_from_slice(&salt);
+    sealed.extend_from_slice(&nonce);
+    sealed.extend(ciphertext);
+    sealed
+}
+
+/// Verifies and decrypts the payload sealed by [`seal`].
+///
+/// Fails with [`EncryptionError::AuthenticationFailed`] if the passphrase is wrong
+/// or the sealed data has been tampered with.
+pub fn open(sealed: &[u8], passphrase: &[u8]) -> Result<Vec<u8>, EncryptionError> {
+    if sealed.len() < SEALING_OVERHEAD {
+        return Err(EncryptionError::TooShort {
+            length: sealed.len(),
+        });
+    }
+    let (salt, rest) = sealed.split_at(SALT_LENGTH);
+    let (nonce, ciphertext) = rest.split_at(NONCE_LENGTH);
+
+    let cipher = ChaCha20Poly1305::new(&derive_key(passphrase, salt));
+    cipher
+        .decrypt(Nonce::from_slice(nonce), ciphertext)
+        .map_err(|_| EncryptionError::AuthenticationFailed)
+}
+
+/// Encrypts and authenticates the payload like [`seal`], together with its length.
+///
+/// Returns salt, nonce, sealed length and ciphertext concatenated together.
+pub fn seal_delimited(payload: &[u8], passphrase: &[u8]) -> Vec<u8> {
+    let mut salt = [0; SALT_LENGTH];
+    let mut nonce = [0; NONCE_LENGTH];
+    OsRng.fill_bytes(&mut salt);
+    OsRng.fill_bytes(&mut nonce);
+
+    let cipher = ChaCha20Poly1305::new(&derive_key(passphrase, &salt));
+    let length = u32::try_from(payload.len()).expect("Payload is too long to be encrypted");
+    let sealed_length = cipher
+        .encrypt(Nonce::from_slice(&nonce), &length.to_be_bytes()[..])
+        .expect("Length is short enough to be encrypted");
+    let ciphertext = cipher
+        .encrypt(&payload_nonce(&nonce), payload)
+        .expect("Payload is too long to be encrypted");
+
+    let mut sealed = Vec::with_capacity(payload.len() + DELIMITED_SEALING_OVERHEAD);
+    sealed.extend_from_slice(&salt);
+    sealed.extend_from_slice(&nonce);
+    sealed.extend(sealed_length);
+    sealed.extend(ciphertext);
+    sealed
+}
+
+/// Verifies and decrypts the payload sealed by [`seal_delimited`], ignoring anything that follows it.
+///
+/// Fails with [`EncryptionError::AuthenticationFailed`] if the passphrase is wrong
+/// or the sealed data has been tampered with.
+pub fn open_delimited(sealed: &[u8], passphrase: &[u8]) -> Result<Vec<u8>, EncryptionError> {
+    if sealed.len() < DELIMITED_SEALING_OVERHEAD {
+        return Err(EncryptionError::TooShort {
+            length: sealed.len(),
+        });
+    }
+    let (salt, rest) = sealed.split_at(SALT_LENGTH);
+    let (nonce, rest) = rest.split_at(NONCE_LENGTH);
+    let (sealed_length, rest) = rest.split_at(SEALED_LENGTH_LENGTH);
+
+    let cipher = ChaCha20Poly1305::new(&derive_key(passphrase, salt));
+    let length = cipher
+        .decrypt(Nonce::from_slice(nonce), sealed_length)
+        .map_err(|_| EncryptionError::AuthenticationFailed)?;
+    let length = u32::from_be_bytes([length[0], length[1], length[2], length[3]]) as usize;
+    let ciphertext = rest
+        .get(..length + TAG_LENGTH)
+        .ok_or(EncryptionError::TooShort {
+            length: sealed.len(),
+        })?;
+    cipher
+        .decrypt(&payload_nonce(nonce), ciphertext)
+        .map_err(|_| EncryptionError::AuthenticationFailed)
+}
+
+fn payload_nonce(nonce: &[u8]) -> Nonce {
+    let mut payload_nonce = *Nonce::from_slice(nonce);
+    payload_nonce[0] ^= PAYLOAD_NONCE_FLAG;
+    payload_nonce
+}
+
+pub(crate) fn derive_key(passphrase: &[u8], salt: &[u8]) -> Key {
+    let params = Params::new(
+        KDF_MEMORY_COST,
+        KDF_TIME_COST,
+        KDF_PARALLELISM,
+        Some(KEY_LENGTH),
+    )
+    .expect("Argon2 parameters are valid");
+    let mut key = Key::default();
+    Argon2::new(Algorithm::Argon2id, Version::V0x13, params)
+        .hash_password_into(passphrase, salt, &mut key)
+        .expect("Salt and key lengths are valid for Argon2");
+    key
+}
+
+/// Describes possible errors while opening the sealed payload.
+#[derive(Debug, PartialEq, Snafu)]
+pub enum EncryptionError {
+    /// Sealed data cannot even hold the salt, nonce and authentication tag.
+    #[snafu(display(
+        "Encrypted payload is too short, got only '{}' bytes",
+        length
+    ))]
+    TooShort { length: usize },
+    /// Authentication tag doesn't match - wrong passphrase or the ciphertext has been modified.
+    #[snafu(display("Couldn't decrypt the payload, the passphrase is wrong or the data is corrupted"))]
+    AuthenticationFailed,
+}
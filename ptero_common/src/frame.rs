@@ -0,0 +1,522 @@
+//! Framing of the hidden payload.
+//!
+//! Before concealing, the payload is wrapped into a frame:
+//!
+//! | flags (1 byte) | payload length (varint) | payload | CRC32 (4 bytes, optional) |
+//!
+//! The length is encoded as an unsigned LEB128 varint. When revealing, it tells exactly where
+//! the payload ends, so the trailing noise produced by the unused part of the cover text can be dropped.
+//! The flags describe what else has been put into the frame, e.g. [`CHECKSUM_FLAG`] marks that
+//! the frame ends with the CRC32 of everything before it and [`ENCRYPTION_FLAG`] marks
+//! that the payload has been sealed with a passphrase (see [`crate::encryption`]).
+//! [`PUBLIC_KEY_FLAG`] marks the payload sealed to the recipient's public key
+//! (see [`crate::public_key`]), the ephemeral public key is stored in front of the sealed payload.
+//! Two bits covered by [`COMPRESSION_FLAGS`] store the codec used to compress the payload
+//! (see [`crate::compression`]). The payload is compressed first, then encrypted.
+//!
+//! Optionally, the whole frame is protected with the error correcting code (see [`crate::ecc`]).
+//!
+//! # Examples
+//!
+//! Frame the payload and read it back bit by bit:
+//! ```
+//! use bitvec::prelude::*;
+//! use ptero_common::frame::{frame_payload, FrameOptions, FrameReader};
+//!
+//! let framed = frame_payload(b"Hi", &FrameOptions::default());
+//! assert_eq!(&framed, &[0, 2, b'H', b'i']);
+//!
+//! let mut reader = FrameReader::new();
+//! // Noise after the frame is ignored
+//! let mut stream = framed.clone();
+//! stream.extend_from_slice(&[0, 0]);
+//! for bit in stream.view_bits::<Msb0>().iter() {
+//!     if reader.is_complete() {
+//!         break;
+//!     }
+//!     reader.push_bit(*bit).unwrap();
+//! }
+//! assert_eq!(reader.into_payload().unwrap(), b"Hi");
+//! ```
+//!
+//! Detect corrupted payload with checksum:
+//! ```
+//! use ptero_common::frame::{frame_payload, unframe_payload, FrameOptions, RevealError};
+//!
+//! let options = FrameOptions {
+//!     checksum: true,
+//!     ..FrameOptions::default()
+//! };
+//! let mut framed = frame_payload(b"Hi", &options);
+//! assert_eq!(unframe_payload(&framed, &options), Ok(b"Hi".to_vec()));
+//!
+//! framed[2] = b'h';
+//! assert!(matches!(
+//!     unframe_payload(&framed, &options),
+//!     Err(RevealError::ChecksumMismatch { .. })
+//! ));
+//! ```
+//!
+//! Encrypt the payload with a passphrase:
+//! ```
+//! use ptero_common::frame::{frame_payload, unframe_payload, FrameOptions, RevealError};
+//!
+//! let options = FrameOptions {
+//!     passphrase: Some(b"panda".to_vec()),
+//!     ..FrameOptions::default()
+//! };
+//! let framed = frame_payload(b"Hi", &options);
+//! assert_eq!(unframe_payload(&framed, &options), Ok(b"Hi".to_vec()));
+//! assert_eq!(
+//!     unframe_payload(&framed, &FrameOptions::default()),
+//!     Err(RevealError::MissingPassphrase)
+//! );
+//! ```
+//!
+//! Encrypt the payload to the recipient's public key:
+//! ```
+//! use ptero_common::frame::{frame_payload, unframe_payload, FrameOptions};
+//! use ptero_common::public_key::Identity;
+//!
+//! let identity = Identity::generate();
+//! let framed = frame_payload(
+//!     b"Hi",
+//!     &FrameOptions {
+//!         recipient: Some(identity.recipient()),
+//!         ..FrameOptions::default()
+//!     },
+//! );
+//! let options = FrameOptions {
+//!     identity: Some(identity),
+//!     ..FrameOptions::default()
+//! };
+//! assert_eq!(unframe_payload(&framed, &options), Ok(b"Hi".to_vec()));
+//! ```
+use std::fmt;
+
+use bitvec::prelude::*;
+use crc32fast::Hasher;
+use snafu::{ResultExt, Snafu};
+
+use crate::compression::{compress, decompress, Codec, CompressionError};
+use crate::ecc::{EccError, EccLevel, ReedSolomon};
+use crate::encryption::{open, seal, EncryptionError};
+use crate::public_key::{open_with, seal_to, Identity, Recipient};
+
+const VARINT_CONTINUATION_FLAG: u8 = 0b1000_0000;
+const VARINT_VALUE_MASK: u8 = 0b0111_1111;
+const VARINT_MAX_BYTES: usize = 10;
+const CHECKSUM_LENGTH: usize = 4;
+
+/// Flag marking that the frame ends with CRC32 checksum.
+pub const CHECKSUM_FLAG: u8 = 0b0000_0001;
+/// Flag marking that the payload is encrypted.
+pub const ENCRYPTION_FLAG: u8 = 0b0000_0010;
+/// Mask of the flags storing the compression codec.
+pub const COMPRESSION_FLAGS: u8 = 0b0000_1100;
+/// Compression flags value marking the payload compressed with [`Codec::Deflate`].
+pub const DEFLATE_FLAGS: u8 = 0b0000_0100;
+/// Compression flags value marking the payload compressed with [`Codec::EnglishText`].
+pub const ENGLISH_TEXT_FLAGS: u8 = 0b0000_1000;
+/// Flag marking that the payload is encrypted to the recipient's public key.
+pub const PUBLIC_KEY_FLAG: u8 = 0b0001_0000;
+/// All the flags recognized by this version of the frame.
+const KNOWN_FLAGS: u8 = CHECKSUM_FLAG | ENCRYPTION_FLAG | COMPRESSION_FLAGS | PUBLIC_KEY_FLAG;
+
+/// Describes what should be put into the frame along with the payload.
+///
+/// When reading the frame only the passphrase, the identity and the error correction level
+/// are taken into account, everything else is described by the frame flags.
+#[derive(Clone, Default, PartialEq)]
+pub struct FrameOptions {
+    /// Append CRC32 checksum, so the corrupted payload can be detected when revealing.
+    pub checksum: bool,
+    /// Encrypt the payload with the key derived from this passphrase.
+    pub passphrase: Option<Vec<u8>>,
+    /// Compress the payload with the codec giving the smallest result.
+    pub compression: bool,
+    /// Protect the frame with the error correcting code of given level.
+    pub error_correction: Option<EccLevel>,
+    /// Encrypt the payload to this public key, after the passphrase if both are given.
+    pub recipient: Option<Recipient>,
+    /// Decrypt the payload encrypted to the public key of this identity.
+    pub identity: Option<Identity>,
+}
+
+impl FrameOptions {
+    fn flags(&self, codec: Codec) -> u8 {
+        let mut flags = codec_to_flags(codec);
+        if self.checksum {
+            flags |= CHECKSUM_FLAG;
+        }
+        if self.passphrase.is_some() {
+            flags |= ENCRYPTION_FLAG;
+        }
+        if self.recipient.is_some() {
+            flags |= PUBLIC_KEY_FLAG;
+        }
+        flags
+    }
+}
+
+impl fmt::Debug for FrameOptions {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("FrameOptions")
+            .field("checksum", &self.checksum)
+            .field("passphrase", &self.passphrase.as_ref().map(|_| "<redacted>"))
+            .field("compression", &self.compression)
+            .field("error_correction", &self.error_correction)
+            .field("recipient", &self.recipient)
+            .field("identity", &self.identity)
+            .finish()
+    }
+}
+
+fn codec_to_flags(codec: Codec) -> u8 {
+    match codec {
+        Codec::Stored => 0,
+        Codec::Deflate => DEFLATE_FLAGS,
+        Codec::EnglishText => ENGLISH_TEXT_FLAGS,
+    }
+}
+
+fn codec_from_flags(flags: u8) -> Option<Codec> {
+    match flags & COMPRESSION_FLAGS {
+        0 => Some(Codec::Stored),
+        DEFLATE_FLAGS => Some(Codec::Deflate),
+        ENGLISH_TEXT_FLAGS => Some(Codec::EnglishText),
+        _ => None,
+    }
+}
+
+/// Encodes `value` as an unsigned LEB128 varint.
+///
+/// # Examples
+///
+/// ```
+/// use ptero_common::frame::encode_varint;
+///
+/// assert_eq!(encode_varint(0), vec![0]);
+/// assert_eq!(encode_varint(127), vec![127]);
+/// assert_eq!(encode_varint(300), vec![0b1010_1100, 0b0000_0010]);
+/// ```
+pub fn encode_varint(mut value: usize) -> Vec<u8> {
+    let mut bytes = Vec::with_capacity(VARINT_MAX_BYTES);
+    loop {
+        let byte = value as u8 & VARINT_VALUE_MASK;
+        value >>= 7;
+        if value == 0 {
+            bytes.push(byte);
+            return bytes;
+        }
+        bytes.push(byte | VARINT_CONTINUATION_FLAG);
+    }
+}
+
+/// Wraps the payload into a frame that can be concealed.
+/// If requested, the payload is compressed and then encrypted (with the passphrase first,
+/// then to the recipient) before framing
+/// and the frame is protected with the error correcting code.
+pub fn frame_payload(payload: &[u8], options: &FrameOptions) -> Vec<u8> {
+    let (codec, mut payload) = if options.compression {
+        compress(payload)
+    } else {
+        (Codec::Stored, payload.to_vec())
+    };
+    if let Some(passphrase) = &options.passphrase {
+        payload = seal(&payload, passphrase);
+    }
+    if let Some(recipient) = &options.recipient {
+        payload = seal_to(&payload, recipient);
+    }
+
+    let flags = options.flags(codec);
+    let mut frame = vec![flags];
+    frame.extend(encode_varint(payload.len()));
+    frame.extend_from_slice(&payload);
+
+    if flags & CHECKSUM_FLAG != 0 {
+        let checksum = crc32(&frame);
+        frame.extend_from_slice(&checksum.to_be_bytes());
+    }
+
+    match options.error_correction {
+        Some(level) => ReedSolomon::new(level).encode(&frame),
+        None => frame,
+    }
+}
+
+/// Reads the frame from the revealed bytes, skipping anything that follows it.
+///
+/// # Examples
+///
+/// ```
+/// use ptero_common::frame::{unframe_payload, FrameOptions, RevealError};
+///
+/// let options = FrameOptions::default();
+/// assert_eq!(unframe_payload(&[0, 1, 42, 0, 0], &options), Ok(vec![42]));
+/// assert_eq!(
+///     unframe_payload(&[0, 3, 42], &options),
+///     Err(RevealError::TruncatedPayload { expected: 3, available: 1 })
+/// );
+/// ```
+pub fn unframe_payload(bytes: &[u8], options: &FrameOptions) -> Result<Vec<u8>, RevealError> {
+    let mut reader = FrameReader::with_options(options.clone());
+    for bit in bytes.view_bits::<Msb0>().iter() {
+        if reader.is_complete() {
+            break;
+        }
+        reader.push_bit(*bit)?;
+    }
+    reader.into_payload()
+}
+
+/// Incremental frame parser fed with revealed bits (most significant bit first).
+///
+/// It lets the revealing process stop as soon as the whole frame has been read.
+#[derive(Debug, Default)]
+pub struct FrameReader {
+    options: FrameOptions,
+    error_correction: Option<ReedSolomon>,
+    block: Vec<u8>,
+    flags: Option<u8>,
+    header: Vec<u8>,
+    payload: Vec<u8>,
+    payload_length: Option<usize>,
+    checksum: Vec<u8>,
+    current_byte: u8,
+    current_bit_count: u8,
+}
+
+impl FrameReader {
+    pub fn new() -> Self {
+        FrameReader::default()
+    }
+
+    /// Creates the reader which uses the passphrase from `options` to decrypt the payload
+    /// and the error correction level to repair the frame.
+    pub fn with_options(options: FrameOptions) -> Self {
+        FrameReader {
+            error_correction: options.error_correction.map(ReedSolomon::new),
+            options,
+            ..FrameReader::default()
+        }
+    }
+
+    /// Consumes the next revealed bit.
+    ///
+    /// Fails with [`RevealError::UnknownFlags`] or [`RevealError::MalformedLength`] if the
+    /// header cannot be a valid one and with [`RevealError::ErrorCorrection`] if the frame
+    /// is damaged beyond repair.
+    pub fn push_bit(&mut self, bit: bool) -> Result<(), RevealError> {
+        self.current_byte = (self.current_byte << 1) | bit as u8;
+        self.current_bit_count += 1;
+
+        if self.current_bit_count == 8 {
+            let byte = self.current_byte;
+            self.current_byte = 0;
+            self.current_bit_count = 0;
+            self.push_encoded_byte(byte)?;
+        }
+        Ok(())
+    }
+
+    /// Returns `true` when the whole frame has been read.
+    pub fn is_complete(&self) -> bool {
+        self.is_payload_complete() && self.checksum.len() == self.checksum_length()
+    }
+
+    /// Returns the payload length declared by the frame, once it has been read.
+    /// For the encrypted payload it's the length of the sealed one.
+    pub fn payload_length(&self) -> Option<usize> {
+        self.payload_length
+    }
+
+    /// Finishes reading and returns the payload.
+    ///
+    /// Fails with [`RevealError::TruncatedPayload`] if the frame was not read completely,
+    /// with [`RevealError::ChecksumMismatch`] if the payload is corrupted,
+    /// with [`RevealError::Decryption`] if the encrypted payload cannot be opened
+    /// (see also [`RevealError::MissingIdentity`] and [`RevealError::MissingPassphrase`]) or
+    /// with [`RevealError::Decompression`] if the compressed payload cannot be restored.
+    pub fn into_payload(self) -> Result<Vec<u8>, RevealError> {
+        let expected = self.payload_length.ok_or(RevealError::MissingHeader)?;
+        if !self.is_payload_complete() {
+            return Err(RevealError::TruncatedPayload {
+                expected,
+                available: self.payload.len(),
+            });
+        }
+        if !self.is_complete() {
+            return Err(RevealError::MissingChecksum);
+        }
+
+        if self.checksum_length() > 0 {
+            let mut checksum_bytes = [0; CHECKSUM_LENGTH];
+            checksum_bytes.copy_from_slice(&self.checksum);
+            let expected = u32::from_be_bytes(checksum_bytes);
+
+            let actual = crc32(&[self.header.as_slice(), &self.payload].concat());
+
+            if expected != actual {
+                return Err(RevealError::ChecksumMismatch { expected, actual });
+            }
+        }
+
+        let is_encrypted = self.has_flag(ENCRYPTION_FLAG);
+        let payload = match (self.has_flag(PUBLIC_KEY_FLAG), &self.options.identity) {
+            (true, Some(identity)) => open_with(&self.payload, identity).context(Decryption)?,
+            (true, None) => return Err(RevealError::MissingIdentity),
+            (false, Some(_)) => return Err(RevealError::NotEncryptedToIdentity),
+            (false, None) => self.payload,
+        };
+        let payload = match (is_encrypted, &self.options.passphrase) {
+            (true, Some(passphrase)) => open(&payload, passphrase).context(Decryption)?,
+            (true, None) => return Err(RevealError::MissingPassphrase),
+            (false, Some(_)) => return Err(RevealError::NotEncrypted),
+            (false, None) => payload,
+        };
+
+        // Flags have been validated when read
+        let codec = self.flags.and_then(codec_from_flags).unwrap_or(Codec::Stored);
+        decompress(codec, &payload).context(Decompression)
+    }
+
+    fn has_flag(&self, flag: u8) -> bool {
+        matches!(self.flags, Some(flags) if flags & flag != 0)
+    }
+
+    fn is_payload_complete(&self) -> bool {
+        matches!(self.payload_length, Some(length) if length == self.payload.len())
+    }
+
+    fn checksum_length(&self) -> usize {
+        if self.has_flag(CHECKSUM_FLAG) {
+            CHECKSUM_LENGTH
+        } else {
+            0
+        }
+    }
+
+    fn push_encoded_byte(&mut self, byte: u8) -> Result<(), RevealError> {
+        let codec = match &self.error_correction {
+            Some(codec) => codec,
+            None => return self.push_byte(byte),
+        };
+        self.block.push(byte);
+        if self.block.len() < codec.level().block_length() {
+            return Ok(());
+        }
+
+        let decoded = codec.decode_block(&self.block).context(ErrorCorrection)?;
+        self.block.clear();
+        for byte in decoded {
+            if self.is_complete() {
+                break;
+            }
+            self.push_byte(byte)?;
+        }
+        Ok(())
+    }
+
+    fn push_byte(&mut self, byte: u8) -> Result<(), RevealError> {
+        if self.flags.is_none() {
+            if byte & !KNOWN_FLAGS != 0 || codec_from_flags(byte).is_none() {
+                return Err(RevealError::UnknownFlags { flags: byte });
+            }
+            self.flags = Some(byte);
+            self.header.push(byte);
+            return Ok(());
+        }
+
+        if self.payload_length.is_none() {
+            self.header.push(byte);
+            if byte & VARINT_CONTINUATION_FLAG == 0 {
+                self.payload_length = Some(decode_varint(&self.header[1..])?);
+            } else if self.header.len() > VARINT_MAX_BYTES {
+                return Err(RevealError::MalformedLength);
+            }
+            return Ok(());
+        }
+
+        if !self.is_payload_complete() {
+            self.payload.push(byte);
+        } else if self.checksum.len() < self.checksum_length() {
+            self.checksum.push(byte);
+        }
+        Ok(())
+    }
+}
+
+fn crc32(data: &[u8]) -> u32 {
+    let mut hasher = Hasher::new();
+    hasher.update(data);
+    hasher.finalize()
+}
+
+pub(crate) fn decode_varint(bytes: &[u8]) -> Result<usize, RevealError> {
+    let mut value: usize = 0;
+    for (index, byte) in bytes.iter().enumerate() {
+        let chunk = (byte & VARINT_VALUE_MASK) as usize;
+        let shift = 7 * index as u32;
+        let shifted = chunk
+            .checked_shl(shift)
+            .filter(|shifted| shifted >> shift == chunk)
+            .ok_or(RevealError::MalformedLength)?;
+        value |= shifted;
+    }
+    Ok(value)
+}
+
+/// Describes possible errors while reading the payload from revealed data.
+#[derive(Debug, PartialEq, Snafu)]
+pub enum RevealError {
+    /// Revealed data ended before the frame header was read.
+    #[snafu(display("Revealed data is too short to contain the payload length"))]
+    MissingHeader,
+    /// Frame has flags which are not supported - most likely the data is not a frame at all.
+    #[snafu(display("Revealed frame has unknown flags set: {:#010b}", flags))]
+    UnknownFlags { flags: u8 },
+    /// Length header is not a valid varint or doesn't fit into `usize`.
+    #[snafu(display("Revealed payload length is malformed"))]
+    MalformedLength,
+    /// Revealed data ended before the whole frame was read.
+    #[snafu(display(
+        "Revealed payload is truncated, expected '{}' bytes but got only '{}'",
+        expected,
+        available
+    ))]
+    TruncatedPayload { expected: usize, available: usize },
+    /// Revealed data ended before the checksum was read.
+    #[snafu(display("Revealed data is too short to contain the payload checksum"))]
+    MissingChecksum,
+    /// Checksum of the revealed payload doesn't match the concealed one.
+    /// The stego text has been corrupted (e.g. by the channel) or was concealed with different parameters.
+    #[snafu(display(
+        "Corrupted stego text, checksum {:#010x} doesn't match the expected {:#010x}",
+        actual,
+        expected
+    ))]
+    ChecksumMismatch { expected: u32, actual: u32 },
+    /// Payload is encrypted, but no passphrase has been given.
+    #[snafu(display("Revealed payload is encrypted, passphrase is required"))]
+    MissingPassphrase,
+    /// Passphrase has been given, but the payload is not encrypted - it may have been forged.
+    #[snafu(display("Revealed payload is not encrypted, but the passphrase has been given"))]
+    NotEncrypted,
+    /// Payload is encrypted to the recipient's public key, but no identity has been given.
+    #[snafu(display("Revealed payload is encrypted to the public key, identity is required"))]
+    MissingIdentity,
+    /// Identity has been given, but the payload is not encrypted to any public key - it may have been forged.
+    #[snafu(display("Revealed payload is not encrypted to the public key, but the identity has been given"))]
+    NotEncryptedToIdentity,
+    /// Encrypted payload cannot be verified or decrypted.
+    #[snafu(display("{}", source))]
+    Decryption { source: EncryptionError },
+    /// Compressed payload cannot be restored.
+    #[snafu(display("{}", source))]
+    Decompression { source: CompressionError },
+    /// Frame has more errors than the error correcting code is able to fix.
+    #[snafu(display("{}", source))]
+    ErrorCorrection { source: EccError },
+}
@@ -2,5 +2,13 @@
 extern crate derive_builder;
 
 pub mod method;
+pub mod compression;
 pub mod config;
-pub mod observer;
\ No newline at end of file
+pub mod ecc;
+pub mod encryption;
+pub mod frame;
+pub mod multipart;
+pub mod observer;
+pub mod public_key;
+pub mod rotation;
+pub mod selection;
\ No newline at end of file
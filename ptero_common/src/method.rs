@@ -1,5 +1,8 @@
 use bitvec::prelude::*;
 use bitvec::slice::Iter;
+use snafu::Snafu;
+
+use crate::frame::RevealError;
 
 /// Possible results of data encoding
 #[derive(Debug, Clone)]
@@ -34,4 +37,21 @@ pub trait SteganographyMethod<Cover, Err> {
         where
             Order: BitOrder,
             Type: BitStore;
-}
\ No newline at end of file
+}
+
+/// Describes the errors shared by the methods while concealing or revealing data.
+/// Methods with their own failure modes wrap it in their error type.
+#[derive(Debug, PartialEq, Snafu)]
+#[snafu(visibility(pub))]
+pub enum MethodError {
+    /// Used cover text doesn't have enough places used by the method to conceal all the data.
+    #[snafu(display(
+        "Exceeded cover text capacity. Remaining data size: {} bits",
+        remaining_data_size
+    ))]
+    CoverTextTooSmall { remaining_data_size: usize },
+    /// Revealed data doesn't contain a valid payload frame.
+    /// Usually means that the stego text was concealed with different parameters or got mangled.
+    #[snafu(display("Couldn't read the payload from revealed data: {}", source))]
+    InvalidPayload { source: RevealError },
+}
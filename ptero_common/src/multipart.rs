@@ -0,0 +1,307 @@
+//! Splitting of the payload across multiple cover texts.
+//!
+//! A single post rarely has enough lines for a bigger payload, so it can be cut into parts,
+//! each concealed in a separate cover text. Every part is laid out as:
+//!
+//! | message ID (4 bytes) | part index (varint) | total count (varint) | chunk of the payload |
+//!
+//! and then framed like any other payload (see [`crate::frame`]). The message ID lets the
+//! revealing side tell the parts of different messages apart, the index and total count let it
+//! reassemble the parts given in any order and report the missing ones.
+//!
+//! # Examples
+//!
+//! ```
+//! use ptero_common::multipart::{reassemble, split_payload, MultipartError, Part};
+//!
+//! let parts = split_payload(b"Hello, panda!", 7, &[5, 5, 5]);
+//! assert_eq!(parts.len(), 3);
+//!
+//! let mut bytes = parts.iter().map(Part::to_bytes).collect::<Vec<Vec<u8>>>();
+//! bytes.reverse();
+//! let revealed = bytes
+//!     .iter()
+//!     .map(|part| Part::from_bytes(part))
+//!     .collect::<Result<Vec<Part>, MultipartError>>()
+//!     .unwrap();
+//! assert_eq!(reassemble(revealed.clone()), Ok(b"Hello, panda!".to_vec()));
+//!
+//! assert_eq!(
+//!     reassemble(revealed[1..].to_vec()),
+//!     Err(MultipartError::MissingParts { message_id: 7, missing: vec![3], total: 3 })
+//! );
+//! ```
+use std::collections::BTreeMap;
+
+use snafu::Snafu;
+
+use crate::frame::{decode_varint, encode_varint};
+
+const MESSAGE_ID_LENGTH: usize = 4;
+const VARINT_CONTINUATION_FLAG: u8 = 0b1000_0000;
+
+/// Single part of the split payload.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Part {
+    /// Identifies the message the part belongs to.
+    pub message_id: u32,
+    /// Index of the part, counted from 0.
+    pub index: usize,
+    /// Amount of parts the message has been split into.
+    pub total: usize,
+    /// Chunk of the payload.
+    pub data: Vec<u8>,
+}
+
+/// Outcome of a single attempt to conceal a part, see [`conceal_in_parts`].
+#[derive(Debug)]
+pub enum Attempt<Output> {
+    /// The part fits into the cover.
+    Concealed(Output),
+    /// The cover is too small, `remaining_bits` of the concealed data didn't fit.
+    TooSmall { remaining_bits: usize },
+}
+
+impl Part {
+    /// Serializes the part, see the module docs for the layout.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = self.message_id.to_be_bytes().to_vec();
+        bytes.extend(encode_varint(self.index));
+        bytes.extend(encode_varint(self.total));
+        bytes.extend_from_slice(&self.data);
+        bytes
+    }
+
+    /// Restores the part from bytes produced by [`Part::to_bytes`].
+    ///
+    /// Fails with [`MultipartError::MalformedPart`] if the bytes can't be a part.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, MultipartError> {
+        if bytes.len() < MESSAGE_ID_LENGTH {
+            return Err(MultipartError::MalformedPart);
+        }
+        let (id_bytes, rest) = bytes.split_at(MESSAGE_ID_LENGTH);
+        let mut message_id = [0; MESSAGE_ID_LENGTH];
+        message_id.copy_from_slice(id_bytes);
+
+        let (index, rest) = read_varint(rest)?;
+        let (total, data) = read_varint(rest)?;
+        if index >= total {
+            return Err(MultipartError::MalformedPart);
+        }
+        Ok(Part {
+            message_id: u32::from_be_bytes(message_id),
+            index,
+            total,
+            data: data.to_vec(),
+        })
+    }
+}
+
+fn read_varint(bytes: &[u8]) -> Result<(usize, &[u8]), MultipartError> {
+    let end = bytes
+        .iter()
+        .position(|byte| byte & VARINT_CONTINUATION_FLAG == 0)
+        .ok_or(MultipartError::MalformedPart)?;
+    let value = decode_varint(&bytes[..=end]).map_err(|_| MultipartError::MalformedPart)?;
+    Ok((value, &bytes[end + 1..]))
+}
+
+/// Cuts the payload into parts with chunks of given lengths.
+///
+/// Whatever is left after the given lengths goes into one more part.
+/// Empty payload still gets a single, empty part.
+pub fn split_payload(payload: &[u8], message_id: u32, chunk_lengths: &[usize]) -> Vec<Part> {
+    let mut chunks = Vec::new();
+    let mut rest = payload;
+    for length in chunk_lengths {
+        if rest.is_empty() {
+            break;
+        }
+        let (chunk, remaining) = rest.split_at(rest.len().min(*length));
+        chunks.push(chunk);
+        rest = remaining;
+    }
+    if chunks.is_empty() || !rest.is_empty() {
+        chunks.push(rest);
+    }
+
+    let total = chunks.len();
+    chunks
+        .into_iter()
+        .enumerate()
+        .map(|(index, chunk)| Part {
+            message_id,
+            index,
+            total,
+            data: chunk.to_vec(),
+        })
+        .collect()
+}
+
+/// Conceals the payload in as few covers as possible, cutting it into parts that fit.
+///
+/// `try_conceal` is called with the index of the cover and the serialized part. It should report
+/// [`Attempt::TooSmall`] with the amount of data that didn't fit - the part is then shrunk and
+/// tried again. Once the lengths of all the chunks are known, the parts are concealed again with
+/// the final total count.
+///
+/// Fails with [`MultipartError::CoversTooSmall`] if all the covers together can't hold the payload.
+pub fn conceal_in_parts<Output, Err, F>(
+    payload: &[u8],
+    message_id: u32,
+    cover_count: usize,
+    mut try_conceal: F,
+) -> Result<Vec<Output>, Err>
+where
+    Err: From<MultipartError>,
+    F: FnMut(usize, &[u8]) -> Result<Attempt<Output>, Err>,
+{
+    let mut chunk_lengths = Vec::new();
+    let mut rest = payload;
+    for cover_index in 0..cover_count {
+        if rest.is_empty() && cover_index > 0 {
+            break;
+        }
+        // Total count is not known yet, the cover count is its upper bound
+        let mut length = rest.len();
+        loop {
+            let part = Part {
+                message_id,
+                index: cover_index,
+                total: cover_count,
+                data: rest[..length].to_vec(),
+            };
+            match try_conceal(cover_index, &part.to_bytes())? {
+                Attempt::Concealed(_) => break,
+                Attempt::TooSmall { .. } if length == 0 => {
+                    return Err(MultipartError::CoversTooSmall {
+                        remaining_data_size: rest.len(),
+                    }
+                    .into())
+                }
+                Attempt::TooSmall { remaining_bits } => {
+                    let excess = remaining_bits.div_ceil(8).max(1);
+                    length = length.saturating_sub(excess);
+                }
+            }
+        }
+        chunk_lengths.push(length);
+        rest = &rest[length..];
+    }
+    if !rest.is_empty() {
+        return Err(MultipartError::CoversTooSmall {
+            remaining_data_size: rest.len(),
+        }
+        .into());
+    }
+
+    let mut outputs = Vec::new();
+    for (cover_index, part) in split_payload(payload, message_id, &chunk_lengths)
+        .iter()
+        .enumerate()
+    {
+        match try_conceal(cover_index, &part.to_bytes())? {
+            Attempt::Concealed(output) => outputs.push(output),
+            Attempt::TooSmall { .. } => {
+                return Err(MultipartError::CoversTooSmall {
+                    remaining_data_size: part.data.len(),
+                }
+                .into())
+            }
+        }
+    }
+    Ok(outputs)
+}
+
+/// Joins the parts given in any order back into the payload.
+///
+/// Duplicated parts are accepted as long as they carry the same chunk.
+/// Fails with [`MultipartError::MissingParts`] listing the missing parts (counted from 1)
+/// or with another [`MultipartError`] if the parts don't belong together.
+pub fn reassemble(parts: impl IntoIterator<Item = Part>) -> Result<Vec<u8>, MultipartError> {
+    let mut parts = parts.into_iter().peekable();
+    let first = parts.peek().ok_or(MultipartError::NoParts)?;
+    let (message_id, total) = (first.message_id, first.total);
+
+    let mut chunks = BTreeMap::new();
+    for part in parts {
+        if part.message_id != message_id {
+            return Err(MultipartError::MixedMessages {
+                first: message_id,
+                second: part.message_id,
+            });
+        }
+        if part.total != total {
+            return Err(MultipartError::InconsistentTotal {
+                expected: total,
+                actual: part.total,
+            });
+        }
+        match chunks.get(&part.index) {
+            Some(chunk) if chunk != &part.data => {
+                return Err(MultipartError::ConflictingPart {
+                    index: part.index + 1,
+                })
+            }
+            Some(_) => {}
+            None => {
+                chunks.insert(part.index, part.data);
+            }
+        }
+    }
+
+    let missing = (0..total)
+        .filter(|index| !chunks.contains_key(index))
+        .map(|index| index + 1)
+        .collect::<Vec<usize>>();
+    if !missing.is_empty() {
+        return Err(MultipartError::MissingParts {
+            message_id,
+            missing,
+            total,
+        });
+    }
+    Ok(chunks.into_values().flatten().collect())
+}
+
+/// Describes possible errors while splitting or reassembling the payload.
+#[derive(Debug, PartialEq, Snafu)]
+pub enum MultipartError {
+    /// Revealed data is too short or its header is invalid.
+    #[snafu(display("Revealed data is not a valid part of a message"))]
+    MalformedPart,
+    /// Nothing to reassemble.
+    #[snafu(display("No parts of the message were given"))]
+    NoParts,
+    /// Parts belong to different messages.
+    #[snafu(display(
+        "Parts belong to different messages, '{:08x}' and '{:08x}'",
+        first,
+        second
+    ))]
+    MixedMessages { first: u32, second: u32 },
+    /// Parts disagree on the amount of parts.
+    #[snafu(display("Parts disagree on the total count, '{}' and '{}'", expected, actual))]
+    InconsistentTotal { expected: usize, actual: usize },
+    /// The same part was given twice with different data.
+    #[snafu(display("Part {} was given twice with different data", index))]
+    ConflictingPart { index: usize },
+    /// Some of the parts are missing, they are counted from 1.
+    #[snafu(display(
+        "Missing parts {:?} of {} of the message '{:08x}'",
+        missing,
+        total,
+        message_id
+    ))]
+    MissingParts {
+        message_id: u32,
+        missing: Vec<usize>,
+        total: usize,
+    },
+    /// All the covers together are too small for the payload.
+    #[snafu(display(
+        "Cover texts are too small, '{}' bytes of the payload didn't fit",
+        remaining_data_size
+    ))]
+    CoversTooSmall { remaining_data_size: usize },
+}
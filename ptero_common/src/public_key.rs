@@ -0,0 +1,237 @@
+//! Public-key encryption of the payload to the recipient's X25519 key.
+//!
+//! Instead of sharing the passphrase, every recipient generates the [`Identity`] once and hands out
+//! its public part, the [`Recipient`]. The payload is sealed with ephemeral-static Diffie-Hellman:
+//! a fresh ephemeral key is generated for every sealing, the key of ChaCha20-Poly1305 is derived
+//! from the shared secret (hashed with BLAKE2b together with both public keys) and the ephemeral
+//! public key is stored in front of the ciphertext:
+//!
+//! | ephemeral public key (32 bytes) | ciphertext | authentication tag (16 bytes) |
+//!
+//! Only the owner of the identity can derive the same key again. As every key is used only once,
+//! the nonce is fixed.
+//!
+//! Keys are written as the prefixed hex strings, see [`RECIPIENT_PREFIX`] and [`IDENTITY_PREFIX`].
+//!
+//! # Examples
+//!
+//! ```
+//! use ptero_common::encryption::EncryptionError;
+//! use ptero_common::public_key::{open_with, seal_to, Identity, Recipient, PUBLIC_KEY_SEALING_OVERHEAD};
+//!
+//! let identity = Identity::generate();
+//! let recipient: Recipient = identity.recipient().to_string().parse().unwrap();
+//!
+//! let sealed = seal_to(b"Hi", &recipient);
+//! assert_eq!(sealed.len(), 2 + PUBLIC_KEY_SEALING_OVERHEAD);
+//! assert_eq!(open_with(&sealed, &identity), Ok(b"Hi".to_vec()));
+//! assert_eq!(
+//!     open_with(&sealed, &Identity::generate()),
+//!     Err(EncryptionError::AuthenticationFailed)
+//! );
+//! ```
+use std::fmt;
+use std::str::FromStr;
+
+use blake2::digest::consts::U32;
+use blake2::{Blake2b, Digest};
+use chacha20poly1305::aead::{Aead, KeyInit};
+use chacha20poly1305::{ChaCha20Poly1305, Key, Nonce};
+use rand::rngs::OsRng;
+use snafu::Snafu;
+use x25519_dalek::{EphemeralSecret, PublicKey, StaticSecret};
+
+use crate::encryption::{EncryptionError, TAG_LENGTH};
+
+/// Length of the ephemeral public key stored in front of the ciphertext.
+pub const EPHEMERAL_KEY_LENGTH: usize = 32;
+/// Amount of bytes the sealing adds to the payload.
+pub const PUBLIC_KEY_SEALING_OVERHEAD: usize = EPHEMERAL_KEY_LENGTH + TAG_LENGTH;
+/// Prefix of the written [`Recipient`].
+pub const RECIPIENT_PREFIX: &str = "ptero-pk-";
+/// Prefix of the written [`Identity`].
+pub const IDENTITY_PREFIX: &str = "ptero-sk-";
+
+const KEY_LENGTH: usize = 32;
+// Part of the format, changing it makes older stego texts unreadable
+const KDF_CONTEXT: &[u8] = b"ptero x25519 payload key";
+// Any scalar kills the low order points, as X25519 clamps it to the multiple of the cofactor
+const PROBE_SCALAR: [u8; KEY_LENGTH] = [1; KEY_LENGTH];
+
+/// Public key the payload is sealed to.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Recipient(PublicKey);
+
+/// Secret key of the recipient, opens the payloads sealed to its [`Recipient`].
+#[derive(Clone)]
+pub struct Identity(StaticSecret);
+
+impl Identity {
+    /// Generates the new identity from the OS entropy source.
+    pub fn generate() -> Self {
+        Identity(StaticSecret::random_from_rng(OsRng))
+    }
+
+    /// Returns the public key to share with the senders.
+    pub fn recipient(&self) -> Recipient {
+        Recipient(PublicKey::from(&self.0))
+    }
+
+    /// Writes the identity as the key file - the comment with the public key followed by the identity.
+    pub fn to_key_file(&self) -> String {
+        format!("# Public key: {}\n{}\n", self.recipient(), self)
+    }
+
+    /// Reads the identity from the key file written by [`Identity::to_key_file`].
+    /// Empty lines and comments starting with `#` are skipped.
+    pub fn from_key_file(key_file: &str) -> Result<Self, KeyError> {
+        key_file
+            .lines()
+            .map(str::trim)
+            .find(|line| !line.is_empty() && !line.starts_with('#'))
+            .ok_or(KeyError::MalformedIdentity)?
+            .parse()
+    }
+}
+
+impl fmt::Display for Recipient {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}{}", RECIPIENT_PREFIX, to_hex(self.0.as_bytes()))
+    }
+}
+
+impl FromStr for Recipient {
+    type Err = KeyError;
+
+    /// Parses the recipient, rejecting the keys which would give the predictable shared secret.
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        let bytes = parse_key(text, RECIPIENT_PREFIX).ok_or(KeyError::MalformedRecipient)?;
+        let public_key = PublicKey::from(bytes);
+        if !StaticSecret::from(PROBE_SCALAR)
+            .diffie_hellman(&public_key)
+            .was_contributory()
+        {
+            return Err(KeyError::WeakRecipient);
+        }
+        Ok(Recipient(public_key))
+    }
+}
+
+impl fmt::Display for Identity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}{}", IDENTITY_PREFIX, to_hex(self.0.as_bytes()))
+    }
+}
+
+impl fmt::Debug for Identity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("Identity").field(&"<redacted>").finish()
+    }
+}
+
+impl PartialEq for Identity {
+    fn eq(&self, other: &Self) -> bool {
+        self.0.as_bytes() == other.0.as_bytes()
+    }
+}
+
+impl FromStr for Identity {
+    type Err = KeyError;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        let bytes = parse_key(text, IDENTITY_PREFIX).ok_or(KeyError::MalformedIdentity)?;
+        Ok(Identity(StaticSecret::from(bytes)))
+    }
+}
+
+/// Encrypts and authenticates the payload, so it can be opened only with the recipient's identity.
+///
+/// Returns the ephemeral public key and ciphertext concatenated together.
+pub fn seal_to(payload: &[u8], recipient: &Recipient) -> Vec<u8> {
+    let ephemeral_secret = EphemeralSecret::random_from_rng(OsRng);
+    let ephemeral_key = PublicKey::from(&ephemeral_secret);
+    let shared_secret = ephemeral_secret.diffie_hellman(&recipient.0);
+
+    let cipher = ChaCha20Poly1305::new(&derive_key(
+        shared_secret.as_bytes(),
+        &ephemeral_key,
+        &recipient.0,
+    ));
+    let ciphertext = cipher
+        .encrypt(&Nonce::default(), payload)
+        .expect("Payload is too long to be encrypted");
+
+    let mut sealed = Vec::with_capacity(payload.len() + PUBLIC_KEY_SEALING_OVERHEAD);
+    sealed.extend_from_slice(ephemeral_key.as_bytes());
+    sealed.extend(ciphertext);
+    sealed
+}
+
+/// Verifies and decrypts the payload sealed by [`seal_to`].
+///
+/// Fails with [`EncryptionError::AuthenticationFailed`] if the payload has been sealed
+/// to another recipient or has been tampered with.
+pub fn open_with(sealed: &[u8], identity: &Identity) -> Result<Vec<u8>, EncryptionError> {
+    if sealed.len() < PUBLIC_KEY_SEALING_OVERHEAD {
+        return Err(EncryptionError::TooShort {
+            length: sealed.len(),
+        });
+    }
+    let (ephemeral_key, ciphertext) = sealed.split_at(EPHEMERAL_KEY_LENGTH);
+    let mut ephemeral_key_bytes = [0; EPHEMERAL_KEY_LENGTH];
+    ephemeral_key_bytes.copy_from_slice(ephemeral_key);
+    let ephemeral_key = PublicKey::from(ephemeral_key_bytes);
+
+    let shared_secret = identity.0.diffie_hellman(&ephemeral_key);
+    if !shared_secret.was_contributory() {
+        return Err(EncryptionError::AuthenticationFailed);
+    }
+    let cipher = ChaCha20Poly1305::new(&derive_key(
+        shared_secret.as_bytes(),
+        &ephemeral_key,
+        &identity.recipient().0,
+    ));
+    cipher
+        .decrypt(&Nonce::default(), ciphertext)
+        .map_err(|_| EncryptionError::AuthenticationFailed)
+}
+
+fn derive_key(shared_secret: &[u8], ephemeral_key: &PublicKey, recipient_key: &PublicKey) -> Key {
+    let digest = Blake2b::<U32>::new()
+        .chain_update(KDF_CONTEXT)
+        .chain_update(shared_secret)
+        .chain_update(ephemeral_key.as_bytes())
+        .chain_update(recipient_key.as_bytes())
+        .finalize();
+    Key::clone_from_slice(&digest)
+}
+
+fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+fn parse_key(text: &str, prefix: &str) -> Option<[u8; KEY_LENGTH]> {
+    let hex = text.trim().strip_prefix(prefix)?;
+    if hex.len() != 2 * KEY_LENGTH || !hex.is_ascii() {
+        return None;
+    }
+    let mut bytes = [0; KEY_LENGTH];
+    for (index, byte) in bytes.iter_mut().enumerate() {
+        *byte = u8::from_str_radix(&hex[2 * index..2 * index + 2], 16).ok()?;
+    }
+    Some(bytes)
+}
+
+/// Describes possible errors while reading the keys.
+#[derive(Debug, PartialEq, Snafu)]
+pub enum KeyError {
+    /// Text is not the public key written by [`Recipient`].
+    #[snafu(display("Recipient has to be '{}' followed by 64 hex digits", RECIPIENT_PREFIX))]
+    MalformedRecipient,
+    /// Public key is one of the few giving the same shared secret for every identity.
+    #[snafu(display("Recipient is not a valid public key"))]
+    WeakRecipient,
+    /// Text is not the secret key written by [`Identity`].
+    #[snafu(display("Identity has to be '{}' followed by 64 hex digits", IDENTITY_PREFIX))]
+    MalformedIdentity,
+}
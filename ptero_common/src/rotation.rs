@@ -0,0 +1,71 @@
+//! Keyed rotation of the submethods order.
+//!
+//! Complex methods trigger their three submethods in the order fixed by the variant, the same one
+//! for every line. With the rotation, the order of every line is drawn from all [`PERMUTATIONS`]
+//! instead, by the ChaCha20 stream seeded from the shared secret. The seed is derived with Argon2id
+//! (see [`crate::encryption`]) and a fixed salt, so revealing side replays the same schedule
+//! knowing only the secret.
+//!
+//! The order describes which submethod takes the data bits first. Methods are free to modify
+//! the line in their own order, as long as every submethod can still read its bits back.
+//!
+//! # Examples
+//!
+//! ```
+//! use ptero_common::rotation::{VariantRotation, PERMUTATIONS};
+//!
+//! let rotation = VariantRotation::new(b"bamboo");
+//! let orders = rotation.clone().take(16).collect::<Vec<[usize; 3]>>();
+//!
+//! // The same secret always gives the same schedule
+//! assert_eq!(VariantRotation::new(b"bamboo").take(16).collect::<Vec<[usize; 3]>>(), orders);
+//! assert!(orders.iter().all(|order| PERMUTATIONS.contains(order)));
+//! ```
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha20Rng;
+
+use crate::encryption::derive_key;
+
+/// Every order of three submethods, as the indices of the submethods.
+pub const PERMUTATIONS: [[usize; 3]; 6] = [
+    [0, 1, 2],
+    [0, 2, 1],
+    [1, 0, 2],
+    [1, 2, 0],
+    [2, 0, 1],
+    [2, 1, 0],
+];
+
+// Part of the format, changing it makes older stego texts unreadable
+const ROTATION_SALT: &[u8] = b"ptero variant rotation";
+
+/// Endless sequence of the submethods orders, one for every line.
+///
+/// Clone it before use to start the sequence over for another cover.
+#[derive(Debug, Clone)]
+pub struct VariantRotation {
+    rng: ChaCha20Rng,
+}
+
+impl VariantRotation {
+    /// Creates the rotation seeded from the shared secret.
+    pub fn new(secret: impl AsRef<[u8]>) -> Self {
+        let seed = derive_key(secret.as_ref(), ROTATION_SALT);
+        VariantRotation {
+            rng: ChaCha20Rng::from_seed(seed.into()),
+        }
+    }
+
+    /// Draws the order of the submethods for the next line.
+    pub fn next_order(&mut self) -> [usize; 3] {
+        PERMUTATIONS[self.rng.gen_range(0..PERMUTATIONS.len())]
+    }
+}
+
+impl Iterator for VariantRotation {
+    type Item = [usize; 3];
+
+    fn next(&mut self) -> Option<Self::Item> {
+        Some(self.next_order())
+    }
+}
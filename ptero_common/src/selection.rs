@@ -0,0 +1,214 @@
+//! Selection of the lines carrying the data.
+//!
+//! Without the selection, methods modify every line from the top of the cover until the data runs
+//! out, which leaves a recognizable pattern - modified head followed by pristine tail. There are two
+//! ways to pick the lines instead, the other lines are left untouched as decoys:
+//!
+//! * keyed - a PRNG seeded from the shared secret decides for every line whether it carries the data.
+//!   The seed is derived from the secret with Argon2id (see [`crate::encryption`]) and a fixed salt,
+//!   lines are picked with [`SELECTION_PROBABILITY`] by ChaCha20 stream,
+//! * spread - the lines needed by the payload are spaced through the whole cover, see [`Spread`].
+//!   It doesn't need any secret, the spacing is described by a single byte concealed
+//!   before the data.
+//!
+//! Lines can also be dealt into [`POOL_COUNT`] disjoint pools by a pseudo-random sequence drawn
+//! from a seed, so each pool carries a different payload (see [`LineSelector::pool`]).
+//!
+//! Revealing side uses the same selection to skip the decoys.
+//!
+//! # Examples
+//!
+//! ```
+//! use ptero_common::selection::{LineSelector, Spread, SpreadMode};
+//!
+//! let selector = LineSelector::new(b"bamboo");
+//! let picked = selector.clone().take(16).collect::<Vec<bool>>();
+//!
+//! // The same secret always picks the same lines
+//! assert_eq!(LineSelector::new(b"bamboo").take(16).collect::<Vec<bool>>(), picked);
+//! assert_ne!(LineSelector::new(b"eucalyptus").take(16).collect::<Vec<bool>>(), picked);
+//!
+//! // 4 lines needed out of 13 available, every third one carries the data
+//! let spread = Spread::fitting(SpreadMode::Uniform, 4, 13);
+//! assert_eq!(spread.stride, 3);
+//! assert_eq!(
+//!     LineSelector::spread(spread).take(7).collect::<Vec<bool>>(),
+//!     vec![true, false, false, true, false, false, true]
+//! );
+//! ```
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha20Rng;
+use snafu::Snafu;
+
+use crate::encryption::derive_key;
+
+/// Chance of the line being picked to carry the data.
+pub const SELECTION_PROBABILITY: f64 = 0.5;
+/// The largest distance between the lines carrying the data.
+pub const MAX_STRIDE: usize = 0b0111_1111;
+/// Amount of bits taken by the byte describing the [`Spread`].
+pub const SPREAD_DESCRIPTOR_LENGTH: usize = 8;
+/// Amount of the disjoint pools the lines are dealt into.
+pub const POOL_COUNT: usize = 2;
+
+// Part of the format, changing them makes older stego texts unreadable
+const SELECTION_SALT: &[u8] = b"ptero line selection";
+const SPREAD_SEED: u64 = 0x7074_6572_6f5f_7370;
+const RANDOM_MODE_FLAG: u8 = 0b1000_0000;
+
+/// How the lines are picked within the stride.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SpreadMode {
+    /// The first line of every stride carries the data.
+    Uniform,
+    /// A pseudo-random line of every stride carries the data.
+    /// The sequence is fixed, so it doesn't need a secret to be reproduced.
+    Random,
+}
+
+/// Spacing of the lines carrying the data.
+///
+/// Lines are grouped by `stride` and only one line of each group carries the data.
+/// Serialized into a single byte - the highest bit tells the mode, the rest is the stride.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Spread {
+    pub mode: SpreadMode,
+    /// Distance between the lines carrying the data, from 1 to [`MAX_STRIDE`].
+    pub stride: usize,
+}
+
+impl Spread {
+    /// Picks the widest stride that still lets the payload fit into the cover.
+    ///
+    /// # Arguments
+    ///
+    /// * `needed_lines` - how many lines the payload takes when concealed line by line
+    /// * `available_lines` - how many lines the cover has
+    pub fn fitting(mode: SpreadMode, needed_lines: usize, available_lines: usize) -> Self {
+        let stride = available_lines
+            .checked_div(needed_lines)
+            .unwrap_or(MAX_STRIDE)
+            .clamp(1, MAX_STRIDE);
+        Spread { mode, stride }
+    }
+
+    /// Serializes the spread into the descriptor byte.
+    pub fn to_byte(&self) -> u8 {
+        let mode_flag = match self.mode {
+            SpreadMode::Uniform => 0,
+            SpreadMode::Random => RANDOM_MODE_FLAG,
+        };
+        mode_flag | self.stride.clamp(1, MAX_STRIDE) as u8
+    }
+
+    /// Restores the spread from the descriptor byte.
+    ///
+    /// Fails with [`SelectionError::InvalidSpread`] if the stride is zero.
+    pub fn from_byte(byte: u8) -> Result<Self, SelectionError> {
+        let stride = (byte & !RANDOM_MODE_FLAG) as usize;
+        if stride == 0 {
+            return Err(SelectionError::InvalidSpread { byte });
+        }
+        let mode = if byte & RANDOM_MODE_FLAG != 0 {
+            SpreadMode::Random
+        } else {
+            SpreadMode::Uniform
+        };
+        Ok(Spread { mode, stride })
+    }
+}
+
+#[derive(Debug, Clone)]
+enum Selection {
+    Keyed(ChaCha20Rng),
+    Spread {
+        spread: Spread,
+        rng: ChaCha20Rng,
+        position: usize,
+        selected_offset: usize,
+    },
+    Pool {
+        pool: usize,
+        rng: ChaCha20Rng,
+    },
+}
+
+/// Endless sequence of decisions whether the next line carries the data.
+///
+/// Clone it before use to start the sequence over for another cover.
+#[derive(Debug, Clone)]
+pub struct LineSelector {
+    selection: Selection,
+}
+
+impl LineSelector {
+    /// Creates the selector seeded from the shared secret.
+    pub fn new(secret: impl AsRef<[u8]>) -> Self {
+        let seed = derive_key(secret.as_ref(), SELECTION_SALT);
+        LineSelector {
+            selection: Selection::Keyed(ChaCha20Rng::from_seed(seed.into())),
+        }
+    }
+
+    /// Creates the selector picking one line of every stride.
+    pub fn spread(spread: Spread) -> Self {
+        LineSelector {
+            selection: Selection::Spread {
+                spread,
+                rng: ChaCha20Rng::seed_from_u64(SPREAD_SEED),
+                position: 0,
+                selected_offset: 0,
+            },
+        }
+    }
+
+    /// Creates the selector picking the lines dealt into the given pool, counted from 0.
+    ///
+    /// Every line is dealt into exactly one of the [`POOL_COUNT`] pools by the sequence drawn
+    /// from the `seed`. Selectors of different pools are disjoint only when they share the seed.
+    pub fn pool(seed: u64, pool: usize) -> Self {
+        LineSelector {
+            selection: Selection::Pool {
+                pool,
+                rng: ChaCha20Rng::seed_from_u64(seed),
+            },
+        }
+    }
+
+    /// Decides whether the next line carries the data.
+    pub fn is_next_selected(&mut self) -> bool {
+        match &mut self.selection {
+            Selection::Keyed(rng) => rng.gen_bool(SELECTION_PROBABILITY),
+            Selection::Spread {
+                spread,
+                rng,
+                position,
+                selected_offset,
+            } => {
+                let offset = *position % spread.stride;
+                if offset == 0 && spread.mode == SpreadMode::Random {
+                    *selected_offset = rng.gen_range(0..spread.stride);
+                }
+                *position += 1;
+                offset == *selected_offset
+            }
+            Selection::Pool { pool, rng } => rng.gen_range(0..POOL_COUNT) == *pool,
+        }
+    }
+}
+
+impl Iterator for LineSelector {
+    type Item = bool;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        Some(self.is_next_selected())
+    }
+}
+
+/// Describes possible errors while restoring the selection.
+#[derive(Debug, PartialEq, Snafu)]
+pub enum SelectionError {
+    /// Descriptor byte doesn't describe a valid spread.
+    #[snafu(display("Revealed '{:#04x}' is not a valid spread of the lines", byte))]
+    InvalidSpread { byte: u8 },
+}
@@ -0,0 +1,78 @@
+use ptero_common::compression::{
+    compress, compress_with, decompress, Codec, CompressionError, MAX_DECOMPRESSED_LENGTH,
+};
+use ptero_common::frame::encode_varint;
+
+#[test]
+fn should_restore_payload_compressed_with_any_codec() {
+    let payloads: Vec<Vec<u8>> = vec![
+        vec![],
+        b"Hello, panda!".to_vec(),
+        (0..=255).collect(),
+        "Zażółć gęślą jaźń".as_bytes().to_vec(),
+    ];
+
+    for codec in [Codec::Stored, Codec::Deflate, Codec::EnglishText].iter() {
+        for payload in payloads.iter() {
+            let compressed = compress_with(*codec, payload);
+            assert_eq!(
+                decompress(*codec, &compressed).as_ref(),
+                Ok(payload),
+                "Failed for {:?}",
+                codec
+            );
+        }
+    }
+}
+
+#[test]
+fn should_pick_english_text_codec_for_short_messages() {
+    let message = b"The panda fell from a tree and went rolling down the hill.";
+
+    let (codec, compressed) = compress(message);
+
+    assert_eq!(codec, Codec::EnglishText);
+    assert!(compressed.len() * 10 < message.len() * 7);
+}
+
+#[test]
+fn should_pick_deflate_for_repetitive_data() {
+    let data = [0, 1, 2, 3].repeat(100);
+
+    let (codec, compressed) = compress(&data);
+
+    assert_eq!(codec, Codec::Deflate);
+    assert!(compressed.len() < data.len() / 10);
+}
+
+#[test]
+fn should_store_data_that_cannot_be_compressed() {
+    let data = [0xf7, 0x13, 0x8a, 0x5c];
+
+    assert_eq!(compress(&data), (Codec::Stored, data.to_vec()));
+}
+
+#[test]
+fn should_fail_when_compressed_data_is_corrupted() {
+    let mut compressed = compress_with(Codec::EnglishText, b"panda");
+    compressed.truncate(compressed.len() - 1);
+
+    assert_eq!(
+        decompress(Codec::EnglishText, &compressed),
+        Err(CompressionError::Corrupted)
+    );
+    assert_eq!(
+        decompress(Codec::Deflate, &[0xff, 0xff, 0xff]),
+        Err(CompressionError::Corrupted)
+    );
+}
+
+#[test]
+fn should_fail_when_decompressed_data_is_too_large() {
+    let compressed = encode_varint(MAX_DECOMPRESSED_LENGTH + 1);
+
+    assert_eq!(
+        decompress(Codec::EnglishText, &compressed),
+        Err(CompressionError::TooLarge)
+    );
+}
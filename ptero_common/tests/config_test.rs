@@ -0,0 +1,36 @@
+use rand::Rng;
+
+use ptero_common::config::{keyed_rng, CommonMethodConfig};
+
+fn numbers(rng: &mut impl Rng) -> Vec<u32> {
+    (0..16).map(|_| rng.gen()).collect()
+}
+
+#[test]
+fn should_derive_the_same_rng_from_the_same_key() {
+    let expected = numbers(&mut keyed_rng("bamboo", "forest"));
+
+    assert_eq!(numbers(&mut keyed_rng(b"bamboo", b"forest")), expected);
+    assert_ne!(numbers(&mut keyed_rng("Bamboo", "forest")), expected);
+    assert_ne!(numbers(&mut keyed_rng("bamboo", "Forest")), expected);
+}
+
+#[test]
+fn should_accept_empty_salt() {
+    let expected = numbers(&mut keyed_rng("bamboo", ""));
+
+    assert_eq!(numbers(&mut keyed_rng("bamboo", [])), expected);
+}
+
+#[test]
+fn should_build_config_with_keyed_rng() {
+    let mut config = CommonMethodConfig::builder()
+        .with_key("bamboo", "forest")
+        .build()
+        .unwrap();
+
+    assert_eq!(
+        numbers(&mut config.rng),
+        numbers(&mut keyed_rng("bamboo", "forest"))
+    );
+}
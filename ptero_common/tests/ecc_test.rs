@@ -0,0 +1,91 @@
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+use ptero_common::ecc::{EccError, EccLevel, ReedSolomon, BLOCK_DATA_LENGTH, MAX_ECC_LEVEL};
+
+#[test]
+fn should_accept_only_supported_levels() {
+    assert_eq!(EccLevel::new(0), None);
+    assert_eq!(EccLevel::new(1).map(|level| level.parity_length()), Some(2));
+    assert_eq!(
+        EccLevel::new(MAX_ECC_LEVEL).map(|level| level.value()),
+        Some(MAX_ECC_LEVEL)
+    );
+    assert_eq!(EccLevel::new(MAX_ECC_LEVEL + 1), None);
+}
+
+#[test]
+fn should_pad_data_to_full_blocks() {
+    let codec = ReedSolomon::new(EccLevel::new(1).unwrap());
+    let data = [7; BLOCK_DATA_LENGTH + 1];
+
+    let encoded = codec.encode(&data);
+
+    assert_eq!(encoded.len(), 2 * (BLOCK_DATA_LENGTH + 2));
+    assert_eq!(&encoded[..BLOCK_DATA_LENGTH], &data[..BLOCK_DATA_LENGTH]);
+    let mut expected = data.to_vec();
+    expected.resize(2 * BLOCK_DATA_LENGTH, 0);
+    assert_eq!(codec.decode(&encoded), Ok(expected));
+}
+
+#[test]
+fn should_correct_up_to_level_errors_in_every_block() {
+    let mut rng = StdRng::seed_from_u64(42);
+    for level in 1..=MAX_ECC_LEVEL {
+        let codec = ReedSolomon::new(EccLevel::new(level).unwrap());
+        for _ in 0..50 {
+            let data = (0..3 * BLOCK_DATA_LENGTH)
+                .map(|_| rng.gen())
+                .collect::<Vec<u8>>();
+            let mut encoded = codec.encode(&data);
+
+            let block_length = BLOCK_DATA_LENGTH + 2 * level as usize;
+            for block in encoded.chunks_mut(block_length) {
+                let error_count = rng.gen_range(0..=level as usize);
+                for position in rand::seq::index::sample(&mut rng, block_length, error_count) {
+                    block[position] ^= rng.gen_range(1..=255);
+                }
+            }
+
+            assert_eq!(
+                codec.decode(&encoded),
+                Ok(data),
+                "Failed for level {}",
+                level
+            );
+        }
+    }
+}
+
+#[test]
+fn should_fail_when_block_has_too_many_errors() {
+    let codec = ReedSolomon::new(EccLevel::new(1).unwrap());
+    let mut encoded = codec.encode(b"Hello, panda!");
+    encoded[0] ^= 1;
+    encoded[5] ^= 1;
+    encoded[10] ^= 1;
+
+    assert_eq!(codec.decode(&encoded), Err(EccError::TooManyErrors));
+}
+
+#[test]
+fn should_fail_when_block_length_is_invalid() {
+    let level = EccLevel::new(2).unwrap();
+    let codec = ReedSolomon::new(level);
+    let encoded = codec.encode(b"Hello, panda!");
+
+    assert_eq!(
+        codec.decode_block(&encoded[..3]),
+        Err(EccError::InvalidBlockLength {
+            length: 3,
+            expected: level.block_length()
+        })
+    );
+    assert_eq!(
+        codec.decode_block(&[]),
+        Err(EccError::InvalidBlockLength {
+            length: 0,
+            expected: level.block_length()
+        })
+    );
+}
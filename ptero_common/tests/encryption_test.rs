@@ -0,0 +1,33 @@
+use ptero_common::encryption::{
+    open_delimited, seal_delimited, EncryptionError, DELIMITED_SEALING_OVERHEAD,
+};
+
+#[test]
+fn should_open_delimited_payload_followed_by_noise() {
+    let mut sealed = seal_delimited(b"panda", b"bamboo");
+    sealed.extend_from_slice(&[0xff; 32]);
+
+    assert_eq!(open_delimited(&sealed, b"bamboo"), Ok(b"panda".to_vec()));
+    assert_eq!(
+        open_delimited(&sealed, b"eucalyptus"),
+        Err(EncryptionError::AuthenticationFailed)
+    );
+}
+
+#[test]
+fn should_fail_when_delimited_payload_is_truncated() {
+    let sealed = seal_delimited(b"panda", b"bamboo");
+
+    assert_eq!(
+        open_delimited(&sealed[..sealed.len() - 1], b"bamboo"),
+        Err(EncryptionError::TooShort {
+            length: 4 + DELIMITED_SEALING_OVERHEAD
+        })
+    );
+    assert_eq!(
+        open_delimited(&sealed[..DELIMITED_SEALING_OVERHEAD - 1], b"bamboo"),
+        Err(EncryptionError::TooShort {
+            length: DELIMITED_SEALING_OVERHEAD - 1
+        })
+    );
+}
@@ -0,0 +1,360 @@
+use bitvec::prelude::*;
+
+use ptero_common::ecc::{EccError, EccLevel};
+use ptero_common::encryption::{EncryptionError, SEALING_OVERHEAD};
+use ptero_common::frame::{
+    encode_varint, frame_payload, unframe_payload, FrameOptions, FrameReader, RevealError,
+    CHECKSUM_FLAG, COMPRESSION_FLAGS, DEFLATE_FLAGS, ENCRYPTION_FLAG, ENGLISH_TEXT_FLAGS,
+    PUBLIC_KEY_FLAG,
+};
+use ptero_common::public_key::{Identity, PUBLIC_KEY_SEALING_OVERHEAD};
+
+#[test]
+fn should_frame_and_unframe_payload() {
+    let payload: Vec<u8> = (0..=255).collect();
+
+    let framed = frame_payload(&payload, &FrameOptions::default());
+
+    assert_eq!(&framed[..3], &[0, 0b1000_0000, 0b0000_0010]);
+    assert_eq!(unframe_payload(&framed, &FrameOptions::default()), Ok(payload));
+}
+
+#[test]
+fn should_ignore_data_after_the_frame() {
+    let mut revealed = frame_payload(b"panda", &FrameOptions::default());
+    revealed.extend_from_slice(&[0, 0, 0, 255]);
+
+    assert_eq!(unframe_payload(&revealed, &FrameOptions::default()), Ok(b"panda".to_vec()));
+}
+
+#[test]
+fn should_report_completion_right_after_last_payload_bit() {
+    let options = FrameOptions {
+        checksum: true,
+        ..FrameOptions::default()
+    };
+    let framed = frame_payload(&[1, 2], &options);
+    let bits = framed.view_bits::<Msb0>();
+    let mut reader = FrameReader::new();
+
+    for (index, bit) in bits.iter().enumerate() {
+        assert!(!reader.is_complete(), "Completed too early at bit {}", index);
+        reader.push_bit(*bit).unwrap();
+    }
+
+    assert!(reader.is_complete());
+    assert_eq!(reader.into_payload(), Ok(vec![1, 2]));
+}
+
+#[test]
+fn should_fail_when_header_is_missing() {
+    assert_eq!(unframe_payload(&[], &FrameOptions::default()), Err(RevealError::MissingHeader));
+    assert_eq!(unframe_payload(&[0], &FrameOptions::default()), Err(RevealError::MissingHeader));
+    assert_eq!(unframe_payload(&[0, 0b1000_0001], &FrameOptions::default()), Err(RevealError::MissingHeader));
+}
+
+#[test]
+fn should_fail_when_length_is_malformed() {
+    let mut revealed = vec![0];
+    revealed.extend_from_slice(&[255; 12]);
+
+    assert_eq!(unframe_payload(&revealed, &FrameOptions::default()), Err(RevealError::MalformedLength));
+}
+
+#[test]
+fn should_fail_when_payload_is_truncated() {
+    let mut framed = vec![0];
+    framed.extend(encode_varint(10));
+    framed.extend_from_slice(&[1, 2, 3]);
+
+    assert_eq!(
+        unframe_payload(&framed, &FrameOptions::default()),
+        Err(RevealError::TruncatedPayload {
+            expected: 10,
+            available: 3
+        })
+    );
+}
+
+#[test]
+fn should_fail_when_flags_are_unknown() {
+    assert_eq!(
+        unframe_payload(&[0b1000_0000, 0], &FrameOptions::default()),
+        Err(RevealError::UnknownFlags { flags: 0b1000_0000 })
+    );
+}
+
+#[test]
+fn should_verify_checksum() {
+    let options = FrameOptions {
+        checksum: true,
+        ..FrameOptions::default()
+    };
+    let framed = frame_payload(b"panda", &options);
+
+    assert_eq!(framed.len(), 1 + 1 + 5 + 4);
+    assert_eq!(unframe_payload(&framed, &FrameOptions::default()), Ok(b"panda".to_vec()));
+}
+
+#[test]
+fn should_fail_when_checksum_does_not_match() {
+    let options = FrameOptions {
+        checksum: true,
+        ..FrameOptions::default()
+    };
+    let mut framed = frame_payload(b"panda", &options);
+    framed[3] ^= 0b0000_0100;
+
+    assert!(matches!(
+        unframe_payload(&framed, &FrameOptions::default()),
+        Err(RevealError::ChecksumMismatch { .. })
+    ));
+}
+
+#[test]
+fn should_fail_when_checksum_is_missing() {
+    let options = FrameOptions {
+        checksum: true,
+        ..FrameOptions::default()
+    };
+    let framed = frame_payload(b"panda", &options);
+
+    assert_eq!(
+        unframe_payload(&framed[..framed.len() - 1], &FrameOptions::default()),
+        Err(RevealError::MissingChecksum)
+    );
+}
+
+#[test]
+fn should_encrypt_payload_with_passphrase() {
+    let options = FrameOptions {
+        passphrase: Some(b"bamboo".to_vec()),
+        ..FrameOptions::default()
+    };
+    let framed = frame_payload(b"panda", &options);
+
+    assert_eq!(framed[0], ENCRYPTION_FLAG);
+    assert_eq!(framed[1] as usize, 5 + SEALING_OVERHEAD);
+    assert!(!framed.windows(5).any(|window| window == b"panda"));
+    assert_eq!(unframe_payload(&framed, &options), Ok(b"panda".to_vec()));
+}
+
+#[test]
+fn should_fail_when_passphrase_is_wrong() {
+    let options = FrameOptions {
+        passphrase: Some(b"bamboo".to_vec()),
+        ..FrameOptions::default()
+    };
+    let framed = frame_payload(b"panda", &options);
+    let wrong_options = FrameOptions {
+        passphrase: Some(b"eucalyptus".to_vec()),
+        ..FrameOptions::default()
+    };
+
+    assert_eq!(
+        unframe_payload(&framed, &wrong_options),
+        Err(RevealError::Decryption {
+            source: EncryptionError::AuthenticationFailed
+        })
+    );
+}
+
+#[test]
+fn should_fail_when_passphrase_is_missing_or_not_needed() {
+    let options = FrameOptions {
+        passphrase: Some(b"bamboo".to_vec()),
+        ..FrameOptions::default()
+    };
+    let encrypted = frame_payload(b"panda", &options);
+    let plain = frame_payload(b"panda", &FrameOptions::default());
+
+    assert_eq!(
+        unframe_payload(&encrypted, &FrameOptions::default()),
+        Err(RevealError::MissingPassphrase)
+    );
+    assert_eq!(
+        unframe_payload(&plain, &options),
+        Err(RevealError::NotEncrypted)
+    );
+}
+
+#[test]
+fn should_detect_tampered_ciphertext() {
+    let options = FrameOptions {
+        passphrase: Some(b"bamboo".to_vec()),
+        ..FrameOptions::default()
+    };
+    let mut framed = frame_payload(b"panda", &options);
+    let last = framed.len() - 1;
+    framed[last] ^= 1;
+
+    assert_eq!(
+        unframe_payload(&framed, &options),
+        Err(RevealError::Decryption {
+            source: EncryptionError::AuthenticationFailed
+        })
+    );
+}
+
+#[test]
+fn should_encrypt_payload_to_recipient() {
+    let identity = Identity::generate();
+    let framed = frame_payload(
+        b"panda",
+        &FrameOptions {
+            recipient: Some(identity.recipient()),
+            ..FrameOptions::default()
+        },
+    );
+    let options = FrameOptions {
+        identity: Some(identity),
+        ..FrameOptions::default()
+    };
+
+    assert_eq!(framed[0], PUBLIC_KEY_FLAG);
+    assert_eq!(framed[1] as usize, 5 + PUBLIC_KEY_SEALING_OVERHEAD);
+    assert_eq!(unframe_payload(&framed, &options), Ok(b"panda".to_vec()));
+}
+
+#[test]
+fn should_fail_when_identity_is_wrong_missing_or_not_needed() {
+    let identity = Identity::generate();
+    let encrypted = frame_payload(
+        b"panda",
+        &FrameOptions {
+            recipient: Some(identity.recipient()),
+            ..FrameOptions::default()
+        },
+    );
+    let plain = frame_payload(b"panda", &FrameOptions::default());
+    let wrong_options = FrameOptions {
+        identity: Some(Identity::generate()),
+        ..FrameOptions::default()
+    };
+
+    assert_eq!(
+        unframe_payload(&encrypted, &wrong_options),
+        Err(RevealError::Decryption {
+            source: EncryptionError::AuthenticationFailed
+        })
+    );
+    assert_eq!(
+        unframe_payload(&encrypted, &FrameOptions::default()),
+        Err(RevealError::MissingIdentity)
+    );
+    assert_eq!(
+        unframe_payload(&plain, &wrong_options),
+        Err(RevealError::NotEncryptedToIdentity)
+    );
+}
+
+#[test]
+fn should_require_both_passphrase_and_identity_when_both_are_used() {
+    let identity = Identity::generate();
+    let framed = frame_payload(
+        b"panda",
+        &FrameOptions {
+            passphrase: Some(b"bamboo".to_vec()),
+            recipient: Some(identity.recipient()),
+            ..FrameOptions::default()
+        },
+    );
+    let options = FrameOptions {
+        passphrase: Some(b"bamboo".to_vec()),
+        identity: Some(identity.clone()),
+        ..FrameOptions::default()
+    };
+    let identity_only = FrameOptions {
+        identity: Some(identity),
+        ..FrameOptions::default()
+    };
+
+    assert_eq!(framed[0], ENCRYPTION_FLAG | PUBLIC_KEY_FLAG);
+    assert_eq!(unframe_payload(&framed, &options), Ok(b"panda".to_vec()));
+    assert_eq!(
+        unframe_payload(&framed, &identity_only),
+        Err(RevealError::MissingPassphrase)
+    );
+}
+
+#[test]
+fn should_compress_payload() {
+    let options = FrameOptions {
+        compression: true,
+        ..FrameOptions::default()
+    };
+    let message = b"Meet me at the old bridge at noon, bring the bamboo.";
+    let framed = frame_payload(message, &options);
+
+    assert_eq!(framed[0] & COMPRESSION_FLAGS, ENGLISH_TEXT_FLAGS);
+    assert!(framed.len() < message.len());
+    assert_eq!(unframe_payload(&framed, &options), Ok(message.to_vec()));
+}
+
+#[test]
+fn should_compress_payload_before_encryption() {
+    let options = FrameOptions {
+        checksum: true,
+        passphrase: Some(b"bamboo".to_vec()),
+        compression: true,
+        ..FrameOptions::default()
+    };
+    let data = [42; 200];
+    let framed = frame_payload(&data, &options);
+
+    assert_eq!(framed[0], CHECKSUM_FLAG | ENCRYPTION_FLAG | DEFLATE_FLAGS);
+    assert!(framed.len() < 100);
+    assert_eq!(unframe_payload(&framed, &options), Ok(data.to_vec()));
+}
+
+#[test]
+fn should_fail_when_compression_codec_is_unknown() {
+    assert_eq!(
+        unframe_payload(&[COMPRESSION_FLAGS, 0], &FrameOptions::default()),
+        Err(RevealError::UnknownFlags {
+            flags: COMPRESSION_FLAGS
+        })
+    );
+}
+
+#[test]
+fn should_correct_errors_in_the_frame() {
+    let options = FrameOptions {
+        checksum: true,
+        error_correction: EccLevel::new(2),
+        ..FrameOptions::default()
+    };
+    let mut framed = frame_payload(b"Hello, panda!", &options);
+    // 19 bytes of the frame padded to 2 blocks, with 4 parity bytes each
+    assert_eq!(framed.len(), 40);
+    framed[2] = 0;
+    framed[17] ^= 0b1010_1010;
+    framed[25] = 0;
+    framed[39] ^= 0b1111_1111;
+    // Noise after the frame is ignored
+    framed.extend_from_slice(&[1, 2, 3]);
+
+    assert_eq!(
+        unframe_payload(&framed, &options),
+        Ok(b"Hello, panda!".to_vec())
+    );
+}
+
+#[test]
+fn should_fail_when_frame_is_damaged_beyond_repair() {
+    let options = FrameOptions {
+        error_correction: EccLevel::new(1),
+        ..FrameOptions::default()
+    };
+    let mut framed = frame_payload(b"panda", &options);
+    for byte in framed.iter_mut().take(4) {
+        *byte ^= 0b0101_0101;
+    }
+
+    assert_eq!(
+        unframe_payload(&framed, &options),
+        Err(RevealError::ErrorCorrection {
+            source: EccError::TooManyErrors
+        })
+    );
+}
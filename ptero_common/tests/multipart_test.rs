@@ -0,0 +1,123 @@
+use ptero_common::multipart::{
+    conceal_in_parts, reassemble, split_payload, Attempt, MultipartError, Part,
+};
+
+#[test]
+fn should_serialize_and_restore_part() {
+    let part = Part {
+        message_id: 0xdead_beef,
+        index: 1,
+        total: 200,
+        data: b"panda".to_vec(),
+    };
+
+    let bytes = part.to_bytes();
+
+    assert_eq!(&bytes[..7], &[0xde, 0xad, 0xbe, 0xef, 1, 0b1100_1000, 1]);
+    assert_eq!(Part::from_bytes(&bytes), Ok(part));
+}
+
+#[test]
+fn should_fail_when_part_is_malformed() {
+    assert_eq!(Part::from_bytes(&[0, 0, 0]), Err(MultipartError::MalformedPart));
+    assert_eq!(Part::from_bytes(&[0, 0, 0, 0, 0b1000_0000]), Err(MultipartError::MalformedPart));
+    // Index has to be smaller than the total count
+    assert_eq!(Part::from_bytes(&[0, 0, 0, 0, 2, 2]), Err(MultipartError::MalformedPart));
+}
+
+#[test]
+fn should_split_payload_into_chunks() {
+    let parts = split_payload(b"abcdefgh", 1, &[3, 3]);
+
+    let chunks = parts.iter().map(|part| part.data.as_slice()).collect::<Vec<&[u8]>>();
+    assert_eq!(chunks, vec![&b"abc"[..], b"def", b"gh"]);
+    assert!(parts.iter().all(|part| part.total == 3));
+    assert_eq!(split_payload(b"", 1, &[3]).len(), 1);
+}
+
+#[test]
+fn should_reassemble_parts_in_any_order() {
+    let mut parts = split_payload(b"Hello, panda!", 42, &[4, 4, 4]);
+    parts.swap(0, 3);
+    parts.push(parts[1].clone());
+
+    assert_eq!(reassemble(parts), Ok(b"Hello, panda!".to_vec()));
+}
+
+#[test]
+fn should_report_missing_parts() {
+    let parts = split_payload(b"Hello, panda!", 42, &[2, 2, 2, 2, 2]);
+
+    assert_eq!(
+        reassemble(vec![parts[0].clone(), parts[2].clone(), parts[5].clone()]),
+        Err(MultipartError::MissingParts {
+            message_id: 42,
+            missing: vec![2, 4, 5],
+            total: 6
+        })
+    );
+    assert_eq!(reassemble(vec![]), Err(MultipartError::NoParts));
+}
+
+#[test]
+fn should_not_mix_parts_of_different_messages() {
+    let mut parts = split_payload(b"Hello", 1, &[3]);
+    parts[1].message_id = 2;
+
+    assert_eq!(
+        reassemble(parts),
+        Err(MultipartError::MixedMessages { first: 1, second: 2 })
+    );
+}
+
+#[test]
+fn should_conceal_in_as_many_covers_as_needed() {
+    let capacities = [8, 10, 100, 100];
+    let payload = (0..20).collect::<Vec<u8>>();
+
+    let concealed: Result<Vec<Vec<u8>>, MultipartError> =
+        conceal_in_parts(&payload, 9, capacities.len(), |cover_index, part| {
+            let capacity = capacities[cover_index];
+            Ok(if part.len() <= capacity {
+                Attempt::Concealed(part.to_vec())
+            } else {
+                Attempt::TooSmall {
+                    remaining_bits: (part.len() - capacity) * 8,
+                }
+            })
+        });
+
+    let concealed = concealed.unwrap();
+    assert_eq!(concealed.len(), 3);
+    assert!(concealed
+        .iter()
+        .zip(capacities.iter())
+        .all(|(part, capacity)| part.len() <= *capacity));
+    let parts = concealed
+        .iter()
+        .map(|part| Part::from_bytes(part).unwrap())
+        .collect::<Vec<Part>>();
+    assert_eq!(reassemble(parts), Ok(payload));
+}
+
+#[test]
+fn should_fail_when_covers_are_too_small() {
+    // Every cover holds 6 bytes of the part header and 4 bytes of the payload
+    let concealed: Result<Vec<Vec<u8>>, MultipartError> =
+        conceal_in_parts(&[1; 20], 9, 2, |_, part| {
+            Ok(if part.len() <= 10 {
+                Attempt::Concealed(part.to_vec())
+            } else {
+                Attempt::TooSmall {
+                    remaining_bits: (part.len() - 10) * 8,
+                }
+            })
+        });
+
+    assert_eq!(
+        concealed,
+        Err(MultipartError::CoversTooSmall {
+            remaining_data_size: 12
+        })
+    );
+}
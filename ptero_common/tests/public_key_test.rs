@@ -0,0 +1,79 @@
+use ptero_common::encryption::EncryptionError;
+use ptero_common::public_key::{
+    open_with, seal_to, Identity, KeyError, Recipient, IDENTITY_PREFIX, RECIPIENT_PREFIX,
+};
+
+#[test]
+fn should_write_and_read_keys() {
+    let identity = Identity::generate();
+    let recipient = identity.recipient();
+
+    assert!(recipient.to_string().starts_with(RECIPIENT_PREFIX));
+    assert!(identity.to_string().starts_with(IDENTITY_PREFIX));
+    assert_eq!(recipient.to_string().parse::<Recipient>(), Ok(recipient));
+    assert_eq!(
+        identity.to_string().parse::<Identity>(),
+        Ok(identity.clone())
+    );
+    assert_eq!(
+        Identity::from_key_file(&identity.to_key_file()),
+        Ok(identity)
+    );
+}
+
+#[test]
+fn should_not_show_identity_in_debug_output() {
+    let identity = Identity::generate();
+    let hex = identity.to_string()[IDENTITY_PREFIX.len()..].to_string();
+
+    assert!(!format!("{:?}", identity).contains(&hex));
+}
+
+#[test]
+fn should_open_payload_only_with_recipients_identity() {
+    let identity = Identity::generate();
+    let sealed = seal_to(b"panda", &identity.recipient());
+
+    assert_ne!(sealed, seal_to(b"panda", &identity.recipient()));
+    assert_eq!(open_with(&sealed, &identity), Ok(b"panda".to_vec()));
+    assert_eq!(
+        open_with(&sealed, &Identity::generate()),
+        Err(EncryptionError::AuthenticationFailed)
+    );
+    assert_eq!(
+        open_with(&sealed[..10], &identity),
+        Err(EncryptionError::TooShort { length: 10 })
+    );
+}
+
+#[test]
+fn should_fail_when_keys_are_malformed() {
+    assert_eq!(
+        "ptero-pk-1234".parse::<Recipient>(),
+        Err(KeyError::MalformedRecipient)
+    );
+    assert_eq!(
+        format!("ptero-sk-{}", "0".repeat(64)).parse::<Recipient>(),
+        Err(KeyError::MalformedRecipient)
+    );
+    assert_eq!(
+        format!("ptero-sk-{}", "zz".repeat(32)).parse::<Identity>(),
+        Err(KeyError::MalformedIdentity)
+    );
+    assert_eq!(
+        Identity::from_key_file("# Public key only\n"),
+        Err(KeyError::MalformedIdentity)
+    );
+}
+
+#[test]
+fn should_reject_low_order_recipient() {
+    assert_eq!(
+        format!("ptero-pk-{}", "0".repeat(64)).parse::<Recipient>(),
+        Err(KeyError::WeakRecipient)
+    );
+    assert_eq!(
+        format!("ptero-pk-01{}", "0".repeat(62)).parse::<Recipient>(),
+        Err(KeyError::WeakRecipient)
+    );
+}
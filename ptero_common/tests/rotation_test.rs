@@ -0,0 +1,43 @@
+use ptero_common::rotation::{VariantRotation, PERMUTATIONS};
+
+#[test]
+fn should_draw_orders_deterministically_from_the_secret() {
+    let orders = VariantRotation::new("bamboo")
+        .take(64)
+        .collect::<Vec<[usize; 3]>>();
+
+    assert_eq!(
+        VariantRotation::new(b"bamboo")
+            .take(64)
+            .collect::<Vec<[usize; 3]>>(),
+        orders
+    );
+    assert_ne!(
+        VariantRotation::new(b"Bamboo")
+            .take(64)
+            .collect::<Vec<[usize; 3]>>(),
+        orders
+    );
+}
+
+#[test]
+fn should_draw_every_permutation() {
+    let orders = VariantRotation::new(b"bamboo")
+        .take(256)
+        .collect::<Vec<[usize; 3]>>();
+
+    for permutation in &PERMUTATIONS {
+        assert!(orders.contains(permutation));
+    }
+}
+
+#[test]
+fn should_start_over_when_cloned() {
+    let mut rotation = VariantRotation::new(b"bamboo");
+    let fresh = rotation.clone();
+
+    let first = rotation.by_ref().take(32).collect::<Vec<[usize; 3]>>();
+
+    assert_eq!(fresh.take(32).collect::<Vec<[usize; 3]>>(), first);
+    assert_ne!(rotation.take(32).collect::<Vec<[usize; 3]>>(), first);
+}
@@ -0,0 +1,128 @@
+use ptero_common::selection::{
+    LineSelector, SelectionError, Spread, SpreadMode, MAX_STRIDE, POOL_COUNT,
+};
+
+#[test]
+fn should_pick_lines_deterministically_from_the_secret() {
+    let picked = LineSelector::new("bamboo").take(64).collect::<Vec<bool>>();
+
+    assert_eq!(
+        LineSelector::new(b"bamboo").take(64).collect::<Vec<bool>>(),
+        picked
+    );
+    assert_ne!(
+        LineSelector::new(b"Bamboo").take(64).collect::<Vec<bool>>(),
+        picked
+    );
+}
+
+#[test]
+fn should_pick_and_skip_lines() {
+    let picked = LineSelector::new(b"bamboo")
+        .take(256)
+        .filter(|is_selected| *is_selected)
+        .count();
+
+    assert!(picked > 64);
+    assert!(picked < 192);
+}
+
+#[test]
+fn should_start_over_when_cloned() {
+    let mut selector = LineSelector::new(b"bamboo");
+    let fresh = selector.clone();
+
+    let first = selector.by_ref().take(32).collect::<Vec<bool>>();
+
+    assert_eq!(fresh.take(32).collect::<Vec<bool>>(), first);
+    assert_ne!(selector.take(32).collect::<Vec<bool>>(), first);
+}
+
+#[test]
+fn should_fit_the_stride_to_the_cover() {
+    assert_eq!(Spread::fitting(SpreadMode::Uniform, 10, 35).stride, 3);
+    assert_eq!(Spread::fitting(SpreadMode::Uniform, 10, 5).stride, 1);
+    assert_eq!(
+        Spread::fitting(SpreadMode::Random, 1, 1000).stride,
+        MAX_STRIDE
+    );
+    assert_eq!(
+        Spread::fitting(SpreadMode::Random, 0, 10).stride,
+        MAX_STRIDE
+    );
+}
+
+#[test]
+fn should_serialize_and_restore_spread() {
+    let uniform = Spread {
+        mode: SpreadMode::Uniform,
+        stride: 5,
+    };
+    let random = Spread {
+        mode: SpreadMode::Random,
+        stride: MAX_STRIDE,
+    };
+
+    assert_eq!(uniform.to_byte(), 5);
+    assert_eq!(random.to_byte(), 0xff);
+    assert_eq!(Spread::from_byte(5), Ok(uniform));
+    assert_eq!(Spread::from_byte(0xff), Ok(random));
+    assert_eq!(
+        Spread::from_byte(0x80),
+        Err(SelectionError::InvalidSpread { byte: 0x80 })
+    );
+}
+
+#[test]
+fn should_pick_one_line_of_every_stride() {
+    let spread = Spread {
+        mode: SpreadMode::Random,
+        stride: 4,
+    };
+    let picked = LineSelector::spread(spread).take(40).collect::<Vec<bool>>();
+
+    assert!(picked.chunks(4).all(|stride| stride
+        .iter()
+        .filter(|is_selected| **is_selected)
+        .count()
+        == 1));
+    assert_ne!(
+        picked,
+        LineSelector::spread(Spread {
+            mode: SpreadMode::Uniform,
+            ..spread
+        })
+        .take(40)
+        .collect::<Vec<bool>>()
+    );
+    assert_eq!(
+        LineSelector::spread(spread).take(40).collect::<Vec<bool>>(),
+        picked
+    );
+}
+
+#[test]
+fn should_deal_every_line_into_exactly_one_pool() {
+    let pools = (0..POOL_COUNT)
+        .map(|pool| LineSelector::pool(42, pool).take(64).collect::<Vec<bool>>())
+        .collect::<Vec<Vec<bool>>>();
+
+    for line in 0..64 {
+        assert_eq!(pools.iter().filter(|pool| pool[line]).count(), 1);
+    }
+    assert!(pools.iter().all(|pool| pool.contains(&true)));
+}
+
+#[test]
+fn should_deal_lines_by_the_seed() {
+    let pool = LineSelector::pool(42, 0).take(64).collect::<Vec<bool>>();
+
+    assert_eq!(
+        LineSelector::pool(42, 0).take(64).collect::<Vec<bool>>(),
+        pool
+    );
+    assert_ne!(
+        LineSelector::pool(7, 0).take(64).collect::<Vec<bool>>(),
+        pool
+    );
+}
@@ -1,2 +1,20 @@
 #[cfg(test)]
-mod observer_test;
\ No newline at end of file
+mod compression_test;
+#[cfg(test)]
+mod config_test;
+#[cfg(test)]
+mod ecc_test;
+#[cfg(test)]
+mod encryption_test;
+#[cfg(test)]
+mod frame_test;
+#[cfg(test)]
+mod multipart_test;
+#[cfg(test)]
+mod observer_test;
+#[cfg(test)]
+mod public_key_test;
+#[cfg(test)]
+mod rotation_test;
+#[cfg(test)]
+mod selection_test;
@@ -18,7 +18,8 @@
 //! use ptero_text::line_separator::LineSeparatorType;
 //!
 //! let rng = StdRng::seed_from_u64(1337);
-//! let cover_text = "This is a sample text that is harmless";
+//! let cover_text = "This is a sample text that is harmless, it tells a story \
+//!     about a panda that fell from a tree and then went rolling down the hill";
 //! let mut ext_line_method = ExtendedLineMethod::builder()
 //!     .with_rng(rng)
 //!     .with_pivot(11)
@@ -30,7 +31,11 @@
 //! let mut data_bit_iter = data.as_bits::<Msb0>().iter();
 //!
 //! if let Ok(stego_text) = ext_line_method.try_conceal(cover_text, &mut data_bit_iter) {
-//!     assert_eq!(&stego_text, "This  is a\nsample text \nthat  is\nharmless");
+//!     assert_eq!(
+//!         &stego_text,
+//!         "This is a\nsample text\nthat is\nharmless,\nit tells a\nstory about a \npanda that\n\
+//!          fell from a tree \nand then\nwent\nrolling\ndown the\nhill"
+//!     );
 //! } else {
 //!     panic!("Something went wrong");
 //! }
@@ -50,7 +55,8 @@
 //!
 //! // The RNG must be seeded with the same value as used when concealing
 //! let rng = StdRng::seed_from_u64(1337);
-//! let stego_text = "This  is a\nsample text \nthat  is\nharmless";
+//! let stego_text = "This is a\nsample text\nthat is\nharmless,\nit tells a\nstory about a \npanda that\n\
+//!     fell from a tree \nand then\nwent\nrolling\ndown the\nhill";
 //! let mut ext_line_method = ExtendedLineMethod::builder()
 //!     .with_rng(rng)
 //!     .with_pivot(11)
@@ -59,9 +65,8 @@
 //!     .unwrap();
 //!
 //! if let Ok(bits) = ext_line_method.try_reveal::<Msb0, u8>(stego_text) {
-//!     // Most often you'll get your data with trailing noise (probably zeroes)
-//!     // If you know the payload's size, you can trim it
-//!     let output = String::from_utf8_lossy(&bits.as_raw_slice()[0..1]);
+//!     // The payload is framed with its length, so you get exactly the concealed data
+//!     let output = String::from_utf8_lossy(bits.as_raw_slice());
 //!     assert_eq!(&output, "E");
 //! }
 //! ```
@@ -105,7 +110,8 @@
 //!
 //! // The RNG must be seeded with the same value as used when concealing
 //! let rng = StdRng::seed_from_u64(1337);
-//! let cover_text = "This is a sample text that is harmless";
+//! let cover_text = "This is a sample text that is harmless, it tells a story \
+//!     about a panda that fell from a tree and then went rolling down the hill";
 //! let mut ext_line_method = ExtendedLineMethod::builder()
 //!     .with_rng(rng)
 //!     .with_pivot(11)
@@ -122,12 +128,12 @@
 //! ext_line_method.try_conceal(cover_text, &mut data_bit_iter);
 //!
 //! assert_eq!(listener_arc.borrow().has_finished, true);
-//! assert_eq!(listener_arc.borrow().amount_written, 8);
+//! // Payload frame header is concealed as well
+//! assert_eq!(listener_arc.borrow().amount_written, 24);
 //! ```
 //! # Description
 //! TBD
 use std::cell::RefCell;
-use std::error::Error;
 use std::fmt::{Display, Formatter};
 use std::iter::Peekable;
 use std::rc::Rc;
@@ -136,14 +142,20 @@ use std::sync::Arc;
 use bitvec::prelude::*;
 use bitvec::slice::Iter;
 use rand::RngCore;
-use snafu::Snafu;
+use snafu::{ResultExt, Snafu};
 use unicode_segmentation::UnicodeSegmentation;
 
-use ptero_common::config::{
-    CommonMethodConfig, CommonMethodConfigBuilder,
+pub use ptero_common::config::BuilderError;
+use ptero_common::config::{CommonMethodConfig, CommonMethodConfigBuilder, ConfigurableBuilder};
+use ptero_common::method::{
+    InvalidPayload, MethodError, MethodProgressStatus, MethodResult, SteganographyMethod,
 };
-use ptero_common::method::{MethodProgressStatus, MethodResult, SteganographyMethod};
+use ptero_common::multipart::{conceal_in_parts, reassemble, Attempt, MultipartError, Part};
 use ptero_common::observer::{Observable, Observer};
+use ptero_common::rotation::VariantRotation;
+use ptero_common::selection::{
+    LineSelector, SelectionError, Spread, SpreadMode, SPREAD_DESCRIPTOR_LENGTH,
+};
 
 use crate::extended_line_method::character_sets::GetCharacterSet;
 use crate::line_separator::{DEFAULT_LINE_SEPARATOR, LineSeparatorType};
@@ -159,11 +171,11 @@ use self::trailing_whitespace_method::{
 };
 
 pub mod character_sets;
-mod line_extend_method;
+pub(crate) mod line_extend_method;
 mod random_whitespace_method;
 mod trailing_whitespace_method;
 
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy)]
 pub(crate) enum MethodActions {
     LineExtend,
     RandomASCIIWhitespace,
@@ -188,6 +200,18 @@ pub enum Variant {
     V3,
 }
 
+/// Describes how the Random Whitespace submethod conceals the data.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum RandomWhitespaceMode {
+    /// A single bit per line - a double space in a random gap between the words or none at all.
+    Single,
+    /// Index of the gap with the double space, counted from 1, or 0 for no double space at all.
+    ///
+    /// Line with `k` gaps between the words carries `floor(log2(k + 1))` bits,
+    /// so lines with a single word carry none.
+    GapIndex,
+}
+
 pub struct ExtendedLineMethodBuilder {
     rw_submethod_builder: RandomWhitespaceMethodBuilder,
     tw_submethod_builder: TrailingWhitespaceMethodBuilder,
@@ -195,6 +219,8 @@ pub struct ExtendedLineMethodBuilder {
     config_builder: CommonMethodConfigBuilder,
     line_separator_type: LineSeparatorType,
     variant: Variant,
+    variant_rotation: Option<VariantRotation>,
+    line_selection: LineSelection,
 }
 
 impl<'a> Default for ExtendedLineMethodBuilder {
@@ -206,6 +232,8 @@ impl<'a> Default for ExtendedLineMethodBuilder {
             config_builder: CommonMethodConfig::builder(),
             line_separator_type: DEFAULT_LINE_SEPARATOR,
             variant: Variant::V1,
+            variant_rotation: None,
+            line_selection: LineSelection::All,
         }
     }
 }
@@ -220,10 +248,19 @@ impl ExtendedLineMethodBuilder {
         self
     }
 
+    /// Derives the RNG from the shared key and salt, so every random choice is reproducible.
+    /// See [`CommonMethodConfigBuilder::with_key`].
+    pub fn with_key(mut self, passphrase: impl AsRef<[u8]>, salt: impl AsRef<[u8]>) -> Self {
+        self.config_builder = self.config_builder.with_key(passphrase, salt);
+        self
+    }
+
     /// Sets custom character set to be used when triggering Trailing Whitespace submethod.
     ///
     /// Possible values are listed in [`CharacterSetType`]. You can implement your custom type
-    /// as long as it extends [`GetCharacterSet`].
+    /// as long as it extends [`GetCharacterSet`]. Use
+    /// [`KeyedCharacterSet`](character_sets::KeyedCharacterSet) to shuffle the characters
+    /// of a set with the permutation derived from a shared key.
     ///
     /// By manipulating this value, you can increase bitrate of the method, maximum being 7 bits
     /// per cycle.
@@ -241,17 +278,68 @@ impl ExtendedLineMethodBuilder {
         self
     }
 
+    /// Draws the order of the submethods for every line from all their permutations,
+    /// with the PRNG seeded from given secret. Replaces the fixed order of [`Self::with_variant`].
+    ///
+    /// The order decides which submethod takes the data bits first, see [`ptero_common::rotation`].
+    /// Lines are modified in the order of [`Variant::V3`], so every permutation can be revealed.
+    /// Revealing requires the same secret, but no variant.
+    pub fn with_variant_rotation_key(mut self, secret: impl AsRef<[u8]>) -> Self {
+        self.variant_rotation = Some(VariantRotation::new(secret));
+        self
+    }
+
+    /// Sets how the Random Whitespace submethod conceals the data
+    /// ([`RandomWhitespaceMode::Single`] by default).
+    ///
+    /// Revealing requires the same mode.
+    pub fn with_random_whitespace_mode(mut self, mode: RandomWhitespaceMode) -> Self {
+        self.rw_submethod_builder = self.rw_submethod_builder.with_mode(mode);
+        self
+    }
+
     /// Set pivot
     pub fn with_pivot(mut self, pivot: usize) -> Self {
         self.le_submethod_builder.with_pivot(pivot);
         self
     }
 
+    /// Sets the maximal amount of words extending the line beyond the pivot (1 by default).
+    ///
+    /// The amount of the extra words carries `floor(log2(max_overflow + 1))` bits, e.g. up to
+    /// 3 words carry 2 bits. The lines get longer and the cover is used up faster,
+    /// but no characters which the channels might strip are needed. Revealing requires the same value.
+    pub fn with_max_line_overflow(mut self, max_overflow: usize) -> Self {
+        self.le_submethod_builder.with_max_overflow(max_overflow);
+        self
+    }
+
     pub fn with_line_separator(mut self, line_sep_type: LineSeparatorType) -> Self {
         self.line_separator_type = line_sep_type;
         self
     }
 
+    /// Conceals the data only in the lines picked by the PRNG seeded from given secret.
+    ///
+    /// By default the data is concealed in every line from the top until it runs out.
+    /// With the secret, other lines are left untouched as decoys, see [`ptero_common::selection`].
+    /// Revealing requires the same secret. Lowers the capacity of the cover about twice.
+    pub fn with_line_selection_key(mut self, secret: impl AsRef<[u8]>) -> Self {
+        self.line_selection = LineSelection::Keyed(Box::new(LineSelector::new(secret)));
+        self
+    }
+
+    /// Spaces the lines carrying the data evenly or pseudo-randomly through the whole cover.
+    ///
+    /// The distance between the lines is worked out from the size of the payload and the amount
+    /// of lines in the cover, see [`Spread`]. It's concealed in the first
+    /// [`SPREAD_DESCRIPTOR_LENGTH`] lines (a trailing space for every set bit), so revealing only
+    /// needs the spread enabled, with any mode. Replaces [`Self::with_line_selection_key`].
+    pub fn with_line_spread(mut self, mode: SpreadMode) -> Self {
+        self.line_selection = LineSelection::Spread(mode);
+        self
+    }
+
     /// Constructs the method
     ///
     /// # Examples
@@ -292,9 +380,7 @@ impl ExtendedLineMethodBuilder {
         let config = self
             .config_builder
             .build()
-            .map_err(|source| {
-                BuilderError { source: source.into() }
-            })?;
+            .map_err(|source| BuilderError::new("ExtendedLineMethod", source))?;
 
         let config_rc = Rc::new(RefCell::new(config));
 
@@ -305,20 +391,22 @@ impl ExtendedLineMethodBuilder {
                 .with_shared_config(config_rc.clone())
                 .with_line_separator(self.line_separator_type)
                 .build()
-                .map_err(|source| BuilderError { source: source.into() })?,
+                .map_err(|source| BuilderError::new("ExtendedLineMethod", source))?,
             tw_submethod: self
                 .tw_submethod_builder
                 .with_shared_config(config_rc.clone())
                 .build()
-                .map_err(|source| BuilderError { source: source.into() })?,
+                .map_err(|source| BuilderError::new("ExtendedLineMethod", source))?,
             le_submethod: self
                 .le_submethod_builder
                 .with_shared_config(config_rc.clone())
                 .build()
-                .map_err(|source| BuilderError { source: source.into() })?,
+                .map_err(|source| BuilderError::new("ExtendedLineMethod", source))?,
             config: config_rc,
             line_separator_type: self.line_separator_type,
             variant: self.variant,
+            variant_rotation: self.variant_rotation,
+            line_selection: self.line_selection,
         })
     }
 }
@@ -343,26 +431,67 @@ pub(crate) fn get_variant_methods(variant: &Variant) -> &'static [MethodActions;
     }
 }
 
-#[derive(Debug, Snafu)]
-#[snafu(display("Couldn't finish building ExtendedLineMethod: {}", source))]
-pub struct BuilderError {
-    source: Box<dyn Error>
+/// Submethods indexed by the orders of [`VariantRotation`], in the order they modify the line.
+///
+/// It's the order of [`Variant::V3`] - bitrates of all the submethods are known from
+/// the pivot line, before any of them modifies it.
+const ROTATED_METHODS: [MethodActions; 3] = [
+    MethodActions::RandomASCIIWhitespace,
+    MethodActions::LineExtend,
+    MethodActions::TrailingASCIIWhitespace,
+];
+
+impl ConfigurableBuilder for ExtendedLineMethodBuilder {
+    fn map_config_builder<F>(mut self, update: F) -> Self
+    where
+        F: FnOnce(CommonMethodConfigBuilder) -> CommonMethodConfigBuilder,
+    {
+        self.config_builder = update(self.config_builder);
+        self
+    }
 }
 
-fn graphemes_length(text: &str) -> usize {
+pub(crate) fn graphemes_length(text: &str) -> usize {
     text.graphemes(true).count()
 }
 
+pub(crate) fn cover_words(cover: &str) -> Peekable<impl Iterator<Item = &str>> {
+    cover
+        .split_whitespace()
+        .filter(|word| !word.contains(char::is_whitespace))
+        .peekable()
+}
+
+fn is_next_line_selected(line_selector: &mut Option<LineSelector>) -> bool {
+    match line_selector {
+        Some(line_selector) => line_selector.is_next_selected(),
+        None => true,
+    }
+}
+
 pub type Result<Success> = std::result::Result<Success, ConcealError>;
 
+/// Decides which lines carry the data.
+#[derive(Debug, Clone)]
+enum LineSelection {
+    /// Every line from the top, until the data runs out.
+    All,
+    /// Lines picked by the PRNG seeded from the shared secret.
+    Keyed(Box<LineSelector>),
+    /// Lines spaced through the whole cover, the spacing is concealed before the data.
+    Spread(SpreadMode),
+}
+
 /// The main structure describing internal state for the Extended Line method.
 pub struct ExtendedLineMethod {
     variant: Variant,
+    variant_rotation: Option<VariantRotation>,
     config: Rc<RefCell<CommonMethodConfig>>,
     line_separator_type: LineSeparatorType,
     rw_submethod: RandomWhitespaceMethod,
     tw_submethod: TrailingWhitespaceMethod,
     le_submethod: LineExtendMethod,
+    line_selection: LineSelection,
 }
 
 impl ExtendedLineMethod {
@@ -410,15 +539,256 @@ impl ExtendedLineMethod {
         self.config.borrow_mut().notifier.subscribe(subscriber);
     }
 
+    /// Conceals the data in multiple cover texts, when a single one is too small.
+    ///
+    /// The payload is cut into parts (see [`ptero_common::multipart`]), each concealed in the next
+    /// cover text. Covers are filled as much as possible, so the result can have less stego texts
+    /// than there are covers. Every part is concealed a few times while looking for the size
+    /// that fits, which is reflected in the progress notifications.
+    /// Requires the framing to be enabled.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bitvec::prelude::*;
+    /// use rand::rngs::mock::StepRng;
+    /// use ptero_text::extended_line_method::ExtendedLineMethod;
+    ///
+    /// let cover = "A little panda has fallen from a tree. The panda went rolling down the hill. "
+    ///     .repeat(8);
+    /// let covers = [cover.as_str(); 4];
+    /// let mut method = ExtendedLineMethod::builder()
+    ///     .with_rng(StepRng::new(1, 1))
+    ///     .with_pivot(15)
+    ///     .build()
+    ///     .unwrap();
+    ///
+    /// let stego_texts = method
+    ///     .try_conceal_in_parts(&covers, &mut b"Meet me at the old bridge".view_bits::<Msb0>().iter())
+    ///     .unwrap();
+    /// assert!(stego_texts.len() > 1);
+    ///
+    /// let mut shuffled = stego_texts.iter().map(String::as_str).collect::<Vec<&str>>();
+    /// shuffled.reverse();
+    /// assert_eq!(method.try_reveal_parts(&shuffled).unwrap(), b"Meet me at the old bridge");
+    /// ```
+    pub fn try_conceal_in_parts<Order, Type>(
+        &mut self,
+        covers: &[&str],
+        data: &mut Iter<Order, Type>,
+    ) -> Result<Vec<String>>
+    where
+        Order: BitOrder,
+        Type: BitStore,
+    {
+        let mut bits = data.collect::<BitVec<Msb0, u8>>();
+        bits.resize(bits.len().div_ceil(8) * 8, false);
+        let message_id = self.config.borrow_mut().rng.next_u32();
+
+        conceal_in_parts(
+            bits.as_raw_slice(),
+            message_id,
+            covers.len(),
+            |cover_index, part| {
+                match self.try_conceal(covers[cover_index], &mut part.view_bits::<Msb0>().iter()) {
+                    Ok(stego_text) => Ok(Attempt::Concealed(stego_text)),
+                    Err(ConcealError::CoverTextTooSmall {
+                        remaining_data_size,
+                        ..
+                    }) => Ok(Attempt::TooSmall {
+                        remaining_bits: remaining_data_size,
+                    }),
+                    Err(error) => Err(error),
+                }
+            },
+        )
+    }
+
+    /// Reveals the data concealed with [`ExtendedLineMethod::try_conceal_in_parts`].
+    /// Stego texts can be given in any order.
+    ///
+    /// Fails with [`ConcealError::InvalidParts`] if any part is missing or the parts don't belong together.
+    pub fn try_reveal_parts(&mut self, stego_texts: &[&str]) -> Result<Vec<u8>> {
+        let mut parts = Vec::with_capacity(stego_texts.len());
+        for stego_text in stego_texts {
+            let revealed: BitVec<Msb0, u8> = self.try_reveal(stego_text)?;
+            parts.push(Part::from_bytes(revealed.as_raw_slice())?);
+        }
+        Ok(reassemble(parts)?)
+    }
+
+    /// Returns how many bits can be concealed in the cover text, including the payload frame.
+    ///
+    /// It's the sum of the bitrates of the pivot lines, assuming none of them gets extended.
+    /// When the line can be extended by multiple words (see [`ExtendedLineMethodBuilder::with_max_line_overflow`]),
+    /// every line is assumed to take half of the most words instead, which is what random data,
+    /// e.g. an encrypted payload, takes on average.
+    /// With [`RandomWhitespaceMode::GapIndex`], the bitrate depends on the words in the line.
+    ///
+    /// # Examples
+    /// ```
+    /// use rand::rngs::mock::StepRng;
+    /// use ptero_text::extended_line_method::{ExtendedLineMethod, RandomWhitespaceMode};
+    ///
+    /// let cover = "A little panda has fallen from a tree";
+    /// let method = ExtendedLineMethod::builder()
+    ///     .with_rng(StepRng::new(1, 1))
+    ///     .with_pivot(20)
+    ///     .build()
+    ///     .unwrap();
+    /// let gap_index_method = ExtendedLineMethod::builder()
+    ///     .with_rng(StepRng::new(1, 1))
+    ///     .with_pivot(20)
+    ///     .with_random_whitespace_mode(RandomWhitespaceMode::GapIndex)
+    ///     .build()
+    ///     .unwrap();
+    ///
+    /// // Both lines have 3 gaps between the words, which carry 2 bits instead of 1
+    /// assert_eq!(method.capacity(cover), 2 * 3);
+    /// assert_eq!(gap_index_method.capacity(cover), 2 * 4);
+    /// ```
+    pub fn capacity(&self, cover: &str) -> usize {
+        self.line_bitrates(cover).iter().sum()
+    }
+
     fn notify(&mut self, event: &MethodProgressStatus) {
         self.tw_submethod.notify(event);
     }
 
+    /// Amount of bits concealed in every pivot line of the cover by all the submethods.
+    fn line_bitrates(&self, cover: &str) -> Vec<usize> {
+        let mut word_iterator = cover_words(cover);
+        let mut bitrates = Vec::new();
+        let mut extension_words = 0;
+        loop {
+            let line = self.le_submethod.construct_pivot_line(&mut word_iterator);
+            if line.is_empty() {
+                break;
+            }
+            bitrates.push(
+                self.le_submethod.bitrate()
+                    + self.rw_submethod.bitrate(&line)
+                    + self.tw_submethod.bitrate(),
+            );
+            if self.le_submethod.bitrate() > 1 {
+                // Skips the words taken by the extension, half of the maximum on average
+                extension_words += self.le_submethod.max_extension_length();
+                word_iterator.by_ref().take(extension_words / 2).for_each(drop);
+                extension_words %= 2;
+            }
+        }
+        bitrates
+    }
+
+    /// Works out the widest spread of the lines which still lets the payload fit into the cover.
+    fn estimate_spread(&self, cover: &str, mode: SpreadMode, payload_size: usize) -> Spread {
+        let bitrates = self.line_bitrates(cover);
+        let line_count = bitrates.len();
+        // Lines carry different amount of bits, the average one is good enough for the estimate
+        let capacity = bitrates.iter().sum::<usize>().max(1);
+        Spread::fitting(
+            mode,
+            (payload_size * line_count).div_ceil(capacity),
+            line_count.saturating_sub(SPREAD_DESCRIPTOR_LENGTH),
+        )
+    }
+
+    /// Conceals the payload line by line, starting with the spread descriptor if given.
+    fn conceal_in_lines(
+        &mut self,
+        cover: &str,
+        payload: &BitSlice<Msb0, u8>,
+        spread: Option<Spread>,
+    ) -> Result<String> {
+        let separator = self.line_separator_type.separator();
+        let data = &mut payload.iter();
+
+        let mut result = String::with_capacity(cover.len());
+        let mut word_iterator = cover_words(cover);
+        let mut line_selector = match (&self.line_selection, spread) {
+            (_, Some(spread)) => {
+                self.conceal_spread_descriptor(
+                    &spread,
+                    &mut word_iterator,
+                    payload.len(),
+                    &mut result,
+                )?;
+                Some(LineSelector::spread(spread))
+            }
+            (LineSelection::Keyed(line_selector), None) => Some(*line_selector.clone()),
+            _ => None,
+        };
+
+        let mut variant_rotation = self.variant_rotation.clone();
+        while let MethodResult::Success = self.partial_conceal(
+            &mut word_iterator,
+            data,
+            &mut result,
+            is_next_line_selected(&mut line_selector),
+            variant_rotation.as_mut().map(VariantRotation::next_order),
+        )? {
+            result.push_str(separator);
+        }
+
+        loop {
+            let line = self.le_submethod.construct_pivot_line(&mut word_iterator);
+            if line.is_empty() {
+                break;
+            }
+            result.push_str(separator);
+            result.push_str(&line);
+        }
+
+        self.notify(&MethodProgressStatus::Finished);
+
+        Ok(result)
+    }
+
+    /// Conceals the spread descriptor in the next pivot lines, one bit per line.
+    /// Set bit is concealed as a trailing ASCII space.
+    fn conceal_spread_descriptor<'b, IteratorType>(
+        &self,
+        spread: &Spread,
+        word_iterator: &mut Peekable<IteratorType>,
+        payload_size: usize,
+        result: &mut String,
+    ) -> Result<()>
+    where
+        IteratorType: Iterator<Item = &'b str>,
+    {
+        let separator = self.line_separator_type.separator();
+        for bit in [spread.to_byte()].view_bits::<Msb0>() {
+            let line = self.le_submethod.construct_pivot_line(word_iterator);
+            if line.is_empty() {
+                return Err(ConcealError::no_cover_words_left(
+                    payload_size,
+                    self.le_submethod.get_pivot(),
+                ));
+            }
+            result.push_str(&line);
+            if *bit {
+                result.push(' ');
+            }
+            result.push_str(separator);
+        }
+        Ok(())
+    }
+
+    /// Reveals the spread descriptor from the first lines of the stego text.
+    fn reveal_spread_descriptor<'b>(lines: &mut impl Iterator<Item = &'b str>) -> Result<Spread> {
+        let byte = lines
+            .take(SPREAD_DESCRIPTOR_LENGTH)
+            .fold(0u8, |byte, line| (byte << 1) | line.ends_with(' ') as u8);
+        Ok(Spread::from_byte(byte)?)
+    }
+
     fn partial_conceal<'b, IteratorType, Order, Type>(
         &mut self,
         word_iterator: &mut Peekable<IteratorType>,
         data: &mut Iter<Order, Type>,
         result: &mut String,
+        is_selected: bool,
+        rotated_order: Option<[usize; 3]>,
     ) -> Result<MethodResult>
     where
         IteratorType: Iterator<Item = &'b str>,
@@ -436,22 +806,17 @@ impl ExtendedLineMethod {
                 pivot,
             ));
         }
+        if !is_selected {
+            // Line is left untouched as a decoy
+            return Ok(MethodResult::Success);
+        }
+        if let Some(order) = rotated_order {
+            return self.conceal_in_rotated_order(&pivot_line, order, word_iterator, data, result);
+        }
 
         for action in get_variant_methods(&self.variant) {
-            let method_result = match action {
-                MethodActions::LineExtend => self.le_submethod.conceal_in_extended_line(
-                    graphemes_length(&pivot_line),
-                    word_iterator,
-                    data,
-                    result,
-                ),
-                MethodActions::RandomASCIIWhitespace => {
-                    self.rw_submethod.conceal_in_random_whitespace(data, result)
-                }
-                MethodActions::TrailingASCIIWhitespace => Ok(self
-                    .tw_submethod
-                    .conceal_in_trailing_whitespace(data, result)),
-            };
+            let method_result =
+                self.conceal_with(*action, &pivot_line, word_iterator, data, result);
 
             if let MethodResult::NoDataLeft = method_result? {
                 return Ok(MethodResult::NoDataLeft);
@@ -460,34 +825,143 @@ impl ExtendedLineMethod {
         Ok(MethodResult::Success)
     }
 
-    fn partial_reveal<Order, Type>(&mut self, line: &str, revealed_data: &mut BitVec<Order, Type>)
+    /// Conceals the data with the submethods taking the bits in given order.
+    /// Every submethod gets its bits up front and then they modify the line one by one,
+    /// in the order of [`ROTATED_METHODS`].
+    fn conceal_in_rotated_order<'b, IteratorType, Order, Type>(
+        &mut self,
+        pivot_line: &str,
+        order: [usize; 3],
+        word_iterator: &mut Peekable<IteratorType>,
+        data: &mut Iter<Order, Type>,
+        result: &mut String,
+    ) -> Result<MethodResult>
+    where
+        IteratorType: Iterator<Item = &'b str>,
+        Order: BitOrder,
+        Type: BitStore,
+    {
+        let bitrates = [
+            self.rw_submethod.bitrate(pivot_line),
+            self.le_submethod.bitrate(),
+            self.tw_submethod.bitrate(),
+        ];
+        let mut method_bits: [BitVec<Msb0, u8>; 3] = Default::default();
+        for index in order {
+            method_bits[index] = data.by_ref().take(bitrates[index]).collect();
+        }
+
+        for (action, bits) in ROTATED_METHODS.iter().zip(&method_bits) {
+            self.conceal_with(*action, pivot_line, word_iterator, &mut bits.iter(), result)?;
+        }
+        let is_line_filled = method_bits
+            .iter()
+            .zip(bitrates)
+            .all(|(bits, bitrate)| bits.len() == bitrate);
+        Ok(if is_line_filled {
+            MethodResult::Success
+        } else {
+            MethodResult::NoDataLeft
+        })
+    }
+
+    fn conceal_with<'b, IteratorType, Order, Type>(
+        &mut self,
+        action: MethodActions,
+        pivot_line: &str,
+        word_iterator: &mut Peekable<IteratorType>,
+        data: &mut Iter<Order, Type>,
+        result: &mut String,
+    ) -> Result<MethodResult>
     where
+        IteratorType: Iterator<Item = &'b str>,
+        Order: BitOrder,
+        Type: BitStore,
+    {
+        match action {
+            MethodActions::LineExtend => self.le_submethod.conceal_in_extended_line(
+                graphemes_length(pivot_line),
+                word_iterator,
+                data,
+                result,
+            ),
+            MethodActions::RandomASCIIWhitespace => self
+                .rw_submethod
+                .conceal_in_random_whitespace(data, result)
+                .map_err(|error| match error {
+                    // Single word lines appear when the extension takes the words of the next line
+                    ConcealError::NotEnoughWordsOnPivotLine { .. }
+                        if self.le_submethod.bitrate() > 1 =>
+                    {
+                        ConcealError::single_word_line(data.count(), self.le_submethod.get_pivot())
+                    }
+                    error => error,
+                }),
+            MethodActions::TrailingASCIIWhitespace => Ok(self
+                .tw_submethod
+                .conceal_in_trailing_whitespace(data, result)),
+        }
+    }
+
+    fn partial_reveal<Order, Type>(
+        &mut self,
+        line: &str,
+        rotated_order: Option<[usize; 3]>,
+        revealed_data: &mut BitVec<Order, Type>,
+    ) where
         Order: BitOrder,
         Type: BitStore,
     {
-        let actions = get_variant_methods(&self.variant);
         let mut current_line = line.to_string();
+        if let Some(order) = rotated_order {
+            // Submethods are revealed in reverse, so bits of each of them are gathered separately
+            let mut method_bits: [BitVec<Order, Type>; 3] = Default::default();
+            for (action, bits) in ROTATED_METHODS.iter().zip(&mut method_bits).rev() {
+                self.reveal_with(*action, &mut current_line, bits);
+                bits.reverse();
+            }
+            for index in order {
+                revealed_data.append(&mut method_bits[index]);
+            }
+            return;
+        }
 
         let mut gathered_bits: BitVec<Order, Type> = BitVec::with_capacity(3);
-        for action in actions.iter().rev() {
-            match action {
-                MethodActions::LineExtend => {
-                    self.le_submethod
-                        .reveal_in_extended_line(&current_line, &mut gathered_bits);
-                }
-                MethodActions::RandomASCIIWhitespace => {
-                    self.rw_submethod
-                        .reveal_in_random_whitespace(&mut current_line, &mut gathered_bits);
-                }
-                MethodActions::TrailingASCIIWhitespace => {
-                    self.tw_submethod
-                        .reveal_in_trailing_whitespace(&mut current_line, &mut gathered_bits);
-                }
-            };
+        for action in get_variant_methods(&self.variant).iter().rev() {
+            self.reveal_with(*action, &mut current_line, &mut gathered_bits);
         }
         gathered_bits.reverse();
         revealed_data.append(&mut gathered_bits);
     }
+
+    fn reveal_with<Order, Type>(
+        &mut self,
+        action: MethodActions,
+        current_line: &mut String,
+        gathered_bits: &mut BitVec<Order, Type>,
+    ) where
+        Order: BitOrder,
+        Type: BitStore,
+    {
+        match action {
+            MethodActions::LineExtend => {
+                self.le_submethod
+                    .reveal_in_extended_line(current_line, gathered_bits);
+                // Gap index depends on the words in the line at the time it was concealed
+                if self.rw_submethod.mode() == RandomWhitespaceMode::GapIndex {
+                    self.le_submethod.remove_extension(current_line);
+                }
+            }
+            MethodActions::RandomASCIIWhitespace => {
+                self.rw_submethod
+                    .reveal_in_random_whitespace(current_line, gathered_bits);
+            }
+            MethodActions::TrailingASCIIWhitespace => {
+                self.tw_submethod
+                    .reveal_in_trailing_whitespace(current_line, gathered_bits);
+            }
+        };
+    }
 }
 
 impl<'a> SteganographyMethod<&'a str, ConcealError> for ExtendedLineMethod {
@@ -503,33 +977,23 @@ impl<'a> SteganographyMethod<&'a str, ConcealError> for ExtendedLineMethod {
         Type: BitStore,
     {
         self.le_submethod.verify_pivot(cover)?;
-        let separator = self.line_separator_type.separator();
-
-        let mut result = String::with_capacity(cover.len());
-
-        let mut word_iterator = cover
-            .split_whitespace()
-            .filter(|word| !word.contains(char::is_whitespace))
-            .peekable();
-
-        while let MethodResult::Success =
-            self.partial_conceal(&mut word_iterator, data, &mut result)?
-        {
-            result.push_str(separator);
-        }
+        let payload = self.config.borrow().wrap_payload(data);
 
-        loop {
-            let line = self.le_submethod.construct_pivot_line(&mut word_iterator);
-            if line.is_empty() {
-                break;
+        let mode = match self.line_selection {
+            LineSelection::Spread(mode) => mode,
+            _ => return self.conceal_in_lines(cover, &payload, None),
+        };
+        // Line extension can take words of the following lines, so the estimate may be too wide.
+        // Narrower spread also moves the carrying lines, which may skip the ones with a single word
+        let widest = self.estimate_spread(cover, mode, payload.len());
+        for stride in (2..=widest.stride).rev() {
+            match self.conceal_in_lines(cover, &payload, Some(Spread { mode, stride })) {
+                Err(ConcealError::CoverTextTooSmall { .. })
+                | Err(ConcealError::NotEnoughWordsOnPivotLine { .. }) => continue,
+                result => return result,
             }
-            result.push_str(separator);
-            result.push_str(&line);
         }
-
-        self.notify(&MethodProgressStatus::Finished);
-
-        Ok(result)
+        self.conceal_in_lines(cover, &payload, Some(Spread { mode, stride: 1 }))
     }
 
     fn try_reveal<Order, Type>(&mut self, stego_text: &str) -> Result<BitVec<Order, Type>>
@@ -540,11 +1004,28 @@ impl<'a> SteganographyMethod<&'a str, ConcealError> for ExtendedLineMethod {
         let mut revealed_data: BitVec<Order, Type> = BitVec::new();
         let separator = self.line_separator_type.separator();
 
-        for line in stego_text.split(separator) {
-            self.partial_reveal(line, &mut revealed_data);
+        let mut lines = stego_text.split(separator);
+        let mut line_selector = match &self.line_selection {
+            LineSelection::All => None,
+            LineSelection::Keyed(line_selector) => Some(*line_selector.clone()),
+            LineSelection::Spread(_) => Some(LineSelector::spread(
+                Self::reveal_spread_descriptor(&mut lines)?,
+            )),
+        };
+
+        let mut variant_rotation = self.variant_rotation.clone();
+        for line in lines {
+            let rotated_order = variant_rotation.as_mut().map(VariantRotation::next_order);
+            if is_next_line_selected(&mut line_selector) {
+                self.partial_reveal(line, rotated_order, &mut revealed_data);
+            }
         }
 
-        Ok(revealed_data)
+        self.config
+            .borrow()
+            .unwrap_payload(revealed_data)
+            .context(InvalidPayload)
+            .context(Common)
     }
 }
 
@@ -576,6 +1057,15 @@ pub enum ConcealError {
     /// Adjusting pivot so that more than one word appears on line will mitigate issue.
     #[snafu(display("Line '{}' doesn't have enough words to conceal a bit", line))]
     NotEnoughWordsOnPivotLine { line: String },
+    /// Error shared by all the methods, see [`MethodError`].
+    #[snafu(display("{}", source))]
+    Common { source: MethodError },
+    /// Payload couldn't be split across the cover texts or the revealed parts don't form the payload.
+    #[snafu(display("Couldn't split or reassemble the payload: {}", source))]
+    InvalidParts { source: MultipartError },
+    /// Stego text doesn't start with a valid spread of the lines.
+    #[snafu(display("Couldn't read the spread of the lines: {}", source))]
+    InvalidSpread { source: SelectionError },
 }
 
 #[cfg(not(tarpaulin_include))]
@@ -600,6 +1090,14 @@ impl ConcealError {
         }
     }
 
+    pub fn single_word_line(remaining_data_size: usize, pivot: usize) -> ConcealError {
+        ConcealError::CoverTextTooSmall {
+            reason: CoverTooSmallErrorReason::SingleWordLineLeft,
+            remaining_data_size,
+            pivot,
+        }
+    }
+
     pub fn not_enough_words(line: &str) -> ConcealError {
         ConcealError::NotEnoughWordsOnPivotLine {
             line: line.to_string(),
@@ -607,6 +1105,18 @@ impl ConcealError {
     }
 }
 
+impl From<MultipartError> for ConcealError {
+    fn from(source: MultipartError) -> Self {
+        ConcealError::InvalidParts { source }
+    }
+}
+
+impl From<SelectionError> for ConcealError {
+    fn from(source: SelectionError) -> Self {
+        ConcealError::InvalidSpread { source }
+    }
+}
+
 /// Describes the [`MethodError::CoverTextTooSmall`] error with more context.
 #[derive(Debug, PartialEq)]
 pub enum CoverTooSmallErrorReason {
@@ -614,6 +1124,9 @@ pub enum CoverTooSmallErrorReason {
     NoCoverWordsLeft,
     /// Can occur when the last line constructed from cover emptied the cover, but method has to extend the line to hide a bit.
     ConstructedTooShortLine,
+    /// Can occur when the words taken by the multi-word line extension left a line with a single word,
+    /// which cannot conceal a bit in the random whitespace.
+    SingleWordLineLeft,
 }
 
 #[cfg(not(tarpaulin_include))]
@@ -629,6 +1142,9 @@ impl Display for CoverTooSmallErrorReason {
                     "Line constructed is too short to extend it above pivot length"
                 )
             }
+            CoverTooSmallErrorReason::SingleWordLineLeft => {
+                write!(f, "Line extension left a single word line, cannot conceal a bit in it")
+            }
         }
     }
 }
@@ -1,3 +1,5 @@
+pub use ptero_common::config::KeyedCharacterSet;
+
 /// This trait is used for reading unicode set data.
 ///
 /// New sets should implement `get_set` which provides the array with
@@ -127,6 +129,20 @@ pub const TWO_BIT_CHARACTER_SET: [char; 3] = ['\u{0020}', '\u{2000}', '\u{2001}'
 /// This is the base Extended Line algorithm behaviour.
 pub const ONE_BIT_CHARACTER_SET: [char; 1] = ['\u{0020}'];
 
+/// Set of zero-width characters - ZWSP, ZWNJ, ZWJ, WJ and invisible function application,
+/// times and separator. Meant to be put inside or between words, providing 3-bit encoding capacity.
+pub const ZERO_WIDTH_CHARACTER_SET: [char; 7] = [
+    '\u{200B}', '\u{200C}', '\u{200D}', '\u{2060}', '\u{2061}', '\u{2062}', '\u{2063}',
+];
+
+/// Set with the no-break space only. Meant to replace the spaces between words, providing 1-bit
+/// encoding capacity.
+pub const NO_BREAK_SPACE_CHARACTER_SET: [char; 1] = ['\u{00A0}'];
+
+/// Set of spaces looking like the regular one in most fonts - no-break, narrow no-break
+/// and thin space. Meant to replace the spaces between words, providing 2-bit encoding capacity.
+pub const NARROW_SPACE_CHARACTER_SET: [char; 3] = ['\u{00A0}', '\u{202F}', '\u{2009}'];
+
 /// Enum representing possible character sets e.g. [FULL_UNICODE_CHARACTER_SET].
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub enum CharacterSetType {
@@ -136,6 +152,9 @@ pub enum CharacterSetType {
     TwoBit,
     OneBit,
     Twitter,
+    ZeroWidth,
+    NoBreakSpace,
+    NarrowSpace,
 }
 
 impl GetCharacterSet for CharacterSetType {
@@ -153,6 +172,9 @@ impl GetCharacterSet for CharacterSetType {
     /// assert_eq!(CharacterSetType::TwoBit.get_set(), &TWO_BIT_CHARACTER_SET);
     /// assert_eq!(CharacterSetType::OneBit.get_set(), &ONE_BIT_CHARACTER_SET);
     /// assert_eq!(CharacterSetType::Twitter.get_set(), &TWITTER_UNICODE_CHARACTER_SET);
+    /// assert_eq!(CharacterSetType::ZeroWidth.get_set(), &ZERO_WIDTH_CHARACTER_SET);
+    /// assert_eq!(CharacterSetType::NoBreakSpace.get_set(), &NO_BREAK_SPACE_CHARACTER_SET);
+    /// assert_eq!(CharacterSetType::NarrowSpace.get_set(), &NARROW_SPACE_CHARACTER_SET);
     /// ```
     fn get_set(&self) -> &[char] {
         match *self {
@@ -162,6 +184,28 @@ impl GetCharacterSet for CharacterSetType {
             CharacterSetType::TwoBit => &TWO_BIT_CHARACTER_SET,
             CharacterSetType::OneBit => &ONE_BIT_CHARACTER_SET,
             CharacterSetType::Twitter => &TWITTER_UNICODE_CHARACTER_SET,
+            CharacterSetType::ZeroWidth => &ZERO_WIDTH_CHARACTER_SET,
+            CharacterSetType::NoBreakSpace => &NO_BREAK_SPACE_CHARACTER_SET,
+            CharacterSetType::NarrowSpace => &NARROW_SPACE_CHARACTER_SET,
         }
     }
 }
+
+/// Shuffles the characters of a set with the shared key.
+///
+/// # Examples
+/// ```
+/// use ptero_text::extended_line_method::character_sets::{
+///     CharacterSetType, GetCharacterSet, KeyedCharacterSet,
+/// };
+///
+/// let set = KeyedCharacterSet::new(CharacterSetType::Full.get_set(), "bamboo", "forest");
+///
+/// assert_eq!(set.size(), CharacterSetType::Full.size());
+/// assert_eq!(set.character_to_bits(set.get_character(5).unwrap()), 5);
+/// ```
+impl GetCharacterSet for KeyedCharacterSet {
+    fn get_set(&self) -> &[char] {
+        self.characters()
+    }
+}
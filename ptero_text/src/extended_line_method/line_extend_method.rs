@@ -13,15 +13,19 @@ use crate::extended_line_method::{ConcealError, graphemes_length, Result};
 
 const DEFAULT_ASCII_DELIMITER: &str = " ";
 const DEFAULT_PIVOT: usize = 20;
+const DEFAULT_MAX_OVERFLOW: usize = 1;
 
 pub(crate) type VerificationResult = std::result::Result<(), ConcealError>;
 
 #[derive(Builder)]
+#[builder(build_fn(validate = "Self::validate"))]
 pub struct LineExtendMethod {
     #[builder(private)]
     config_ref: Weak<RefCell<CommonMethodConfig>>,
     #[builder(setter(into, prefix = "with"), default = "DEFAULT_PIVOT")]
     pivot: usize,
+    #[builder(setter(into, prefix = "with"), default = "DEFAULT_MAX_OVERFLOW")]
+    max_overflow: usize,
 }
 
 impl LineExtendMethodBuilder {
@@ -29,6 +33,13 @@ impl LineExtendMethodBuilder {
         self.config_ref = Some(Rc::downgrade(&config));
         self
     }
+
+    fn validate(&self) -> std::result::Result<(), String> {
+        match self.max_overflow {
+            Some(0) => Err("line has to be allowed to overflow by at least one word".into()),
+            _ => Ok(()),
+        }
+    }
 }
 
 impl LineExtendMethod {
@@ -47,6 +58,16 @@ impl LineExtendMethod {
         config.borrow_mut().notifier.notify(event);
     }
 
+    /// Amount of bits concealed in the amount of words extending the line.
+    pub(crate) fn bitrate(&self) -> usize {
+        (usize::BITS - 1 - (self.max_overflow + 1).leading_zeros()) as usize
+    }
+
+    /// The most words appended to a single line by the extension.
+    pub(crate) fn max_extension_length(&self) -> usize {
+        (1 << self.bitrate()) - 1
+    }
+
     pub(crate) fn conceal_in_extended_line<'b, IteratorType, Order, Type>(
         &mut self,
         pivot_line_length: usize,
@@ -59,6 +80,9 @@ impl LineExtendMethod {
         Order: BitOrder,
         Type: BitStore,
     {
+        if self.bitrate() > 1 {
+            return self.conceal_in_extension_length(word_iter, data, result);
+        }
         Ok(match data.next().as_deref() {
             Some(true) => {
                 let next_word = word_iter.next().ok_or_else(|| {
@@ -94,6 +118,46 @@ impl LineExtendMethod {
         })
     }
 
+    /// Appends as many words as the number made of the next bits.
+    /// The first word always overflows the pivot, as the pivot line couldn't fit it.
+    fn conceal_in_extension_length<'b, IteratorType, Order, Type>(
+        &mut self,
+        word_iter: &mut Peekable<IteratorType>,
+        data: &mut Iter<Order, Type>,
+        result: &mut String,
+    ) -> Result<MethodResult>
+    where
+        IteratorType: Iterator<Item = &'b str>,
+        Order: BitOrder,
+        Type: BitStore,
+    {
+        let bitrate = self.bitrate();
+        let next_n_bits = data.take(bitrate).collect::<BitVec<Lsb0, usize>>();
+        if next_n_bits.is_empty() {
+            return Ok(MethodResult::NoDataLeft);
+        }
+        // We might end-up with lower amount of bits than suggested by bitrate,
+        // the missing high bits are revealed as zeroes following the data
+        let word_count = next_n_bits.as_raw_slice()[0];
+
+        trace!("Extending line with {} words", word_count);
+        for _ in 0..word_count {
+            let next_word = word_iter.next().ok_or_else(|| {
+                let remaining_data_size = data.count();
+                ConcealError::no_cover_words_left(remaining_data_size, self.pivot)
+            })?;
+            result.push_str(DEFAULT_ASCII_DELIMITER);
+            result.push_str(next_word);
+        }
+
+        if next_n_bits.len() < bitrate {
+            Ok(MethodResult::NoDataLeft)
+        } else {
+            self.notify(&MethodProgressStatus::DataWritten(bitrate as u64));
+            Ok(MethodResult::Success)
+        }
+    }
+
     pub(crate) fn reveal_in_extended_line<Order, Type>(
         &mut self,
         stego_text_line: &str,
@@ -102,14 +166,53 @@ impl LineExtendMethod {
         Order: BitOrder,
         Type: BitStore,
     {
+        if self.bitrate() > 1 {
+            let word_count = self.extension_word_count(stego_text_line);
+            trace!("Found line extended with {} words", word_count);
+
+            let data: &BitSlice<Msb0, usize> = BitSlice::from_element(&word_count);
+            let data_length = data.len();
+            revealed_data.extend(data.iter().by_val().skip(data_length - self.bitrate()));
+            return;
+        }
+        let bit = self.is_line_extended(stego_text_line);
+        trace!("Found extended line: '{}'", bit);
+        revealed_data.push(bit)
+    }
+
+    /// Removes the words added by the line extension, so the line is as it was before concealing.
+    pub(crate) fn remove_extension(&self, stego_text_line: &mut String) {
+        let word_count = self.extension_word_count(stego_text_line);
+        if word_count == 0 {
+            return;
+        }
+        let line_end = stego_text_line.trim_end().len();
+        let pivot_line_end = (0..word_count).fold(line_end, |line_end, _| {
+            let word_start = stego_text_line[..line_end]
+                .rfind(char::is_whitespace)
+                .unwrap_or(0);
+            stego_text_line[..word_start].trim_end().len()
+        });
+        stego_text_line.truncate(pivot_line_end);
+    }
+
+    /// Amount of words beyond the pivot line, which is constructed again from the words of the line.
+    fn extension_word_count(&self, stego_text_line: &str) -> usize {
+        if self.bitrate() == 1 {
+            return self.is_line_extended(stego_text_line) as usize;
+        }
+        let mut words = stego_text_line.split_whitespace().peekable();
+        self.construct_pivot_line(&mut words);
+        words.count()
+    }
+
+    fn is_line_extended(&self, stego_text_line: &str) -> bool {
         let expected_whitespace_amount = stego_text_line.split_whitespace().count() - 1;
         let ext_line_length: usize = stego_text_line
             .split_whitespace()
             .map(|word| graphemes_length(word))
             .sum();
-        let bit = ext_line_length + expected_whitespace_amount > self.pivot;
-        trace!("Found extended line: '{}'", bit);
-        revealed_data.push(bit)
+        ext_line_length + expected_whitespace_amount > self.pivot
     }
 
     pub(crate) fn verify_pivot(&self, cover: &str) -> VerificationResult {
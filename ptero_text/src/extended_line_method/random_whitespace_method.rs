@@ -1,4 +1,5 @@
 use std::cell::RefCell;
+use std::ops::Range;
 use std::rc::{Rc, Weak};
 
 use bitvec::prelude::*;
@@ -10,7 +11,7 @@ use unicode_segmentation::UnicodeSegmentation;
 use ptero_common::config::CommonMethodConfig;
 use ptero_common::method::{MethodProgressStatus, MethodResult};
 
-use crate::extended_line_method::{ConcealError, Result};
+use crate::extended_line_method::{ConcealError, RandomWhitespaceMode, Result};
 use crate::line_separator::{DEFAULT_LINE_SEPARATOR, LineSeparatorType};
 
 const DEFAULT_ASCII_DELIMITER: &str = " ";
@@ -37,6 +38,34 @@ pub struct RandomWhitespaceMethod {
         default = "DEFAULT_LINE_SEPARATOR"
     )]
     line_separator_type: LineSeparatorType,
+    #[builder(
+        setter(into, name = "with_mode"),
+        default = "RandomWhitespaceMode::Single"
+    )]
+    mode: RandomWhitespaceMode,
+}
+
+/// Returns the byte ranges of the whitespace between the words of the line.
+fn word_gaps(line: &str) -> Vec<Range<usize>> {
+    let mut gaps = Vec::new();
+    let mut gap_start = None;
+    let mut seen_word = false;
+    for (index, cluster) in line.grapheme_indices(true) {
+        if !cluster.contains(char::is_whitespace) {
+            if let Some(start) = gap_start.take() {
+                gaps.push(start..index);
+            }
+            seen_word = true;
+        } else if seen_word && gap_start.is_none() {
+            gap_start = Some(index);
+        }
+    }
+    gaps
+}
+
+/// Amount of bits carried by the index of the gap, with 0 meaning no double whitespace.
+fn gap_index_bitrate(gap_count: usize) -> usize {
+    (usize::BITS - 1 - (gap_count + 1).leading_zeros()) as usize
 }
 
 impl RandomWhitespaceMethod {
@@ -55,6 +84,18 @@ impl RandomWhitespaceMethod {
         config.borrow_mut().notifier.notify(event);
     }
 
+    pub(crate) fn mode(&self) -> RandomWhitespaceMode {
+        self.mode
+    }
+
+    /// Amount of bits concealed in the line.
+    pub(crate) fn bitrate(&self, line: &str) -> usize {
+        match self.mode {
+            RandomWhitespaceMode::Single => Self::CYCLE_BITRATE as usize,
+            RandomWhitespaceMode::GapIndex => gap_index_bitrate(word_gaps(line).len()),
+        }
+    }
+
     pub(crate) fn conceal_in_random_whitespace<Order, Type>(
         &mut self,
         data: &mut Iter<Order, Type>,
@@ -64,6 +105,9 @@ impl RandomWhitespaceMethod {
         Order: BitOrder,
         Type: BitStore,
     {
+        if let RandomWhitespaceMode::GapIndex = self.mode {
+            return Ok(self.conceal_in_gap_index(data, cover));
+        }
         let line_separator = self.line_separator_type.separator();
         Ok(match data.next().as_deref() {
             Some(true) => {
@@ -95,6 +139,49 @@ impl RandomWhitespaceMethod {
         })
     }
 
+    fn conceal_in_gap_index<Order, Type>(
+        &mut self,
+        data: &mut Iter<Order, Type>,
+        cover: &mut String,
+    ) -> MethodResult
+    where
+        Order: BitOrder,
+        Type: BitStore,
+    {
+        let line_separator = self.line_separator_type.separator();
+        let last_newline_index = cover.rfind(line_separator)
+            .map(|index| index + line_separator.len())
+            .unwrap_or(0);
+        let gaps = word_gaps(&cover[last_newline_index..]);
+        let bitrate = gap_index_bitrate(gaps.len());
+        if bitrate == 0 {
+            trace!("No gaps between the words, skipping double whitespace");
+            return MethodResult::Success;
+        }
+
+        let next_n_bits = data.take(bitrate).collect::<BitVec<Lsb0, usize>>();
+        if next_n_bits.is_empty() {
+            return MethodResult::NoDataLeft;
+        }
+        // We might end-up with lower amount of bits than suggested by bitrate,
+        // the missing high bits are revealed as zeroes following the data
+        let gap_index = next_n_bits.as_raw_slice()[0];
+        if gap_index > 0 {
+            let position = last_newline_index + gaps[gap_index - 1].start;
+            trace!("Putting space in gap {} at position {}", gap_index, position);
+            cover.insert_str(position, self.whitespace_str);
+        } else {
+            trace!("Skipping double whitespace");
+        }
+
+        if next_n_bits.len() < bitrate {
+            MethodResult::NoDataLeft
+        } else {
+            self.notify(&MethodProgressStatus::DataWritten(bitrate as u64));
+            MethodResult::Success
+        }
+    }
+
     pub(crate) fn reveal_in_random_whitespace<Order, Type>(
         &mut self,
         stego_text_line: &mut String,
@@ -103,6 +190,9 @@ impl RandomWhitespaceMethod {
         Order: BitOrder,
         Type: BitStore,
     {
+        if let RandomWhitespaceMode::GapIndex = self.mode {
+            return self.reveal_in_gap_index(stego_text_line, revealed_data);
+        }
         let mut seen_whitespace = false;
         let mut bit = false;
         for cluster in stego_text_line.graphemes(true) {
@@ -119,6 +209,36 @@ impl RandomWhitespaceMethod {
         revealed_data.push(bit);
     }
 
+    fn reveal_in_gap_index<Order, Type>(
+        &mut self,
+        stego_text_line: &mut String,
+        revealed_data: &mut BitVec<Order, Type>,
+    ) where
+        Order: BitOrder,
+        Type: BitStore,
+    {
+        let gaps = word_gaps(stego_text_line);
+        let bitrate = gap_index_bitrate(gaps.len());
+        let doubled_gap = gaps.iter().enumerate().find_map(|(index, gap)| {
+            let whitespace = &stego_text_line[gap.clone()];
+            let position = whitespace.find(self.whitespace_str)?;
+            (whitespace.graphemes(true).count() > 1).then(|| (index + 1, gap.start + position))
+        });
+
+        let gap_index = match doubled_gap {
+            Some((gap_index, position)) => {
+                stego_text_line.replace_range(position..position + self.whitespace_str.len(), "");
+                gap_index
+            }
+            None => 0,
+        };
+        trace!("Found two consecutive whitespaces in gap {}", gap_index);
+
+        let data: &BitSlice<Msb0, usize> = BitSlice::from_element(&gap_index);
+        let data_length = data.len();
+        revealed_data.extend(data.iter().by_val().skip(data_length - bitrate));
+    }
+
     fn find_approx_whitespace_position(
         &mut self,
         cover: &mut String,
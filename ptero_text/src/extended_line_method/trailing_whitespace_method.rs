@@ -48,17 +48,15 @@ impl TrailingWhitespaceMethod {
         config.borrow_mut().notifier.notify(event);
     }
 
-    fn bitrate(&self) -> usize {
+    pub(crate) fn bitrate(&self) -> usize {
         let amount_of_bits = std::mem::size_of::<usize>() * 8;
         amount_of_bits - self.charset.size().leading_zeros() as usize
     }
 
     fn assemble_charset_index(&self, next_bits: &BitSlice<Lsb0, usize>) -> usize {
-        let bitrate = self.bitrate();
-        let index = next_bits.as_raw_slice()[0];
-        // Pad to bitrate
-        // We might end-up with lower amount of bits than suggested by bitrate
-        index << (bitrate - next_bits.len())
+        // We might end-up with lower amount of bits than suggested by bitrate,
+        // the missing high bits are revealed as zeroes following the data
+        next_bits.as_raw_slice()[0]
     }
 
     pub(crate) fn conceal_in_trailing_whitespace<Order, Type>(
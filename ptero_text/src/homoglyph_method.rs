@@ -0,0 +1,229 @@
+//! This module contains implementation of Homoglyph algorithm that can be used to
+//! conceal data inside a text.
+//!
+//! It implements [`SteganographyMethod`] trait for [`&str`] type.
+//!
+//! # Examples
+//!
+//! Concealing and revealing data:
+//! ```
+//! use bitvec::prelude::*;
+//! use ptero_common::method::SteganographyMethod;
+//! use ptero_text::homoglyph_method::HomoglyphMethod;
+//!
+//! let cover_text = "This is a sample text that is harmless, it tells a story \
+//!     about a panda that fell from a tree and then went rolling down the hill";
+//! let mut method = HomoglyphMethod::builder().build().unwrap();
+//!
+//! let stego_text = method
+//!     .try_conceal(cover_text, &mut b"E".view_bits::<Msb0>().iter())
+//!     .unwrap();
+//! // Nothing is added or removed, some letters are just replaced by their look-alikes
+//! assert_eq!(stego_text.chars().count(), cover_text.chars().count());
+//! assert_ne!(stego_text, cover_text);
+//!
+//! let revealed: BitVec<Msb0, u8> = method.try_reveal(&stego_text).unwrap();
+//! assert_eq!(revealed.as_raw_slice(), b"E");
+//! ```
+//! # Description
+//!
+//! Every letter of the cover text listed in the homoglyph table (see [`homoglyph_tables`]) carries
+//! a single bit - the Latin letter stands for 0, its Cyrillic or Greek look-alike for 1.
+//! Other characters are copied as they are. Unlike [`crate::extended_line_method`], the method
+//! doesn't add any characters nor change the lines, so it survives channels that trim whitespace.
+//!
+//! Look-alikes already present in the cover text carry the data as well, so the letters
+//! concealing the data might be replaced by the Latin ones. The rest of the cover text is left
+//! untouched once the data runs out.
+use std::cell::RefCell;
+use std::rc::Rc;
+use std::sync::Arc;
+
+use bitvec::prelude::*;
+use bitvec::slice::Iter;
+use snafu::ResultExt;
+
+use ptero_common::config::{
+    BuilderError, CommonMethodConfig, CommonMethodConfigBuilder, ConfigurableBuilder,
+};
+pub use ptero_common::method::MethodError as ConcealError;
+use ptero_common::method::{InvalidPayload, MethodProgressStatus, SteganographyMethod};
+use ptero_common::observer::{Observable, Observer};
+
+use self::homoglyph_tables::{GetHomoglyphTable, HomoglyphTableType};
+
+pub mod homoglyph_tables;
+
+pub struct HomoglyphMethodBuilder {
+    config_builder: CommonMethodConfigBuilder,
+    table: Box<dyn GetHomoglyphTable>,
+}
+
+impl Default for HomoglyphMethodBuilder {
+    fn default() -> Self {
+        HomoglyphMethodBuilder {
+            config_builder: CommonMethodConfig::builder_with_entropy(),
+            table: Box::new(HomoglyphTableType::Cyrillic),
+        }
+    }
+}
+
+impl HomoglyphMethodBuilder {
+    /// Sets custom homoglyph table (Cyrillic by default).
+    ///
+    /// Possible values are listed in [`HomoglyphTableType`]. You can implement your custom type
+    /// as long as it extends [`GetHomoglyphTable`].
+    pub fn with_homoglyph_table<T>(mut self, table: T) -> Self
+    where
+        T: GetHomoglyphTable + 'static,
+    {
+        self.table = Box::new(table);
+        self
+    }
+
+    /// Constructs the method
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use ptero_common::config::ConfigurableBuilder;
+    /// use ptero_text::homoglyph_method::homoglyph_tables::HomoglyphTableType;
+    /// use ptero_text::homoglyph_method::HomoglyphMethodBuilder;
+    ///
+    /// let method = HomoglyphMethodBuilder::default()
+    ///     .with_homoglyph_table(HomoglyphTableType::Greek)
+    ///     .with_checksum(true)
+    ///     .build();
+    /// ```
+    pub fn build(self) -> std::result::Result<HomoglyphMethod, BuilderError> {
+        let config = self
+            .config_builder
+            .build()
+            .map_err(|source| BuilderError::new("HomoglyphMethod", source))?;
+
+        Ok(HomoglyphMethod {
+            config: Rc::new(RefCell::new(config)),
+            table: self.table,
+        })
+    }
+}
+
+impl ConfigurableBuilder for HomoglyphMethodBuilder {
+    fn map_config_builder<F>(mut self, update: F) -> Self
+    where
+        F: FnOnce(CommonMethodConfigBuilder) -> CommonMethodConfigBuilder,
+    {
+        self.config_builder = update(self.config_builder);
+        self
+    }
+}
+
+pub type Result<Success> = std::result::Result<Success, ConcealError>;
+
+/// The main structure describing internal state for the Homoglyph method.
+pub struct HomoglyphMethod {
+    config: Rc<RefCell<CommonMethodConfig>>,
+    table: Box<dyn GetHomoglyphTable>,
+}
+
+impl HomoglyphMethod {
+    const CYCLE_BITRATE: u64 = 1;
+
+    /// Returns a builder for [`HomoglyphMethod`] algorithm.
+    pub fn builder() -> HomoglyphMethodBuilder {
+        HomoglyphMethodBuilder::default()
+    }
+
+    pub fn subscribe(&mut self, subscriber: Arc<RefCell<dyn Observer<MethodProgressStatus>>>) {
+        self.config.borrow_mut().notifier.subscribe(subscriber);
+    }
+
+    /// Returns how many bits can be concealed in the cover text, including the payload frame.
+    ///
+    /// # Examples
+    /// ```
+    /// use ptero_text::homoglyph_method::HomoglyphMethod;
+    ///
+    /// let method = HomoglyphMethod::builder().build().unwrap();
+    ///
+    /// assert_eq!(method.capacity("A panda, sleeping"), 9);
+    /// ```
+    pub fn capacity(&self, cover: &str) -> usize {
+        cover
+            .chars()
+            .filter(|chr| self.latin_letter(chr).is_some())
+            .count()
+    }
+
+    fn notify(&mut self, event: &MethodProgressStatus) {
+        self.config.borrow_mut().notifier.notify(event);
+    }
+
+    /// Returns the Latin letter of the pair the character belongs to.
+    fn latin_letter(&self, chr: &char) -> Option<char> {
+        self.table
+            .get_homoglyph(chr)
+            .map(|_| *chr)
+            .or_else(|| self.table.get_latin(chr))
+    }
+}
+
+impl SteganographyMethod<&str, ConcealError> for HomoglyphMethod {
+    type ConcealedOutput = String;
+
+    fn try_conceal<Order, Type>(
+        &mut self,
+        cover: &str,
+        data: &mut Iter<Order, Type>,
+    ) -> Result<Self::ConcealedOutput>
+    where
+        Order: BitOrder,
+        Type: BitStore,
+    {
+        let payload = self.config.borrow().wrap_payload(data);
+        let data = &mut payload.iter();
+
+        let mut result = String::with_capacity(cover.len());
+        for chr in cover.chars() {
+            let latin = match self.latin_letter(&chr) {
+                Some(latin) if data.len() > 0 => latin,
+                _ => {
+                    result.push(chr);
+                    continue;
+                }
+            };
+            if *data.next().unwrap() {
+                result.push(self.table.get_homoglyph(&latin).unwrap());
+            } else {
+                result.push(latin);
+            }
+            self.notify(&MethodProgressStatus::DataWritten(Self::CYCLE_BITRATE));
+        }
+
+        if data.len() > 0 {
+            return Err(ConcealError::CoverTextTooSmall {
+                remaining_data_size: data.len(),
+            });
+        }
+        self.notify(&MethodProgressStatus::Finished);
+
+        Ok(result)
+    }
+
+    fn try_reveal<Order, Type>(&mut self, stego_text: &str) -> Result<BitVec<Order, Type>>
+    where
+        Order: BitOrder,
+        Type: BitStore,
+    {
+        let revealed_data = stego_text
+            .chars()
+            .filter(|chr| self.latin_letter(chr).is_some())
+            .map(|chr| self.table.get_latin(&chr).is_some())
+            .collect::<BitVec<Order, Type>>();
+
+        self.config
+            .borrow()
+            .unwrap_payload(revealed_data)
+            .context(InvalidPayload)
+    }
+}
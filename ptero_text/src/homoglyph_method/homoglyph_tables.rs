@@ -0,0 +1,117 @@
+/// This trait is used for reading the homoglyph table.
+///
+/// New tables should implement `get_table` which provides the pairs of the Latin letters
+/// and their look-alikes used by the method.
+pub trait GetHomoglyphTable {
+    /// Returns the pairs of the Latin letter and its visually identical counterpart.
+    /// Every character should appear in the table only once, otherwise the data can't be revealed.
+    fn get_table(&self) -> &[(char, char)];
+
+    /// Returns the look-alike of the Latin letter or `None` if the letter has no pair in the table.
+    ///
+    /// # Examples
+    /// ```
+    /// use ptero_text::homoglyph_method::homoglyph_tables::{GetHomoglyphTable, HomoglyphTableType};
+    ///
+    /// let table = HomoglyphTableType::Cyrillic;
+    ///
+    /// assert_eq!(table.get_homoglyph(&'a'), Some('\u{0430}'));
+    /// assert_eq!(table.get_homoglyph(&'b'), None);
+    /// ```
+    fn get_homoglyph(&self, chr: &char) -> Option<char> {
+        self.get_table()
+            .iter()
+            .find(|(latin, _)| latin == chr)
+            .map(|(_, homoglyph)| *homoglyph)
+    }
+
+    /// Returns the Latin letter for the look-alike or `None` if the character is not a look-alike.
+    ///
+    /// # Examples
+    /// ```
+    /// use ptero_text::homoglyph_method::homoglyph_tables::{GetHomoglyphTable, HomoglyphTableType};
+    ///
+    /// let table = HomoglyphTableType::Cyrillic;
+    ///
+    /// assert_eq!(table.get_latin(&'\u{043E}'), Some('o'));
+    /// assert_eq!(table.get_latin(&'o'), None);
+    /// ```
+    fn get_latin(&self, chr: &char) -> Option<char> {
+        self.get_table()
+            .iter()
+            .find(|(_, homoglyph)| homoglyph == chr)
+            .map(|(latin, _)| *latin)
+    }
+}
+
+/// Latin letters paired with Cyrillic letters which look the same in most fonts.
+pub const CYRILLIC_HOMOGLYPH_TABLE: [(char, char); 22] = [
+    ('a', '\u{0430}'),
+    ('c', '\u{0441}'),
+    ('e', '\u{0435}'),
+    ('i', '\u{0456}'),
+    ('j', '\u{0458}'),
+    ('o', '\u{043E}'),
+    ('p', '\u{0440}'),
+    ('s', '\u{0455}'),
+    ('x', '\u{0445}'),
+    ('y', '\u{0443}'),
+    ('A', '\u{0410}'),
+    ('B', '\u{0412}'),
+    ('C', '\u{0421}'),
+    ('E', '\u{0415}'),
+    ('H', '\u{041D}'),
+    ('I', '\u{0406}'),
+    ('K', '\u{041A}'),
+    ('M', '\u{041C}'),
+    ('O', '\u{041E}'),
+    ('P', '\u{0420}'),
+    ('T', '\u{0422}'),
+    ('X', '\u{0425}'),
+];
+
+/// Latin letters paired with Greek letters which look the same in most fonts.
+/// Mostly capital letters, so the capacity is lower than with [CYRILLIC_HOMOGLYPH_TABLE].
+pub const GREEK_HOMOGLYPH_TABLE: [(char, char); 15] = [
+    ('o', '\u{03BF}'),
+    ('A', '\u{0391}'),
+    ('B', '\u{0392}'),
+    ('E', '\u{0395}'),
+    ('H', '\u{0397}'),
+    ('I', '\u{0399}'),
+    ('K', '\u{039A}'),
+    ('M', '\u{039C}'),
+    ('N', '\u{039D}'),
+    ('O', '\u{039F}'),
+    ('P', '\u{03A1}'),
+    ('T', '\u{03A4}'),
+    ('X', '\u{03A7}'),
+    ('Y', '\u{03A5}'),
+    ('Z', '\u{0396}'),
+];
+
+/// Enum representing possible homoglyph tables e.g. [CYRILLIC_HOMOGLYPH_TABLE].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum HomoglyphTableType {
+    Cyrillic,
+    Greek,
+}
+
+impl GetHomoglyphTable for HomoglyphTableType {
+    /// Returns pre-defined homoglyph tables based on enum value.
+    ///
+    /// # Examples
+    /// ## Get every homoglyph table
+    /// ```
+    /// use ptero_text::homoglyph_method::homoglyph_tables::*;
+    ///
+    /// assert_eq!(HomoglyphTableType::Cyrillic.get_table(), &CYRILLIC_HOMOGLYPH_TABLE);
+    /// assert_eq!(HomoglyphTableType::Greek.get_table(), &GREEK_HOMOGLYPH_TABLE);
+    /// ```
+    fn get_table(&self) -> &[(char, char)] {
+        match *self {
+            HomoglyphTableType::Cyrillic => &CYRILLIC_HOMOGLYPH_TABLE,
+            HomoglyphTableType::Greek => &GREEK_HOMOGLYPH_TABLE,
+        }
+    }
+}
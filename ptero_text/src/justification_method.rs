@@ -0,0 +1,431 @@
+//! This module contains implementation of Full Justification algorithm that can be used to
+//! conceal data inside a text.
+//!
+//! It implements [`SteganographyMethod`] trait for [`&str`] type.
+//!
+//! # Examples
+//!
+//! Concealing and revealing data:
+//! ```
+//! use bitvec::prelude::*;
+//! use ptero_common::config::ConfigurableBuilder;
+//! use ptero_common::method::SteganographyMethod;
+//! use ptero_text::justification_method::JustificationMethod;
+//! use ptero_text::line_separator::LineSeparatorType;
+//!
+//! let cover_text = "A little panda has fallen from a tree. The panda went rolling down the hill";
+//! let mut method = JustificationMethod::builder()
+//!     .with_pivot(20)
+//!     .with_line_separator(LineSeparatorType::Unix)
+//!     .with_framing(false)
+//!     .build()
+//!     .unwrap();
+//!
+//! let stego_text = method
+//!     .try_conceal(cover_text, &mut bits![1, 0].iter())
+//!     .unwrap();
+//! // Every line but the last one is exactly as wide as the pivot
+//! let lines = stego_text.lines().collect::<Vec<&str>>();
+//! assert!(lines[..lines.len() - 1].iter().all(|line| line.len() == 20));
+//!
+//! let revealed: BitVec<Msb0, u8> = method.try_reveal(&stego_text).unwrap();
+//! assert_eq!(&revealed[..2], bits![1, 0]);
+//! ```
+//! # Description
+//!
+//! The cover text is split into lines not longer than the pivot, the same way
+//! [`ExtendedLineMethod`](crate::extended_line_method::ExtendedLineMethod) does it.
+//! Every line, except the last one, is then justified to exactly the pivot width.
+//! The extra spaces are spread as evenly as possible - every gap between the words gets
+//! the same amount of them and the remainder goes to some of the gaps, one space each.
+//!
+//! Choosing `r` out of `g` gaps for the remainder can be done in `C(g, r)` ways. The line conceals
+//! `floor(log2(C(g, r)))` bits as the rank of the choice in the combinatorial number system.
+//! Lines where the remainder is zero carry no data. Once the data runs out, the remaining lines
+//! are still justified with random choices, so the whole text looks the same.
+use std::cell::RefCell;
+use std::rc::Rc;
+use std::sync::Arc;
+
+use bitvec::prelude::*;
+use bitvec::slice::Iter;
+use rand::RngCore;
+use snafu::{ResultExt, Snafu};
+
+use ptero_common::config::{
+    BuilderError, CommonMethodConfig, CommonMethodConfigBuilder, ConfigurableBuilder,
+};
+use ptero_common::method::{
+    InvalidPayload, MethodError, MethodProgressStatus, SteganographyMethod,
+};
+use ptero_common::observer::{Observable, Observer};
+
+use crate::extended_line_method::line_extend_method::{LineExtendMethod, LineExtendMethodBuilder};
+use crate::extended_line_method::{cover_words, graphemes_length};
+use crate::line_separator::{LineSeparatorType, DEFAULT_LINE_SEPARATOR};
+
+/// Maximal amount of bits concealed in a single line.
+pub const MAX_LINE_BITRATE: usize = 64;
+
+pub struct JustificationMethodBuilder {
+    le_submethod_builder: LineExtendMethodBuilder,
+    config_builder: CommonMethodConfigBuilder,
+    line_separator_type: LineSeparatorType,
+}
+
+impl Default for JustificationMethodBuilder {
+    fn default() -> Self {
+        JustificationMethodBuilder {
+            le_submethod_builder: LineExtendMethod::builder(),
+            config_builder: CommonMethodConfig::builder_with_entropy(),
+            line_separator_type: DEFAULT_LINE_SEPARATOR,
+        }
+    }
+}
+
+impl JustificationMethodBuilder {
+    /// Set custom RNG for method. It's used to justify the lines following the data.
+    pub fn with_rng<T>(mut self, rng: T) -> Self
+    where
+        T: RngCore + 'static,
+    {
+        self.config_builder = self.config_builder.with_rng(rng);
+        self
+    }
+
+    /// Derives the RNG from the shared key and salt, so every random choice is reproducible.
+    /// See [`CommonMethodConfigBuilder::with_key`].
+    pub fn with_key(mut self, passphrase: impl AsRef<[u8]>, salt: impl AsRef<[u8]>) -> Self {
+        self.config_builder = self.config_builder.with_key(passphrase, salt);
+        self
+    }
+
+    /// Sets the width of the justified lines (20 by default).
+    pub fn with_pivot(mut self, pivot: usize) -> Self {
+        self.le_submethod_builder.with_pivot(pivot);
+        self
+    }
+
+    /// Set the line separator used to join the lines.
+    pub fn with_line_separator(mut self, line_sep_type: LineSeparatorType) -> Self {
+        self.line_separator_type = line_sep_type;
+        self
+    }
+
+    /// Constructs the method
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use ptero_text::justification_method::JustificationMethodBuilder;
+    ///
+    /// let method = JustificationMethodBuilder::default()
+    ///     .with_pivot(40)
+    ///     .build();
+    ///
+    /// assert!(method.is_ok());
+    /// ```
+    pub fn build(self) -> std::result::Result<JustificationMethod, BuilderError> {
+        let config = self
+            .config_builder
+            .build()
+            .map_err(|source| BuilderError::new("JustificationMethod", source))?;
+        let config = Rc::new(RefCell::new(config));
+
+        Ok(JustificationMethod {
+            le_submethod: self
+                .le_submethod_builder
+                .with_shared_config(config.clone())
+                .build()
+                .map_err(|source| BuilderError::new("JustificationMethod", source))?,
+            config,
+            line_separator_type: self.line_separator_type,
+        })
+    }
+}
+
+impl ConfigurableBuilder for JustificationMethodBuilder {
+    fn map_config_builder<F>(mut self, update: F) -> Self
+    where
+        F: FnOnce(CommonMethodConfigBuilder) -> CommonMethodConfigBuilder,
+    {
+        self.config_builder = update(self.config_builder);
+        self
+    }
+}
+
+pub type Result<Success> = std::result::Result<Success, ConcealError>;
+
+/// Amount of ways to choose `k` out of `n` elements, saturated at [`u128::MAX`].
+fn binomial(n: usize, k: usize) -> u128 {
+    if k > n {
+        return 0;
+    }
+    let k = k.min(n - k);
+    let mut result: u128 = 1;
+    for i in 0..k {
+        // Product of `i + 1` consecutive numbers is always divisible by `(i + 1)!`
+        result = match result.checked_mul((n - i) as u128) {
+            Some(product) => product / (i + 1) as u128,
+            None => return u128::MAX,
+        };
+    }
+    result
+}
+
+/// Amount of bits concealed by choosing `remainder` out of `gaps` gaps.
+fn line_bitrate(gaps: usize, remainder: usize) -> usize {
+    let choices = binomial(gaps, remainder);
+    let bitrate = (u128::BITS - 1 - choices.leading_zeros()) as usize;
+    bitrate.min(MAX_LINE_BITRATE)
+}
+
+/// Returns the gaps chosen by the rank, in ascending order.
+fn unrank(mut rank: u128, remainder: usize, gaps: usize) -> Vec<usize> {
+    let mut chosen = Vec::with_capacity(remainder);
+    let mut candidate = gaps;
+    for position in (1..=remainder).rev() {
+        candidate -= 1;
+        while binomial(candidate, position) > rank {
+            candidate -= 1;
+        }
+        rank -= binomial(candidate, position);
+        chosen.push(candidate);
+    }
+    chosen.reverse();
+    chosen
+}
+
+/// Returns the rank of the chosen gaps given in ascending order.
+fn rank(chosen: &[usize]) -> u128 {
+    chosen
+        .iter()
+        .enumerate()
+        .map(|(position, gap)| binomial(*gap, position + 1))
+        .fold(0, u128::saturating_add)
+}
+
+/// The main structure describing internal state for the Full Justification method.
+pub struct JustificationMethod {
+    le_submethod: LineExtendMethod,
+    config: Rc<RefCell<CommonMethodConfig>>,
+    line_separator_type: LineSeparatorType,
+}
+
+impl JustificationMethod {
+    /// Returns a builder for [`JustificationMethod`] algorithm.
+    pub fn builder() -> JustificationMethodBuilder {
+        JustificationMethodBuilder::default()
+    }
+
+    pub fn subscribe(&mut self, subscriber: Arc<RefCell<dyn Observer<MethodProgressStatus>>>) {
+        self.config.borrow_mut().notifier.subscribe(subscriber);
+    }
+
+    /// Returns how many bits can be concealed in the cover text, including the payload frame.
+    ///
+    /// # Examples
+    /// ```
+    /// use ptero_text::justification_method::JustificationMethod;
+    ///
+    /// let method = JustificationMethod::builder().with_pivot(20).build().unwrap();
+    ///
+    /// // First line is "A little panda has" with 2 extra spaces for 3 gaps - C(3, 2) = 3 choices
+    /// assert_eq!(method.capacity("A little panda has fallen from a tree."), 1);
+    /// // The last line is never justified
+    /// assert_eq!(method.capacity("A little panda has"), 0);
+    /// ```
+    pub fn capacity(&self, cover: &str) -> usize {
+        let mut words = cover_words(cover);
+        let mut capacity = 0;
+        loop {
+            let line = self.le_submethod.construct_pivot_line(&mut words);
+            if line.is_empty() || words.peek().is_none() {
+                break;
+            }
+            let (gaps, extra_spaces) = self.line_gaps(&line);
+            if gaps > 0 {
+                capacity += line_bitrate(gaps, extra_spaces % gaps);
+            }
+        }
+        capacity
+    }
+
+    /// Returns the amount of gaps between the words and the amount of extra spaces
+    /// needed to justify the line with single spaces.
+    fn line_gaps(&self, line: &str) -> (usize, usize) {
+        let words = line.split_whitespace().collect::<Vec<&str>>();
+        let gaps = words.len().saturating_sub(1);
+        let natural_length = words
+            .iter()
+            .map(|word| graphemes_length(word))
+            .sum::<usize>()
+            + gaps;
+        (
+            gaps,
+            self.le_submethod.get_pivot().saturating_sub(natural_length),
+        )
+    }
+
+    fn verify_pivot(&self, cover: &str) -> Result<()> {
+        let pivot = self.le_submethod.get_pivot();
+        match cover_words(cover).find(|word| graphemes_length(word) > pivot) {
+            Some(word) => Err(ConcealError::PivotTooSmall {
+                word: word.to_string(),
+                pivot,
+            }),
+            None => Ok(()),
+        }
+    }
+
+    fn justify_line(&mut self, line: &str, data: &mut impl Iterator<Item = bool>) -> String {
+        let (gaps, extra_spaces) = self.line_gaps(line);
+        if gaps == 0 {
+            return line.to_string();
+        }
+        let (spaces_per_gap, remainder) = (extra_spaces / gaps, extra_spaces % gaps);
+        let bitrate = line_bitrate(gaps, remainder);
+
+        let chunk = data.take(bitrate).collect::<Vec<bool>>();
+        let line_rank = if chunk.is_empty() {
+            let random = self.config.borrow_mut().rng.next_u64();
+            random as u128 % binomial(gaps, remainder)
+        } else {
+            self.notify(&MethodProgressStatus::DataWritten(chunk.len() as u64));
+            // Missing bits are concealed as zeroes following the data
+            let number = chunk
+                .iter()
+                .fold(0u128, |number, bit| (number << 1) | *bit as u128);
+            number << (bitrate - chunk.len())
+        };
+        let chosen = unrank(line_rank, remainder, gaps);
+
+        let mut result = String::with_capacity(line.len() + extra_spaces);
+        for (gap, word) in line.split_whitespace().enumerate() {
+            if gap > 0 {
+                let width = 1 + spaces_per_gap + chosen.contains(&(gap - 1)) as usize;
+                result.push_str(&" ".repeat(width));
+            }
+            result.push_str(word);
+        }
+        result
+    }
+
+    fn reveal_in_line<Order, Type>(&self, line: &str, revealed_data: &mut BitVec<Order, Type>)
+    where
+        Order: BitOrder,
+        Type: BitStore,
+    {
+        let (gaps, extra_spaces) = self.line_gaps(line);
+        if gaps == 0 {
+            return;
+        }
+        let (spaces_per_gap, remainder) = (extra_spaces / gaps, extra_spaces % gaps);
+        let bitrate = line_bitrate(gaps, remainder);
+        if bitrate == 0 {
+            return;
+        }
+
+        let words = line.split_whitespace().collect::<Vec<&str>>();
+        let chosen = words
+            .windows(2)
+            .enumerate()
+            .filter_map(|(gap, pair)| {
+                let word_end = pair[0].as_ptr() as usize + pair[0].len();
+                let width = pair[1].as_ptr() as usize - word_end;
+                (width > 1 + spaces_per_gap).then_some(gap)
+            })
+            .collect::<Vec<usize>>();
+        // The line carries at most 64 bits, so the higher ones can be dropped
+        let line_rank = rank(&chosen) as u64;
+
+        let data: &BitSlice<Msb0, u64> = BitSlice::from_element(&line_rank);
+        revealed_data.extend(data.iter().by_val().skip(data.len() - bitrate));
+    }
+
+    fn notify(&mut self, event: &MethodProgressStatus) {
+        self.config.borrow_mut().notifier.notify(event);
+    }
+}
+
+impl SteganographyMethod<&str, ConcealError> for JustificationMethod {
+    type ConcealedOutput = String;
+
+    fn try_conceal<Order, Type>(
+        &mut self,
+        cover: &str,
+        data: &mut Iter<Order, Type>,
+    ) -> Result<Self::ConcealedOutput>
+    where
+        Order: BitOrder,
+        Type: BitStore,
+    {
+        self.verify_pivot(cover)?;
+        let payload = self.config.borrow().wrap_payload(data);
+        let mut bits = payload.iter().by_val();
+
+        let mut lines = Vec::new();
+        let mut words = cover_words(cover);
+        loop {
+            let line = self.le_submethod.construct_pivot_line(&mut words);
+            if line.is_empty() {
+                break;
+            }
+            if words.peek().is_none() {
+                // Last line of justified text is aligned to the left
+                lines.push(line);
+                break;
+            }
+            lines.push(self.justify_line(&line, &mut bits));
+        }
+
+        let remaining_data_size = bits.count();
+        if remaining_data_size > 0 {
+            return Err(MethodError::CoverTextTooSmall {
+                remaining_data_size,
+            }
+            .into());
+        }
+        self.notify(&MethodProgressStatus::Finished);
+
+        Ok(lines.join(self.line_separator_type.separator()))
+    }
+
+    fn try_reveal<Order, Type>(&mut self, stego_text: &str) -> Result<BitVec<Order, Type>>
+    where
+        Order: BitOrder,
+        Type: BitStore,
+    {
+        let mut revealed_data: BitVec<Order, Type> = BitVec::new();
+        let lines = stego_text
+            .split(self.line_separator_type.separator())
+            .collect::<Vec<&str>>();
+
+        for line in lines.iter().take(lines.len().saturating_sub(1)) {
+            self.reveal_in_line(line, &mut revealed_data);
+        }
+
+        self.config
+            .borrow()
+            .unwrap_payload(revealed_data)
+            .context(InvalidPayload)
+            .context(Common)
+    }
+}
+
+/// Describes possible errors while concealing data using [`JustificationMethod`].
+#[derive(Debug, PartialEq, Snafu)]
+pub enum ConcealError {
+    /// Word of the cover text doesn't fit in the line of pivot width.
+    #[snafu(display("Pivot '{}' is smaller than the length of the word '{}'", pivot, word))]
+    PivotTooSmall { word: String, pivot: usize },
+    /// Error shared by all the methods, see [`MethodError`].
+    #[snafu(display("{}", source))]
+    Common { source: MethodError },
+}
+
+impl From<MethodError> for ConcealError {
+    fn from(source: MethodError) -> Self {
+        ConcealError::Common { source }
+    }
+}
@@ -3,4 +3,20 @@ extern crate derive_builder;
 
 pub mod extended_line_method;
 
+pub mod homoglyph_method;
+
+pub mod justification_method;
+
 pub mod line_separator;
+
+pub mod normalization_method;
+
+pub mod space_substitution_method;
+
+pub mod synonym_method;
+
+pub mod typographic_variant_method;
+
+pub mod variation_selector_method;
+
+pub mod zero_width_method;
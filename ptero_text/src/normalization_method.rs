@@ -0,0 +1,301 @@
+//! This module contains implementation of Normalization Form algorithm that can be used to
+//! conceal data inside a text.
+//!
+//! It implements [`SteganographyMethod`] trait for [`&str`] type.
+//!
+//! # Examples
+//!
+//! Concealing and revealing data:
+//! ```
+//! use bitvec::prelude::*;
+//! use ptero_common::config::ConfigurableBuilder;
+//! use ptero_common::method::SteganographyMethod;
+//! use ptero_text::normalization_method::NormalizationMethod;
+//! use unicode_segmentation::UnicodeSegmentation;
+//!
+//! let cover_text = "Zażółć gęślą jaźń, a potem idź do kawiarni i zamów café crème brûlée.";
+//! let mut method = NormalizationMethod::builder()
+//!     .with_framing(false)
+//!     .build()
+//!     .unwrap();
+//!
+//! let stego_text = method
+//!     .try_conceal(cover_text, &mut bits![1, 0, 1, 1].iter())
+//!     .unwrap();
+//! // Both forms look the same, so the text has the same letters
+//! assert_ne!(stego_text, cover_text);
+//! assert_eq!(stego_text.graphemes(true).count(), cover_text.graphemes(true).count());
+//!
+//! let revealed: BitVec<Msb0, u8> = method.try_reveal(&stego_text).unwrap();
+//! assert_eq!(&revealed[..4], bits![1, 0, 1, 1]);
+//! ```
+//! # Description
+//!
+//! Many letters with diacritics, like `ą`, `é` or `ż`, can be written either as a single
+//! precomposed character (NFC) or as the base letter followed by a combining mark (NFD).
+//! Both forms render the same. Every grapheme of the cover text having two different forms
+//! carries a single bit - the composed form stands for 0, the decomposed one for 1.
+//! Other graphemes are copied as they are.
+//!
+//! Channels which normalise the text wipe the data out. To tell that apart from a text without
+//! any data, the first two eligible graphemes are the marker - the first one is always composed,
+//! the second one always decomposed. Revealing fails with [`ConcealError::TextNormalized`]
+//! if both of them are in the same form (see [`NormalizationMethod::detect_normalization`]).
+//! The rest of the cover text is left untouched once the data runs out.
+use std::cell::RefCell;
+use std::rc::Rc;
+use std::sync::Arc;
+
+use bitvec::prelude::*;
+use bitvec::slice::Iter;
+use snafu::{ResultExt, Snafu};
+use unicode_normalization::UnicodeNormalization;
+use unicode_segmentation::UnicodeSegmentation;
+
+use ptero_common::config::{
+    BuilderError, CommonMethodConfig, CommonMethodConfigBuilder, ConfigurableBuilder,
+};
+use ptero_common::method::{
+    InvalidPayload, MethodError, MethodProgressStatus, SteganographyMethod,
+};
+use ptero_common::observer::{Observable, Observer};
+
+use crate::extended_line_method::graphemes_length;
+
+/// Amount of eligible graphemes taken by the marker.
+pub const MARKER_LENGTH: usize = 2;
+
+/// Unicode normalization form which the channel applied to the text.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum NormalizationForm {
+    /// Canonical composition - all the graphemes are precomposed.
+    Nfc,
+    /// Canonical decomposition - all the graphemes are decomposed.
+    Nfd,
+}
+
+pub struct NormalizationMethodBuilder {
+    config_builder: CommonMethodConfigBuilder,
+}
+
+impl Default for NormalizationMethodBuilder {
+    fn default() -> Self {
+        NormalizationMethodBuilder {
+            config_builder: CommonMethodConfig::builder_with_entropy(),
+        }
+    }
+}
+
+impl NormalizationMethodBuilder {
+    /// Constructs the method
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use ptero_common::config::ConfigurableBuilder;
+    /// use ptero_text::normalization_method::NormalizationMethodBuilder;
+    ///
+    /// let method = NormalizationMethodBuilder::default()
+    ///     .with_checksum(true)
+    ///     .build();
+    ///
+    /// assert!(method.is_ok());
+    /// ```
+    pub fn build(self) -> std::result::Result<NormalizationMethod, BuilderError> {
+        let config = self
+            .config_builder
+            .build()
+            .map_err(|source| BuilderError::new("NormalizationMethod", source))?;
+
+        Ok(NormalizationMethod {
+            config: Rc::new(RefCell::new(config)),
+        })
+    }
+}
+
+impl ConfigurableBuilder for NormalizationMethodBuilder {
+    fn map_config_builder<F>(mut self, update: F) -> Self
+    where
+        F: FnOnce(CommonMethodConfigBuilder) -> CommonMethodConfigBuilder,
+    {
+        self.config_builder = update(self.config_builder);
+        self
+    }
+}
+
+pub type Result<Success> = std::result::Result<Success, ConcealError>;
+
+/// Returns the composed and decomposed form of the grapheme,
+/// `None` if the grapheme can't carry any data.
+fn forms(grapheme: &str) -> Option<(String, String)> {
+    let composed = grapheme.nfc().collect::<String>();
+    let decomposed = grapheme.nfd().collect::<String>();
+    if composed == decomposed || graphemes_length(&decomposed) != 1 {
+        return None;
+    }
+    Some((composed, decomposed))
+}
+
+/// Reads the bits from the eligible graphemes, the marker included.
+/// Graphemes in neither of the forms are read as decomposed.
+fn read_bits(text: &str) -> impl Iterator<Item = bool> + '_ {
+    text.graphemes(true)
+        .filter_map(|grapheme| forms(grapheme).map(|(composed, _)| grapheme != composed))
+}
+
+/// The main structure describing internal state for the Normalization Form method.
+pub struct NormalizationMethod {
+    config: Rc<RefCell<CommonMethodConfig>>,
+}
+
+impl NormalizationMethod {
+    const CYCLE_BITRATE: u64 = 1;
+    const MARKER: [bool; MARKER_LENGTH] = [false, true];
+
+    /// Returns a builder for [`NormalizationMethod`] algorithm.
+    pub fn builder() -> NormalizationMethodBuilder {
+        NormalizationMethodBuilder::default()
+    }
+
+    pub fn subscribe(&mut self, subscriber: Arc<RefCell<dyn Observer<MethodProgressStatus>>>) {
+        self.config.borrow_mut().notifier.subscribe(subscriber);
+    }
+
+    /// Returns how many bits can be concealed in the cover text, including the payload frame.
+    ///
+    /// # Examples
+    /// ```
+    /// use ptero_text::normalization_method::NormalizationMethod;
+    ///
+    /// let method = NormalizationMethod::builder().build().unwrap();
+    ///
+    /// // `ł` has no decomposed form and two of the letters are taken by the marker
+    /// assert_eq!(method.capacity("Zażółć gęślą jaźń"), 8 - 2);
+    /// assert_eq!(method.capacity("Panda"), 0);
+    /// ```
+    pub fn capacity(&self, cover: &str) -> usize {
+        read_bits(cover).count().saturating_sub(MARKER_LENGTH)
+    }
+
+    /// Tells if the stego text has been normalised by the channel and in which form.
+    ///
+    /// # Examples
+    /// ```
+    /// use bitvec::prelude::*;
+    /// use ptero_common::config::ConfigurableBuilder;
+    /// use ptero_common::method::SteganographyMethod;
+    /// use ptero_text::normalization_method::{NormalizationForm, NormalizationMethod};
+    /// use unicode_normalization::UnicodeNormalization;
+    ///
+    /// let mut method = NormalizationMethod::builder().with_framing(false).build().unwrap();
+    /// let stego_text = method.try_conceal("Zażółć gęślą jaźń", &mut bits![0, 1].iter()).unwrap();
+    ///
+    /// assert_eq!(method.detect_normalization(&stego_text), None);
+    /// let normalised = stego_text.nfc().collect::<String>();
+    /// assert_eq!(method.detect_normalization(&normalised), Some(NormalizationForm::Nfc));
+    /// ```
+    pub fn detect_normalization(&self, stego_text: &str) -> Option<NormalizationForm> {
+        let marker = read_bits(stego_text)
+            .take(MARKER_LENGTH)
+            .collect::<Vec<bool>>();
+        match marker.as_slice() {
+            [false, false] => Some(NormalizationForm::Nfc),
+            [true, true] => Some(NormalizationForm::Nfd),
+            _ => None,
+        }
+    }
+
+    fn notify(&mut self, event: &MethodProgressStatus) {
+        self.config.borrow_mut().notifier.notify(event);
+    }
+}
+
+impl SteganographyMethod<&str, ConcealError> for NormalizationMethod {
+    type ConcealedOutput = String;
+
+    fn try_conceal<Order, Type>(
+        &mut self,
+        cover: &str,
+        data: &mut Iter<Order, Type>,
+    ) -> Result<Self::ConcealedOutput>
+    where
+        Order: BitOrder,
+        Type: BitStore,
+    {
+        let payload = self.config.borrow().wrap_payload(data);
+        let mut marker = Self::MARKER.iter().copied();
+        let mut bits = payload.iter().by_val();
+
+        let mut result = String::with_capacity(cover.len() + payload.len());
+        for grapheme in cover.graphemes(true) {
+            let (composed, decomposed) = match forms(grapheme) {
+                Some(forms) => forms,
+                None => {
+                    result.push_str(grapheme);
+                    continue;
+                }
+            };
+            let bit = match marker.next() {
+                Some(bit) => Some(bit),
+                None => bits.next().inspect(|_| {
+                    self.notify(&MethodProgressStatus::DataWritten(Self::CYCLE_BITRATE))
+                }),
+            };
+            match bit {
+                Some(true) => result.push_str(&decomposed),
+                Some(false) => result.push_str(&composed),
+                None => result.push_str(grapheme),
+            }
+        }
+
+        let remaining_data_size = marker.count() + bits.count();
+        if remaining_data_size > 0 {
+            return Err(MethodError::CoverTextTooSmall {
+                remaining_data_size,
+            }
+            .into());
+        }
+        self.notify(&MethodProgressStatus::Finished);
+
+        Ok(result)
+    }
+
+    fn try_reveal<Order, Type>(&mut self, stego_text: &str) -> Result<BitVec<Order, Type>>
+    where
+        Order: BitOrder,
+        Type: BitStore,
+    {
+        if let Some(form) = self.detect_normalization(stego_text) {
+            return Err(ConcealError::TextNormalized { form });
+        }
+        let revealed_data = read_bits(stego_text)
+            .skip(MARKER_LENGTH)
+            .collect::<BitVec<Order, Type>>();
+
+        self.config
+            .borrow()
+            .unwrap_payload(revealed_data)
+            .context(InvalidPayload)
+            .context(Common)
+    }
+}
+
+/// Describes possible errors while concealing data using [`NormalizationMethod`].
+#[derive(Debug, PartialEq, Snafu)]
+pub enum ConcealError {
+    /// Channel normalised the stego text, so the concealed data is lost.
+    #[snafu(display(
+        "Stego text has been normalised to {:?} by the channel, the data is lost",
+        form
+    ))]
+    TextNormalized { form: NormalizationForm },
+    /// Error shared by all the methods, see [`MethodError`].
+    #[snafu(display("{}", source))]
+    Common { source: MethodError },
+}
+
+impl From<MethodError> for ConcealError {
+    fn from(source: MethodError) -> Self {
+        ConcealError::Common { source }
+    }
+}
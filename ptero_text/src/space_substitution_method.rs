@@ -0,0 +1,260 @@
+//! This module contains implementation of Space Substitution algorithm that can be used to
+//! conceal data inside a text.
+//!
+//! It implements [`SteganographyMethod`] trait for [`&str`] type.
+//!
+//! # Examples
+//!
+//! Concealing and revealing data:
+//! ```
+//! use bitvec::prelude::*;
+//! use ptero_common::config::ConfigurableBuilder;
+//! use ptero_common::method::SteganographyMethod;
+//! use ptero_text::space_substitution_method::SpaceSubstitutionMethod;
+//!
+//! let cover_text = "A little panda has fallen from a tree. The panda went rolling down the hill";
+//! let mut method = SpaceSubstitutionMethod::builder()
+//!     .with_framing(false)
+//!     .build()
+//!     .unwrap();
+//!
+//! let stego_text = method
+//!     .try_conceal(cover_text, &mut bits![1, 0, 1].iter())
+//!     .unwrap();
+//! assert!(stego_text.starts_with("A\u{00A0}little panda\u{00A0}has"));
+//!
+//! let revealed: BitVec<Msb0, u8> = method.try_reveal(&stego_text).unwrap();
+//! assert_eq!(&revealed[..3], bits![1, 0, 1]);
+//! ```
+//! # Description
+//!
+//! Every space between two words of the cover text (a gap) carries as many bits as the character
+//! set allows, see [`GetCharacterSet`]. The value 0 is concealed by leaving the regular space,
+//! other values replace it with the character of the set.
+//! By default [`CharacterSetType::NoBreakSpace`] is used, giving 1 bit per gap.
+//! [`CharacterSetType::NarrowSpace`] adds the narrow no-break and thin space, giving 2 bits.
+//!
+//! Only single spaces surrounded by words are gaps, so the capacity grows with the word count.
+//! Line breaks, indentation and runs of spaces are left untouched. The gaps following the data
+//! keep the regular space.
+use std::cell::RefCell;
+use std::rc::Rc;
+use std::sync::Arc;
+
+use bitvec::prelude::*;
+use bitvec::slice::Iter;
+use snafu::ResultExt;
+
+use ptero_common::config::{
+    BuilderError, CommonMethodConfig, CommonMethodConfigBuilder, ConfigurableBuilder,
+};
+pub use ptero_common::method::MethodError as ConcealError;
+use ptero_common::method::{InvalidPayload, MethodProgressStatus, SteganographyMethod};
+use ptero_common::observer::{Observable, Observer};
+
+use crate::extended_line_method::character_sets::{CharacterSetType, GetCharacterSet};
+
+const REGULAR_SPACE: char = ' ';
+
+pub struct SpaceSubstitutionMethodBuilder {
+    config_builder: CommonMethodConfigBuilder,
+    charset: Box<dyn GetCharacterSet>,
+}
+
+impl Default for SpaceSubstitutionMethodBuilder {
+    fn default() -> Self {
+        SpaceSubstitutionMethodBuilder {
+            config_builder: CommonMethodConfig::builder_with_entropy(),
+            charset: Box::new(CharacterSetType::NoBreakSpace),
+        }
+    }
+}
+
+impl SpaceSubstitutionMethodBuilder {
+    /// Sets custom character set replacing the spaces ([`CharacterSetType::NoBreakSpace`] by default).
+    ///
+    /// Possible values are listed in [`CharacterSetType`]. You can implement your custom type
+    /// as long as it extends [`GetCharacterSet`]. Bitrate of the set is the bitrate of every gap.
+    /// Characters of the set found between words in the cover text are replaced by regular spaces.
+    pub fn with_charset<T>(mut self, character_set: T) -> Self
+    where
+        T: GetCharacterSet + 'static,
+    {
+        self.charset = Box::new(character_set);
+        self
+    }
+
+    /// Constructs the method
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use ptero_text::extended_line_method::character_sets::CharacterSetType;
+    /// use ptero_text::space_substitution_method::SpaceSubstitutionMethodBuilder;
+    ///
+    /// let method = SpaceSubstitutionMethodBuilder::default()
+    ///     .with_charset(CharacterSetType::NarrowSpace)
+    ///     .build();
+    ///
+    /// assert!(method.is_ok());
+    /// ```
+    pub fn build(self) -> std::result::Result<SpaceSubstitutionMethod, BuilderError> {
+        let config = self
+            .config_builder
+            .build()
+            .map_err(|source| BuilderError::new("SpaceSubstitutionMethod", source))?;
+
+        Ok(SpaceSubstitutionMethod {
+            config: Rc::new(RefCell::new(config)),
+            charset: self.charset,
+        })
+    }
+}
+
+impl ConfigurableBuilder for SpaceSubstitutionMethodBuilder {
+    fn map_config_builder<F>(mut self, update: F) -> Self
+    where
+        F: FnOnce(CommonMethodConfigBuilder) -> CommonMethodConfigBuilder,
+    {
+        self.config_builder = update(self.config_builder);
+        self
+    }
+}
+
+pub type Result<Success> = std::result::Result<Success, ConcealError>;
+
+/// The main structure describing internal state for the Space Substitution method.
+pub struct SpaceSubstitutionMethod {
+    config: Rc<RefCell<CommonMethodConfig>>,
+    charset: Box<dyn GetCharacterSet>,
+}
+
+impl SpaceSubstitutionMethod {
+    /// Returns a builder for [`SpaceSubstitutionMethod`] algorithm.
+    pub fn builder() -> SpaceSubstitutionMethodBuilder {
+        SpaceSubstitutionMethodBuilder::default()
+    }
+
+    pub fn subscribe(&mut self, subscriber: Arc<RefCell<dyn Observer<MethodProgressStatus>>>) {
+        self.config.borrow_mut().notifier.subscribe(subscriber);
+    }
+
+    /// Returns how many bits can be concealed in the cover text, including the payload frame.
+    ///
+    /// # Examples
+    /// ```
+    /// use ptero_text::extended_line_method::character_sets::CharacterSetType;
+    /// use ptero_text::space_substitution_method::SpaceSubstitutionMethod;
+    ///
+    /// let method = SpaceSubstitutionMethod::builder()
+    ///     .with_charset(CharacterSetType::NarrowSpace)
+    ///     .build()
+    ///     .unwrap();
+    ///
+    /// // Double space and the line break are not gaps
+    /// assert_eq!(method.capacity("A little  panda\nhas fallen"), 2 * 2);
+    /// ```
+    pub fn capacity(&self, cover: &str) -> usize {
+        self.gaps(cover).len() * self.bitrate()
+    }
+
+    fn notify(&mut self, event: &MethodProgressStatus) {
+        self.config.borrow_mut().notifier.notify(event);
+    }
+
+    fn bitrate(&self) -> usize {
+        let amount_of_bits = std::mem::size_of::<usize>() * 8;
+        amount_of_bits - self.charset.size().leading_zeros() as usize
+    }
+
+    fn is_space(&self, chr: char) -> bool {
+        chr == REGULAR_SPACE || self.charset.character_to_bits(&chr) != 0
+    }
+
+    /// Byte offsets of the gaps, i.e. single spaces or characters of the set between two words.
+    fn gaps(&self, text: &str) -> Vec<usize> {
+        let chars = text.char_indices().collect::<Vec<(usize, char)>>();
+        chars
+            .windows(3)
+            .filter(|window| {
+                let [(_, previous), (_, chr), (_, next)] = [window[0], window[1], window[2]];
+                self.is_space(chr) && !previous.is_whitespace() && !next.is_whitespace()
+            })
+            .map(|window| window[1].0)
+            .collect()
+    }
+}
+
+impl SteganographyMethod<&str, ConcealError> for SpaceSubstitutionMethod {
+    type ConcealedOutput = String;
+
+    fn try_conceal<Order, Type>(
+        &mut self,
+        cover: &str,
+        data: &mut Iter<Order, Type>,
+    ) -> Result<Self::ConcealedOutput>
+    where
+        Order: BitOrder,
+        Type: BitStore,
+    {
+        let payload = self.config.borrow().wrap_payload(data);
+        let data = &mut payload.iter();
+        let bitrate = self.bitrate();
+
+        let mut result = String::with_capacity(cover.len());
+        let mut last_offset = 0;
+        for offset in self.gaps(cover) {
+            let bits = data
+                .by_ref()
+                .take(bitrate)
+                .map(|bit| *bit)
+                .collect::<Vec<bool>>();
+            // Missing bits at the end of the data are concealed as zeroes
+            let value = bits
+                .iter()
+                .fold(0, |value, bit| (value << 1) | *bit as usize)
+                << (bitrate - bits.len());
+
+            result.push_str(&cover[last_offset..offset]);
+            result.push(*self.charset.get_character(value).unwrap_or(&REGULAR_SPACE));
+            last_offset = offset + cover[offset..].chars().next().map_or(0, char::len_utf8);
+            if !bits.is_empty() {
+                self.notify(&MethodProgressStatus::DataWritten(bits.len() as u64));
+            }
+        }
+        result.push_str(&cover[last_offset..]);
+
+        if data.len() > 0 {
+            return Err(ConcealError::CoverTextTooSmall {
+                remaining_data_size: data.len(),
+            });
+        }
+        self.notify(&MethodProgressStatus::Finished);
+
+        Ok(result)
+    }
+
+    fn try_reveal<Order, Type>(&mut self, stego_text: &str) -> Result<BitVec<Order, Type>>
+    where
+        Order: BitOrder,
+        Type: BitStore,
+    {
+        let bitrate = self.bitrate();
+
+        let mut revealed_data: BitVec<Order, Type> = BitVec::new();
+        for offset in self.gaps(stego_text) {
+            let value = stego_text[offset..]
+                .chars()
+                .next()
+                .map_or(0, |chr| self.charset.character_to_bits(&chr));
+            for position in (0..bitrate).rev() {
+                revealed_data.push((value >> position) & 1 == 1);
+            }
+        }
+
+        self.config
+            .borrow()
+            .unwrap_payload(revealed_data)
+            .context(InvalidPayload)
+    }
+}
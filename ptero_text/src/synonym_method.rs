@@ -0,0 +1,302 @@
+//! This module contains implementation of Synonym Substitution algorithm that can be used to
+//! conceal data inside a text.
+//!
+//! It implements [`SteganographyMethod`] trait for [`&str`] type.
+//!
+//! # Examples
+//!
+//! Concealing and revealing data:
+//! ```
+//! use bitvec::prelude::*;
+//! use ptero_common::config::ConfigurableBuilder;
+//! use ptero_common::method::SteganographyMethod;
+//! use ptero_text::synonym_method::SynonymMethod;
+//!
+//! let cover_text = "It was a big day. We began the trip early and walked up the hill. \
+//!     Finally, we started to look around - the view was very beautiful and the photos \
+//!     are important memories. It was difficult, but the kids were happy.";
+//! let mut method = SynonymMethod::builder().with_framing(false).build().unwrap();
+//!
+//! let stego_text = method
+//!     .try_conceal(cover_text, &mut b"Hi".view_bits::<Msb0>().iter())
+//!     .unwrap();
+//! assert_eq!(
+//!     stego_text.split_whitespace().count(),
+//!     cover_text.split_whitespace().count()
+//! );
+//!
+//! let revealed: BitVec<Msb0, u8> = method.try_reveal(&stego_text).unwrap();
+//! assert_eq!(&revealed.as_raw_slice()[..2], b"Hi");
+//! ```
+//! # Description
+//!
+//! Words of the cover text are looked up in the word list of synonym groups (see [`word_list`]).
+//! Every group has a power of two words, so a word from a group of size `2^n` carries `n` bits -
+//! it's replaced by the group member whose index matches the next `n` bits of the data.
+//! Other words are copied as they are, just like the whitespace between the words.
+//!
+//! Punctuation around the word and its capitalisation (lowercase, capitalised or uppercase) are
+//! kept. Words with mixed case like `iPhone` are not used. The rest of the cover text is left
+//! untouched once the data runs out.
+use std::cell::RefCell;
+use std::rc::Rc;
+use std::sync::Arc;
+
+use bitvec::prelude::*;
+use bitvec::slice::Iter;
+use snafu::ResultExt;
+
+use ptero_common::config::{
+    BuilderError, CommonMethodConfig, CommonMethodConfigBuilder, ConfigurableBuilder,
+};
+pub use ptero_common::method::MethodError as ConcealError;
+use ptero_common::method::{InvalidPayload, MethodProgressStatus, SteganographyMethod};
+use ptero_common::observer::{Observable, Observer};
+
+use crate::extended_line_method::cover_words;
+
+use self::word_list::WordList;
+
+pub mod word_list;
+
+pub struct SynonymMethodBuilder {
+    config_builder: CommonMethodConfigBuilder,
+    word_list: WordList,
+}
+
+impl Default for SynonymMethodBuilder {
+    fn default() -> Self {
+        SynonymMethodBuilder {
+            config_builder: CommonMethodConfig::builder_with_entropy(),
+            word_list: WordList::english(),
+        }
+    }
+}
+
+impl SynonymMethodBuilder {
+    /// Sets custom word list of the synonym groups ([`WordList::english`] by default).
+    ///
+    /// The same word list has to be used when revealing the data.
+    pub fn with_word_list(mut self, word_list: WordList) -> Self {
+        self.word_list = word_list;
+        self
+    }
+
+    /// Constructs the method
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use ptero_common::config::ConfigurableBuilder;
+    /// use ptero_text::synonym_method::SynonymMethodBuilder;
+    /// use ptero_text::synonym_method::word_list::WordList;
+    ///
+    /// let word_list: WordList = "fast, quick\nslow, sluggish".parse().unwrap();
+    /// let method = SynonymMethodBuilder::default()
+    ///     .with_word_list(word_list)
+    ///     .with_checksum(true)
+    ///     .build();
+    ///
+    /// assert!(method.is_ok());
+    /// ```
+    pub fn build(self) -> std::result::Result<SynonymMethod, BuilderError> {
+        let config = self
+            .config_builder
+            .build()
+            .map_err(|source| BuilderError::new("SynonymMethod", source))?;
+
+        Ok(SynonymMethod {
+            config: Rc::new(RefCell::new(config)),
+            word_list: self.word_list,
+        })
+    }
+}
+
+impl ConfigurableBuilder for SynonymMethodBuilder {
+    fn map_config_builder<F>(mut self, update: F) -> Self
+    where
+        F: FnOnce(CommonMethodConfigBuilder) -> CommonMethodConfigBuilder,
+    {
+        self.config_builder = update(self.config_builder);
+        self
+    }
+}
+
+pub type Result<Success> = std::result::Result<Success, ConcealError>;
+
+/// Capitalisation of the word which is kept after the substitution.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Case {
+    Lower,
+    Capitalised,
+    Upper,
+}
+
+impl Case {
+    fn of(word: &str) -> Option<Case> {
+        let mut letters = word.chars().filter(|chr| chr.is_alphabetic());
+        let first = letters.next()?;
+        let rest = letters.collect::<Vec<char>>();
+        if first.is_lowercase() && rest.iter().all(|chr| chr.is_lowercase()) {
+            Some(Case::Lower)
+        } else if first.is_uppercase() && rest.iter().all(|chr| chr.is_lowercase()) {
+            Some(Case::Capitalised)
+        } else if first.is_uppercase() && rest.iter().all(|chr| chr.is_uppercase()) {
+            Some(Case::Upper)
+        } else {
+            None
+        }
+    }
+
+    fn apply(&self, word: &str) -> String {
+        match self {
+            Case::Lower => word.to_string(),
+            Case::Capitalised => {
+                let mut chars = word.chars();
+                chars
+                    .next()
+                    .map(|first| first.to_uppercase().chain(chars).collect())
+                    .unwrap_or_default()
+            }
+            Case::Upper => word.to_uppercase(),
+        }
+    }
+}
+
+/// Splits the word into the leading punctuation, the word itself and the trailing punctuation.
+fn split_punctuation(word: &str) -> (&str, &str, &str) {
+    let is_punctuation = |chr: char| !chr.is_alphanumeric();
+    let start = word.len() - word.trim_start_matches(is_punctuation).len();
+    let end = word.trim_end_matches(is_punctuation).len().max(start);
+    (&word[..start], &word[start..end], &word[end..])
+}
+
+/// The main structure describing internal state for the Synonym Substitution method.
+pub struct SynonymMethod {
+    config: Rc<RefCell<CommonMethodConfig>>,
+    word_list: WordList,
+}
+
+impl SynonymMethod {
+    /// Returns a builder for [`SynonymMethod`] algorithm.
+    pub fn builder() -> SynonymMethodBuilder {
+        SynonymMethodBuilder::default()
+    }
+
+    pub fn subscribe(&mut self, subscriber: Arc<RefCell<dyn Observer<MethodProgressStatus>>>) {
+        self.config.borrow_mut().notifier.subscribe(subscriber);
+    }
+
+    /// Returns how many bits can be concealed in the cover text, including the payload frame.
+    ///
+    /// # Examples
+    /// ```
+    /// use ptero_text::synonym_method::SynonymMethod;
+    ///
+    /// let method = SynonymMethod::builder().build().unwrap();
+    ///
+    /// // "big" carries 2 bits, "slow" a single one
+    /// assert_eq!(method.capacity("A big panda, SLOW and iPhone-happy"), 3);
+    /// ```
+    pub fn capacity(&self, cover: &str) -> usize {
+        cover_words(cover)
+            .filter_map(|word| self.carrier(word))
+            .map(|(group_size, _)| Self::bitrate(group_size))
+            .sum()
+    }
+
+    fn notify(&mut self, event: &MethodProgressStatus) {
+        self.config.borrow_mut().notifier.notify(event);
+    }
+
+    fn bitrate(group_size: usize) -> usize {
+        group_size.trailing_zeros() as usize
+    }
+
+    /// Returns the size of the word's synonym group and its position in the group,
+    /// `None` if the word doesn't carry any data.
+    fn carrier(&self, word: &str) -> Option<(usize, usize)> {
+        let (_, word, _) = split_punctuation(word);
+        Case::of(word)?;
+        let (group, position) = self.word_list.position(word)?;
+        Some((self.word_list.groups()[group].len(), position))
+    }
+}
+
+impl SteganographyMethod<&str, ConcealError> for SynonymMethod {
+    type ConcealedOutput = String;
+
+    fn try_conceal<Order, Type>(
+        &mut self,
+        cover: &str,
+        data: &mut Iter<Order, Type>,
+    ) -> Result<Self::ConcealedOutput>
+    where
+        Order: BitOrder,
+        Type: BitStore,
+    {
+        let payload = self.config.borrow().wrap_payload(data);
+        let mut bits = payload.iter().by_val().peekable();
+
+        let mut result = String::with_capacity(cover.len());
+        let mut copied_until = 0;
+        for word in cover_words(cover) {
+            if bits.peek().is_none() {
+                break;
+            }
+            let group_size = match self.carrier(word) {
+                Some((group_size, _)) => group_size,
+                None => continue,
+            };
+            // Missing bits at the end of the data are filled with zeroes
+            let index = (0..Self::bitrate(group_size)).fold(0, |index, _| {
+                (index << 1) | bits.next().unwrap_or(false) as usize
+            });
+
+            let (prefix, original, suffix) = split_punctuation(word);
+            let synonyms = self.word_list.synonyms(original).unwrap();
+            let case = Case::of(original).unwrap();
+            // Words are slices of the cover, so their offsets can be taken from the pointers
+            let offset = word.as_ptr() as usize - cover.as_ptr() as usize;
+            result.push_str(&cover[copied_until..offset]);
+            result.push_str(prefix);
+            result.push_str(&case.apply(&synonyms[index]));
+            result.push_str(suffix);
+            copied_until = offset + word.len();
+            self.notify(&MethodProgressStatus::DataWritten(
+                Self::bitrate(group_size) as u64,
+            ));
+        }
+
+        let remaining_data_size = bits.count();
+        if remaining_data_size > 0 {
+            return Err(ConcealError::CoverTextTooSmall {
+                remaining_data_size,
+            });
+        }
+        result.push_str(&cover[copied_until..]);
+        self.notify(&MethodProgressStatus::Finished);
+
+        Ok(result)
+    }
+
+    fn try_reveal<Order, Type>(&mut self, stego_text: &str) -> Result<BitVec<Order, Type>>
+    where
+        Order: BitOrder,
+        Type: BitStore,
+    {
+        let mut revealed_data: BitVec<Order, Type> = BitVec::new();
+        for (group_size, position) in cover_words(stego_text).filter_map(|word| self.carrier(word))
+        {
+            let bitrate = Self::bitrate(group_size);
+            for bit_index in (0..bitrate).rev() {
+                revealed_data.push(position & (1 << bit_index) != 0);
+            }
+        }
+
+        self.config
+            .borrow()
+            .unwrap_payload(revealed_data)
+            .context(InvalidPayload)
+    }
+}
@@ -0,0 +1,146 @@
+//! Word lists of the synonym groups used by [`super::SynonymMethod`].
+//!
+//! The list is a plain text with one group of synonyms per line, the words separated by commas.
+//! Empty lines and lines starting with `#` are skipped. Words are case-insensitive and have to
+//! consist of letters, digits, hyphens or apostrophes only.
+//!
+//! ```text
+//! # Adjectives
+//! big, large, huge, vast
+//! fast, quick, rapid
+//! ```
+//!
+//! Every group is cut to the highest power of two not greater than its size, so the words carry
+//! whole bits - `fast, quick, rapid` carries a single bit and `rapid` is never used. Groups
+//! with a single word carry no data and are ignored.
+use std::collections::HashMap;
+use std::str::FromStr;
+
+use snafu::Snafu;
+
+const COMMENT_PREFIX: char = '#';
+const WORD_SEPARATOR: char = ',';
+const ENGLISH_WORD_LIST: &str = include_str!("english.txt");
+
+/// Synonym groups read from the word list, see the module docs for the format.
+#[derive(Debug, Clone, PartialEq)]
+pub struct WordList {
+    groups: Vec<Vec<String>>,
+    positions: HashMap<String, (usize, usize)>,
+}
+
+impl WordList {
+    /// Returns the small built-in list of common English synonyms.
+    ///
+    /// # Examples
+    /// ```
+    /// use ptero_text::synonym_method::word_list::WordList;
+    ///
+    /// let word_list = WordList::english();
+    ///
+    /// assert_eq!(word_list.synonyms("BIG").unwrap(), ["big", "large", "huge", "vast"]);
+    /// assert_eq!(word_list.synonyms("panda"), None);
+    /// ```
+    pub fn english() -> Self {
+        ENGLISH_WORD_LIST
+            .parse()
+            .expect("Built-in English word list should be valid")
+    }
+
+    /// Returns the synonym group containing the word, `None` if the word carries no data.
+    pub fn synonyms(&self, word: &str) -> Option<&[String]> {
+        self.position(word)
+            .map(|(group, _)| self.groups[group].as_slice())
+    }
+
+    /// Returns the index of the group containing the word and its index in the group.
+    pub fn position(&self, word: &str) -> Option<(usize, usize)> {
+        self.positions.get(&word.to_lowercase()).copied()
+    }
+
+    /// Returns the synonym groups, each of them with a power of two words.
+    pub fn groups(&self) -> &[Vec<String>] {
+        &self.groups
+    }
+}
+
+impl FromStr for WordList {
+    type Err = WordListError;
+
+    /// Parses the word list, see the module docs for the format.
+    ///
+    /// # Examples
+    /// ```
+    /// use ptero_text::synonym_method::word_list::{WordList, WordListError};
+    ///
+    /// let word_list: WordList = "# Verbs\nbegin, start, commence\n\nend".parse().unwrap();
+    /// assert_eq!(word_list.groups(), &[vec!["begin".to_string(), "start".to_string()]]);
+    ///
+    /// assert_eq!(
+    ///     "big, large\nhuge, Large".parse::<WordList>(),
+    ///     Err(WordListError::DuplicatedWord { word: "large".to_string(), line: 2 })
+    /// );
+    /// ```
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        let mut word_list = WordList {
+            groups: Vec::new(),
+            positions: HashMap::new(),
+        };
+        let mut seen_words = HashMap::new();
+
+        for (index, line) in text.lines().enumerate() {
+            let line_number = index + 1;
+            let line = line.trim();
+            if line.is_empty() || line.starts_with(COMMENT_PREFIX) {
+                continue;
+            }
+
+            let mut group = Vec::new();
+            for word in line.split(WORD_SEPARATOR).map(str::trim) {
+                let is_valid = !word.is_empty()
+                    && word
+                        .chars()
+                        .all(|chr| chr.is_alphanumeric() || chr == '-' || chr == '\'');
+                if !is_valid {
+                    return Err(WordListError::InvalidWord {
+                        word: word.to_string(),
+                        line: line_number,
+                    });
+                }
+                let word = word.to_lowercase();
+                if seen_words.insert(word.clone(), line_number).is_some() {
+                    return Err(WordListError::DuplicatedWord {
+                        word,
+                        line: line_number,
+                    });
+                }
+                group.push(word);
+            }
+
+            if group.len() < 2 {
+                continue;
+            }
+            let usable_length = 1 << (usize::BITS - 1 - group.len().leading_zeros());
+            group.truncate(usable_length);
+            let group_index = word_list.groups.len();
+            for (position, word) in group.iter().enumerate() {
+                word_list
+                    .positions
+                    .insert(word.clone(), (group_index, position));
+            }
+            word_list.groups.push(group);
+        }
+        Ok(word_list)
+    }
+}
+
+/// Describes possible errors while parsing the word list.
+#[derive(Debug, PartialEq, Snafu)]
+pub enum WordListError {
+    /// Word is empty or contains characters other than letters, digits, hyphens or apostrophes.
+    #[snafu(display("Invalid word '{}' in line {} of the word list", word, line))]
+    InvalidWord { word: String, line: usize },
+    /// Word appears more than once, so it's not known which group it belongs to.
+    #[snafu(display("Word '{}' in line {} is already in the word list", word, line))]
+    DuplicatedWord { word: String, line: usize },
+}
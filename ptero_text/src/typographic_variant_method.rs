@@ -0,0 +1,354 @@
+//! This module contains implementation of Typographic Variant algorithm that can be used to
+//! conceal data inside a text.
+//!
+//! It implements [`SteganographyMethod`] trait for [`&str`] type.
+//!
+//! # Examples
+//!
+//! Concealing and revealing data:
+//! ```
+//! use bitvec::prelude::*;
+//! use ptero_common::config::ConfigurableBuilder;
+//! use ptero_common::method::SteganographyMethod;
+//! use ptero_text::typographic_variant_method::TypographicVariantMethod;
+//!
+//! let cover_text = "\"I can't believe it's already over...\" - she said. \
+//!     \"Let's go again, it's the panda's favourite!\"";
+//! let mut method = TypographicVariantMethod::builder()
+//!     .with_framing(false)
+//!     .build()
+//!     .unwrap();
+//! assert_eq!(method.capacity(cover_text), 11);
+//!
+//! let stego_text = method
+//!     .try_conceal(cover_text, &mut bits![1, 0, 1, 1, 0, 0, 1].iter())
+//!     .unwrap();
+//! assert_eq!(
+//!     stego_text,
+//!     "“I can't believe it’s already over…\" - she said. \
+//!     “Let's go again, it's the panda's favourite!\""
+//! );
+//!
+//! let revealed: BitVec<Msb0, u8> = method.try_reveal(&stego_text).unwrap();
+//! assert_eq!(&revealed[..7], bits![1, 0, 1, 1, 0, 0, 1]);
+//! ```
+//! # Description
+//!
+//! Every site of the cover text that can be written in two typographically equivalent forms
+//! carries a single bit - the plain ASCII form stands for 0, the typographic one for 1.
+//! The sites are:
+//! * apostrophes and single quotes - `'` or `‘`/`’`,
+//! * double quotes - `"` or `“`/`”`,
+//! * dashes surrounded by spaces - `-` or `–`/`—` (the dash of the cover is kept, `—` otherwise),
+//! * ellipses - `...` or `…`.
+//!
+//! The curly quotes are opening after whitespace or an opening bracket, closing otherwise.
+//! Sites are found in the canonical form of the text, with all the variants replaced by
+//! the ASCII ones, so the stego text has exactly the same sites as the cover text.
+//! The rest of the cover text is left untouched once the data runs out.
+use std::cell::RefCell;
+use std::rc::Rc;
+use std::sync::Arc;
+
+use bitvec::prelude::*;
+use bitvec::slice::Iter;
+use snafu::ResultExt;
+
+use ptero_common::config::{
+    BuilderError, CommonMethodConfig, CommonMethodConfigBuilder, ConfigurableBuilder,
+};
+pub use ptero_common::method::MethodError as ConcealError;
+use ptero_common::method::{InvalidPayload, MethodProgressStatus, SteganographyMethod};
+use ptero_common::observer::{Observable, Observer};
+
+const ASCII_APOSTROPHE: char = '\'';
+const OPENING_SINGLE_QUOTE: char = '\u{2018}';
+const CLOSING_SINGLE_QUOTE: char = '\u{2019}';
+const ASCII_QUOTE: char = '"';
+const OPENING_DOUBLE_QUOTE: char = '\u{201C}';
+const CLOSING_DOUBLE_QUOTE: char = '\u{201D}';
+const HYPHEN: char = '-';
+const EN_DASH: char = '\u{2013}';
+const EM_DASH: char = '\u{2014}';
+const DOT: char = '.';
+const ELLIPSIS: char = '\u{2026}';
+const ASCII_ELLIPSIS: &str = "...";
+const OPENING_BRACKETS: [char; 3] = ['(', '[', '{'];
+
+pub struct TypographicVariantMethodBuilder {
+    config_builder: CommonMethodConfigBuilder,
+}
+
+impl Default for TypographicVariantMethodBuilder {
+    fn default() -> Self {
+        TypographicVariantMethodBuilder {
+            config_builder: CommonMethodConfig::builder_with_entropy(),
+        }
+    }
+}
+
+impl TypographicVariantMethodBuilder {
+    /// Constructs the method
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use ptero_common::config::ConfigurableBuilder;
+    /// use ptero_text::typographic_variant_method::TypographicVariantMethodBuilder;
+    ///
+    /// let method = TypographicVariantMethodBuilder::default()
+    ///     .with_checksum(true)
+    ///     .build();
+    ///
+    /// assert!(method.is_ok());
+    /// ```
+    pub fn build(self) -> std::result::Result<TypographicVariantMethod, BuilderError> {
+        let config = self
+            .config_builder
+            .build()
+            .map_err(|source| BuilderError::new("TypographicVariantMethod", source))?;
+
+        Ok(TypographicVariantMethod {
+            config: Rc::new(RefCell::new(config)),
+        })
+    }
+}
+
+impl ConfigurableBuilder for TypographicVariantMethodBuilder {
+    fn map_config_builder<F>(mut self, update: F) -> Self
+    where
+        F: FnOnce(CommonMethodConfigBuilder) -> CommonMethodConfigBuilder,
+    {
+        self.config_builder = update(self.config_builder);
+        self
+    }
+}
+
+pub type Result<Success> = std::result::Result<Success, ConcealError>;
+
+/// Kind of the site carrying a bit.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum SiteKind {
+    SingleQuote { opening: bool },
+    DoubleQuote { opening: bool },
+    Dash { typographic: char },
+    Ellipsis,
+}
+
+impl SiteKind {
+    fn variant(&self, bit: bool) -> String {
+        match (self, bit) {
+            (SiteKind::SingleQuote { .. }, false) => ASCII_APOSTROPHE.to_string(),
+            (SiteKind::SingleQuote { opening: true }, true) => OPENING_SINGLE_QUOTE.to_string(),
+            (SiteKind::SingleQuote { opening: false }, true) => CLOSING_SINGLE_QUOTE.to_string(),
+            (SiteKind::DoubleQuote { .. }, false) => ASCII_QUOTE.to_string(),
+            (SiteKind::DoubleQuote { opening: true }, true) => OPENING_DOUBLE_QUOTE.to_string(),
+            (SiteKind::DoubleQuote { opening: false }, true) => CLOSING_DOUBLE_QUOTE.to_string(),
+            (SiteKind::Dash { .. }, false) => HYPHEN.to_string(),
+            (SiteKind::Dash { typographic }, true) => typographic.to_string(),
+            (SiteKind::Ellipsis, false) => ASCII_ELLIPSIS.to_string(),
+            (SiteKind::Ellipsis, true) => ELLIPSIS.to_string(),
+        }
+    }
+}
+
+/// Piece of the text - either a site carrying a bit or the text copied as it is.
+#[derive(Debug, Clone, PartialEq)]
+enum Segment<'a> {
+    Plain(&'a str),
+    Site {
+        kind: SiteKind,
+        original: &'a str,
+        bit: bool,
+    },
+}
+
+fn canonical(chr: char) -> char {
+    match chr {
+        OPENING_SINGLE_QUOTE | CLOSING_SINGLE_QUOTE => ASCII_APOSTROPHE,
+        OPENING_DOUBLE_QUOTE | CLOSING_DOUBLE_QUOTE => ASCII_QUOTE,
+        EN_DASH | EM_DASH => HYPHEN,
+        other => other,
+    }
+}
+
+fn is_opening_position(previous: Option<char>) -> bool {
+    match previous {
+        Some(chr) => chr.is_whitespace() || OPENING_BRACKETS.contains(&chr),
+        None => true,
+    }
+}
+
+/// Splits the text into sites and plain text, looking at the canonical form of the text.
+fn segments(text: &str) -> Vec<Segment<'_>> {
+    let chars = text.char_indices().collect::<Vec<(usize, char)>>();
+    let mut segments = Vec::new();
+    let mut plain_start = 0;
+    let mut index = 0;
+
+    while index < chars.len() {
+        let (offset, chr) = chars[index];
+        let previous = index.checked_sub(1).map(|previous| chars[previous].1);
+        let next = chars.get(index + 1).map(|(_, next)| *next);
+        let mut site_length = 1;
+
+        let site = match canonical(chr) {
+            ASCII_APOSTROPHE => Some((
+                SiteKind::SingleQuote {
+                    opening: is_opening_position(previous),
+                },
+                chr != ASCII_APOSTROPHE,
+            )),
+            ASCII_QUOTE => Some((
+                SiteKind::DoubleQuote {
+                    opening: is_opening_position(previous),
+                },
+                chr != ASCII_QUOTE,
+            )),
+            HYPHEN => {
+                let line_start = text[..offset].rfind('\n').map_or(0, |start| start + 1);
+                let is_spaced = previous == Some(' ') && next == Some(' ');
+                let is_inside_line = !text[line_start..offset].trim().is_empty();
+                if is_spaced && is_inside_line {
+                    let typographic = if chr == HYPHEN { EM_DASH } else { chr };
+                    Some((SiteKind::Dash { typographic }, chr != HYPHEN))
+                } else {
+                    None
+                }
+            }
+            DOT | ELLIPSIS => {
+                site_length = chars[index..]
+                    .iter()
+                    .take_while(|(_, chr)| *chr == DOT || *chr == ELLIPSIS)
+                    .count();
+                let run_end = chars.get(index + site_length).map_or(text.len(), |c| c.0);
+                match &text[offset..run_end] {
+                    ASCII_ELLIPSIS => Some((SiteKind::Ellipsis, false)),
+                    run if run.starts_with(ELLIPSIS) && site_length == 1 => {
+                        Some((SiteKind::Ellipsis, true))
+                    }
+                    _ => None,
+                }
+            }
+            _ => None,
+        };
+
+        let end = chars.get(index + site_length).map_or(text.len(), |c| c.0);
+        if let Some((kind, bit)) = site {
+            if plain_start < offset {
+                segments.push(Segment::Plain(&text[plain_start..offset]));
+            }
+            segments.push(Segment::Site {
+                kind,
+                original: &text[offset..end],
+                bit,
+            });
+            plain_start = end;
+        }
+        index += site_length;
+    }
+    if plain_start < text.len() {
+        segments.push(Segment::Plain(&text[plain_start..]));
+    }
+    segments
+}
+
+/// The main structure describing internal state for the Typographic Variant method.
+pub struct TypographicVariantMethod {
+    config: Rc<RefCell<CommonMethodConfig>>,
+}
+
+impl TypographicVariantMethod {
+    const CYCLE_BITRATE: u64 = 1;
+
+    /// Returns a builder for [`TypographicVariantMethod`] algorithm.
+    pub fn builder() -> TypographicVariantMethodBuilder {
+        TypographicVariantMethodBuilder::default()
+    }
+
+    pub fn subscribe(&mut self, subscriber: Arc<RefCell<dyn Observer<MethodProgressStatus>>>) {
+        self.config.borrow_mut().notifier.subscribe(subscriber);
+    }
+
+    /// Returns how many bits can be concealed in the cover text, including the payload frame.
+    ///
+    /// # Examples
+    /// ```
+    /// use ptero_text::typographic_variant_method::TypographicVariantMethod;
+    ///
+    /// let method = TypographicVariantMethod::builder().build().unwrap();
+    ///
+    /// // Hyphens inside the words and too long runs of dots are not the sites
+    /// assert_eq!(method.capacity("Panda's well-known nap - zzz.... Or is it…"), 3);
+    /// ```
+    pub fn capacity(&self, cover: &str) -> usize {
+        segments(cover)
+            .iter()
+            .filter(|segment| matches!(segment, Segment::Site { .. }))
+            .count()
+    }
+
+    fn notify(&mut self, event: &MethodProgressStatus) {
+        self.config.borrow_mut().notifier.notify(event);
+    }
+}
+
+impl SteganographyMethod<&str, ConcealError> for TypographicVariantMethod {
+    type ConcealedOutput = String;
+
+    fn try_conceal<Order, Type>(
+        &mut self,
+        cover: &str,
+        data: &mut Iter<Order, Type>,
+    ) -> Result<Self::ConcealedOutput>
+    where
+        Order: BitOrder,
+        Type: BitStore,
+    {
+        let payload = self.config.borrow().wrap_payload(data);
+        let mut bits = payload.iter().by_val();
+
+        let mut result = String::with_capacity(cover.len() + 2 * payload.len());
+        for segment in segments(cover) {
+            match segment {
+                Segment::Plain(text) => result.push_str(text),
+                Segment::Site { kind, original, .. } => match bits.next() {
+                    Some(bit) => {
+                        result.push_str(&kind.variant(bit));
+                        self.notify(&MethodProgressStatus::DataWritten(Self::CYCLE_BITRATE));
+                    }
+                    None => result.push_str(original),
+                },
+            }
+        }
+
+        let remaining_data_size = bits.count();
+        if remaining_data_size > 0 {
+            return Err(ConcealError::CoverTextTooSmall {
+                remaining_data_size,
+            });
+        }
+        self.notify(&MethodProgressStatus::Finished);
+
+        Ok(result)
+    }
+
+    fn try_reveal<Order, Type>(&mut self, stego_text: &str) -> Result<BitVec<Order, Type>>
+    where
+        Order: BitOrder,
+        Type: BitStore,
+    {
+        let revealed_data = segments(stego_text)
+            .iter()
+            .filter_map(|segment| match segment {
+                Segment::Site { bit, .. } => Some(*bit),
+                Segment::Plain(_) => None,
+            })
+            .collect::<BitVec<Order, Type>>();
+
+        self.config
+            .borrow()
+            .unwrap_payload(revealed_data)
+            .context(InvalidPayload)
+    }
+}
@@ -0,0 +1,358 @@
+//! This module contains implementation of Variation Selector algorithm that can be used to
+//! conceal data inside a text.
+//!
+//! It implements [`SteganographyMethod`] trait for [`&str`] type.
+//!
+//! # Examples
+//!
+//! Concealing and revealing data:
+//! ```
+//! use bitvec::prelude::*;
+//! use ptero_common::method::SteganographyMethod;
+//! use ptero_text::variation_selector_method::{BaseCharacters, VariationSelectorMethod};
+//!
+//! let cover_text = "Look at this panda 🐼, rolling down the hill 🏔️!";
+//! let mut method = VariationSelectorMethod::builder()
+//!     .with_base_characters(BaseCharacters::Emoji)
+//!     .with_selectors_per_base(16)
+//!     .build()
+//!     .unwrap();
+//!
+//! let stego_text = method
+//!     .try_conceal(cover_text, &mut b"Meet me at noon".view_bits::<Msb0>().iter())
+//!     .unwrap();
+//! assert!(stego_text.starts_with("Look at this panda 🐼"));
+//!
+//! let revealed: BitVec<Msb0, u8> = method.try_reveal(&stego_text).unwrap();
+//! assert_eq!(revealed.as_raw_slice(), b"Meet me at noon");
+//! ```
+//! # Description
+//!
+//! There are 256 variation selectors - VS1 to VS16 (U+FE00–FE0F) and VS17 to VS256
+//! (U+E0100–E01EF). Most of the base characters have no variants, so the selectors render
+//! as nothing after them. Every selector conceals a single byte of the payload, byte `n` being
+//! VS`n+1`. Selectors are attached to the base characters (see [`BaseCharacters`]) one after
+//! another, up to the configured amount of selectors per base character.
+//!
+//! VS15 and VS16 directly following an emoji choose its text or emoji presentation,
+//! so they are never revealed as data. To conceal the bytes 14 and 15 right after an emoji,
+//! VS16 is put before them. Variation selectors found after the other base characters
+//! of the cover text are removed.
+use std::cell::RefCell;
+use std::rc::Rc;
+use std::sync::Arc;
+
+use bitvec::prelude::*;
+use bitvec::slice::Iter;
+use snafu::{ResultExt, Snafu};
+use unicode_segmentation::UnicodeSegmentation;
+
+use ptero_common::config::{
+    BuilderError, CommonMethodConfig, CommonMethodConfigBuilder, ConfigurableBuilder,
+};
+use ptero_common::method::{
+    InvalidPayload, MethodError, MethodProgressStatus, SteganographyMethod,
+};
+use ptero_common::observer::{Observable, Observer};
+
+/// Default amount of selectors attached to a single base character.
+pub const DEFAULT_SELECTORS_PER_BASE: usize = 1;
+
+const FIRST_SELECTOR_RANGE: std::ops::RangeInclusive<u32> = 0xFE00..=0xFE0F;
+const SECOND_SELECTOR_RANGE: std::ops::RangeInclusive<u32> = 0xE0100..=0xE01EF;
+const TEXT_PRESENTATION_SELECTOR: char = '\u{FE0E}';
+const EMOJI_PRESENTATION_SELECTOR: char = '\u{FE0F}';
+// Blocks holding most of the emoji, it's not the full list of the emoji code points
+const EMOJI_RANGES: [std::ops::RangeInclusive<u32>; 5] = [
+    0x2300..=0x23FF,
+    0x2600..=0x27BF,
+    0x2B00..=0x2BFF,
+    0x1F000..=0x1FAFF,
+    0x1FC00..=0x1FFFD,
+];
+
+/// Describes which characters of the cover text carry the variation selectors.
+#[derive(Debug, Clone, PartialEq)]
+pub enum BaseCharacters {
+    /// Letters and digits not followed by any combining mark.
+    Letters,
+    /// Emoji, including the ZWJ sequences and the ones with skin tone modifiers.
+    Emoji,
+    /// Only the given characters, not followed by any combining mark.
+    Custom(Vec<char>),
+}
+
+pub struct VariationSelectorMethodBuilder {
+    config_builder: CommonMethodConfigBuilder,
+    base_characters: BaseCharacters,
+    selectors_per_base: usize,
+}
+
+impl Default for VariationSelectorMethodBuilder {
+    fn default() -> Self {
+        VariationSelectorMethodBuilder {
+            config_builder: CommonMethodConfig::builder_with_entropy(),
+            base_characters: BaseCharacters::Letters,
+            selectors_per_base: DEFAULT_SELECTORS_PER_BASE,
+        }
+    }
+}
+
+impl VariationSelectorMethodBuilder {
+    /// Sets the characters carrying the selectors ([`BaseCharacters::Letters`] by default).
+    pub fn with_base_characters(mut self, base_characters: BaseCharacters) -> Self {
+        self.base_characters = base_characters;
+        self
+    }
+
+    /// Sets the maximal amount of selectors (bytes) attached to a single base character.
+    ///
+    /// Long runs of selectors make the payload fit into a few base characters,
+    /// but are easier to spot when the text is inspected.
+    pub fn with_selectors_per_base(mut self, selectors_per_base: usize) -> Self {
+        self.selectors_per_base = selectors_per_base;
+        self
+    }
+
+    /// Constructs the method
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use ptero_text::variation_selector_method::{BaseCharacters, VariationSelectorMethodBuilder};
+    ///
+    /// let method = VariationSelectorMethodBuilder::default()
+    ///     .with_base_characters(BaseCharacters::Custom(vec!['a', 'e']))
+    ///     .with_selectors_per_base(4)
+    ///     .build();
+    /// assert!(method.is_ok());
+    ///
+    /// let method = VariationSelectorMethodBuilder::default()
+    ///     .with_selectors_per_base(0)
+    ///     .build();
+    /// assert!(method.is_err());
+    /// ```
+    pub fn build(self) -> std::result::Result<VariationSelectorMethod, BuilderError> {
+        if self.selectors_per_base == 0 {
+            return Err(BuilderError::new(
+                "VariationSelectorMethod",
+                "at least one selector per base character is needed",
+            ));
+        }
+        let config = self
+            .config_builder
+            .build()
+            .map_err(|source| BuilderError::new("VariationSelectorMethod", source))?;
+
+        Ok(VariationSelectorMethod {
+            config: Rc::new(RefCell::new(config)),
+            base_characters: self.base_characters,
+            selectors_per_base: self.selectors_per_base,
+        })
+    }
+}
+
+impl ConfigurableBuilder for VariationSelectorMethodBuilder {
+    fn map_config_builder<F>(mut self, update: F) -> Self
+    where
+        F: FnOnce(CommonMethodConfigBuilder) -> CommonMethodConfigBuilder,
+    {
+        self.config_builder = update(self.config_builder);
+        self
+    }
+}
+
+pub type Result<Success> = std::result::Result<Success, ConcealError>;
+
+fn is_emoji(chr: char) -> bool {
+    EMOJI_RANGES
+        .iter()
+        .any(|range| range.contains(&(chr as u32)))
+}
+
+fn is_variation_selector(chr: char) -> bool {
+    FIRST_SELECTOR_RANGE.contains(&(chr as u32)) || SECOND_SELECTOR_RANGE.contains(&(chr as u32))
+}
+
+fn byte_to_selector(byte: u8) -> char {
+    let code_point = if byte < 16 {
+        FIRST_SELECTOR_RANGE.start() + byte as u32
+    } else {
+        SECOND_SELECTOR_RANGE.start() + (byte - 16) as u32
+    };
+    std::char::from_u32(code_point).unwrap()
+}
+
+fn selector_to_byte(selector: char) -> u8 {
+    let code_point = selector as u32;
+    if FIRST_SELECTOR_RANGE.contains(&code_point) {
+        (code_point - FIRST_SELECTOR_RANGE.start()) as u8
+    } else {
+        (code_point - SECOND_SELECTOR_RANGE.start() + 16) as u8
+    }
+}
+
+/// Splits the grapheme into the base and the selectors concealing the data.
+/// Presentation selector directly following an emoji is a part of the base.
+fn split_selectors(grapheme: &str) -> (&str, &str) {
+    let mut base_length = grapheme.trim_end_matches(is_variation_selector).len();
+    let base = &grapheme[..base_length];
+    let mut selectors = grapheme[base_length..].chars();
+    if let (Some(last), Some(selector)) = (base.chars().last(), selectors.next()) {
+        if is_emoji(last)
+            && (selector == TEXT_PRESENTATION_SELECTOR || selector == EMOJI_PRESENTATION_SELECTOR)
+        {
+            base_length += selector.len_utf8();
+        }
+    }
+    grapheme.split_at(base_length)
+}
+
+/// The main structure describing internal state for the Variation Selector method.
+pub struct VariationSelectorMethod {
+    config: Rc<RefCell<CommonMethodConfig>>,
+    base_characters: BaseCharacters,
+    selectors_per_base: usize,
+}
+
+impl VariationSelectorMethod {
+    const CYCLE_BITRATE: u64 = 8;
+
+    /// Returns a builder for [`VariationSelectorMethod`] algorithm.
+    pub fn builder() -> VariationSelectorMethodBuilder {
+        VariationSelectorMethodBuilder::default()
+    }
+
+    pub fn subscribe(&mut self, subscriber: Arc<RefCell<dyn Observer<MethodProgressStatus>>>) {
+        self.config.borrow_mut().notifier.subscribe(subscriber);
+    }
+
+    /// Returns how many bits can be concealed in the cover text, including the payload frame.
+    ///
+    /// # Examples
+    /// ```
+    /// use ptero_text::variation_selector_method::VariationSelectorMethod;
+    ///
+    /// let method = VariationSelectorMethod::builder().build().unwrap();
+    ///
+    /// assert_eq!(method.capacity("A panda!"), 6 * 8);
+    /// ```
+    pub fn capacity(&self, cover: &str) -> usize {
+        let base_count = cover
+            .graphemes(true)
+            .filter(|grapheme| self.is_base(split_selectors(grapheme).0))
+            .count();
+        base_count * self.selectors_per_base * Self::CYCLE_BITRATE as usize
+    }
+
+    fn notify(&mut self, event: &MethodProgressStatus) {
+        self.config.borrow_mut().notifier.notify(event);
+    }
+
+    fn is_base(&self, base: &str) -> bool {
+        let mut chars = base.chars();
+        let (first, second) = (chars.next(), chars.next());
+        match (&self.base_characters, first) {
+            (BaseCharacters::Letters, Some(chr)) => second.is_none() && chr.is_alphanumeric(),
+            (BaseCharacters::Emoji, Some(chr)) => is_emoji(chr),
+            (BaseCharacters::Custom(characters), Some(chr)) => {
+                second.is_none() && characters.contains(&chr)
+            }
+            (_, None) => false,
+        }
+    }
+}
+
+impl SteganographyMethod<&str, ConcealError> for VariationSelectorMethod {
+    type ConcealedOutput = String;
+
+    fn try_conceal<Order, Type>(
+        &mut self,
+        cover: &str,
+        data: &mut Iter<Order, Type>,
+    ) -> Result<Self::ConcealedOutput>
+    where
+        Order: BitOrder,
+        Type: BitStore,
+    {
+        let mut payload = self.config.borrow().wrap_payload(data);
+        payload.resize(payload.len().div_ceil(8) * 8, false);
+        let mut bytes = payload.as_raw_slice().iter().peekable();
+
+        let mut result = String::with_capacity(cover.len() + 4 * payload.len() / 8);
+        for grapheme in cover.graphemes(true) {
+            let (base, _) = split_selectors(grapheme);
+            if !self.is_base(base) {
+                result.push_str(grapheme);
+                continue;
+            }
+            result.push_str(base);
+            let needs_presentation_selector = base.chars().last().is_some_and(is_emoji)
+                && matches!(bytes.peek(), Some(14) | Some(15));
+            if needs_presentation_selector {
+                result.push(EMOJI_PRESENTATION_SELECTOR);
+            }
+            for byte in bytes.by_ref().take(self.selectors_per_base) {
+                result.push(byte_to_selector(*byte));
+                self.notify(&MethodProgressStatus::DataWritten(Self::CYCLE_BITRATE));
+            }
+        }
+
+        let remaining_bytes = bytes.count();
+        if remaining_bytes > 0 {
+            let required = (payload.len() / 8).div_ceil(self.selectors_per_base);
+            return Err(ConcealError::NotEnoughBaseCharacters {
+                required,
+                available: required - remaining_bytes.div_ceil(self.selectors_per_base),
+            });
+        }
+        self.notify(&MethodProgressStatus::Finished);
+
+        Ok(result)
+    }
+
+    fn try_reveal<Order, Type>(&mut self, stego_text: &str) -> Result<BitVec<Order, Type>>
+    where
+        Order: BitOrder,
+        Type: BitStore,
+    {
+        let revealed_bytes = stego_text
+            .graphemes(true)
+            .map(split_selectors)
+            .filter(|(base, _)| self.is_base(base))
+            .flat_map(|(_, selectors)| selectors.chars().map(selector_to_byte))
+            .collect::<Vec<u8>>();
+        let revealed_data = revealed_bytes
+            .view_bits::<Msb0>()
+            .iter()
+            .collect::<BitVec<Order, Type>>();
+
+        self.config
+            .borrow()
+            .unwrap_payload(revealed_data)
+            .context(InvalidPayload)
+            .context(Common)
+    }
+}
+
+/// Describes possible errors while concealing data using [`VariationSelectorMethod`].
+#[derive(Debug, PartialEq, Snafu)]
+pub enum ConcealError {
+    /// Cover text doesn't have enough base characters to attach all the selectors.
+    /// Allowing more selectors per base character or other base characters will mitigate the issue.
+    #[snafu(display(
+        "Cover text has '{}' base characters, while '{}' are required",
+        available,
+        required
+    ))]
+    NotEnoughBaseCharacters { required: usize, available: usize },
+    /// Error shared by all the methods, see [`MethodError`].
+    #[snafu(display("{}", source))]
+    Common { source: MethodError },
+}
+
+impl From<MethodError> for ConcealError {
+    fn from(source: MethodError) -> Self {
+        ConcealError::Common { source }
+    }
+}
@@ -0,0 +1,327 @@
+//! This module contains implementation of Zero Width algorithm that can be used to
+//! conceal data inside a text.
+//!
+//! It implements [`SteganographyMethod`] trait for [`&str`] type.
+//!
+//! # Examples
+//!
+//! Concealing and revealing data in a single line:
+//! ```
+//! use bitvec::prelude::*;
+//! use ptero_common::method::SteganographyMethod;
+//! use ptero_text::zero_width_method::ZeroWidthMethod;
+//!
+//! let cover_text = "Look at this panda 🐼, rolling down the hill 👨‍👩‍👧!";
+//! let mut method = ZeroWidthMethod::builder().build().unwrap();
+//!
+//! let stego_text = method
+//!     .try_conceal(cover_text, &mut b"Hi".view_bits::<Msb0>().iter())
+//!     .unwrap();
+//! assert_ne!(stego_text, cover_text);
+//! // Emoji sequences are left intact
+//! assert!(stego_text.contains("👨‍👩‍👧"));
+//!
+//! let revealed: BitVec<Msb0, u8> = method.try_reveal(&stego_text).unwrap();
+//! assert_eq!(revealed.as_raw_slice(), b"Hi");
+//! ```
+//! # Description
+//!
+//! Zero-width characters are put right after the letters and digits of the cover text - inside
+//! the words or at their end. Every such place (a slot) carries as many bits as the character
+//! set allows, see [`GetCharacterSet`] - the value 0 is concealed by leaving the slot empty.
+//! By default [`CharacterSetType::ZeroWidth`] is used, giving 3 bits per slot.
+//!
+//! Slots are found on the grapheme clusters, only the clusters made of a single letter or digit
+//! are followed by a slot. That way combining marks are not separated from their letters and
+//! emoji ZWJ sequences, which never start with a letter, aren't broken.
+//!
+//! Characters of the set which already follow a letter in the cover text, like the ZWNJ in
+//! Persian words, are kept and doubled so they aren't mistaken for the concealed ones. They're
+//! restored when revealing.
+//!
+//! The density tells which part of the slots is used, the lower the density the less
+//! characters are inserted, at the cost of the capacity. The same density is required to reveal.
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::rc::Rc;
+use std::sync::Arc;
+
+use bitvec::prelude::*;
+use bitvec::slice::Iter;
+use snafu::ResultExt;
+use unicode_segmentation::UnicodeSegmentation;
+
+use ptero_common::config::{
+    BuilderError, CommonMethodConfig, CommonMethodConfigBuilder, ConfigurableBuilder,
+};
+pub use ptero_common::method::MethodError as ConcealError;
+use ptero_common::method::{InvalidPayload, MethodProgressStatus, SteganographyMethod};
+use ptero_common::observer::{Observable, Observer};
+
+use crate::extended_line_method::character_sets::{CharacterSetType, GetCharacterSet};
+
+/// Default part of the slots used to conceal the data.
+pub const DEFAULT_DENSITY: f64 = 1.0;
+
+pub struct ZeroWidthMethodBuilder {
+    config_builder: CommonMethodConfigBuilder,
+    charset: Box<dyn GetCharacterSet>,
+    density: f64,
+}
+
+impl Default for ZeroWidthMethodBuilder {
+    fn default() -> Self {
+        ZeroWidthMethodBuilder {
+            config_builder: CommonMethodConfig::builder_with_entropy(),
+            charset: Box::new(CharacterSetType::ZeroWidth),
+            density: DEFAULT_DENSITY,
+        }
+    }
+}
+
+impl ZeroWidthMethodBuilder {
+    /// Sets custom character set used as the alphabet ([`CharacterSetType::ZeroWidth`] by default).
+    ///
+    /// Possible values are listed in [`CharacterSetType`]. You can implement your custom type
+    /// as long as it extends [`GetCharacterSet`]. Bitrate of the set is the bitrate of every slot.
+    /// Characters of the set found right after a letter in the cover text are doubled.
+    pub fn with_charset<T>(mut self, character_set: T) -> Self
+    where
+        T: GetCharacterSet + 'static,
+    {
+        self.charset = Box::new(character_set);
+        self
+    }
+
+    /// Sets the part of the slots used to conceal the data, from 0 (exclusive) to 1.
+    ///
+    /// The used slots are evenly spaced, e.g. with density 0.5 every other slot is used.
+    pub fn with_density(mut self, density: f64) -> Self {
+        self.density = density;
+        self
+    }
+
+    /// Constructs the method
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use ptero_text::extended_line_method::character_sets::CharacterSetType;
+    /// use ptero_text::zero_width_method::ZeroWidthMethodBuilder;
+    ///
+    /// let method = ZeroWidthMethodBuilder::default()
+    ///     .with_charset(CharacterSetType::TwoBit)
+    ///     .with_density(0.25)
+    ///     .build();
+    /// assert!(method.is_ok());
+    ///
+    /// let method = ZeroWidthMethodBuilder::default().with_density(0.0).build();
+    /// assert!(method.is_err());
+    /// ```
+    pub fn build(self) -> std::result::Result<ZeroWidthMethod, BuilderError> {
+        if !(self.density > 0.0 && self.density <= 1.0) {
+            return Err(BuilderError::new(
+                "ZeroWidthMethod",
+                format!("density '{}' is not within (0, 1]", self.density),
+            ));
+        }
+        let config = self
+            .config_builder
+            .build()
+            .map_err(|source| BuilderError::new("ZeroWidthMethod", source))?;
+
+        Ok(ZeroWidthMethod {
+            config: Rc::new(RefCell::new(config)),
+            charset: self.charset,
+            density: self.density,
+        })
+    }
+}
+
+impl ConfigurableBuilder for ZeroWidthMethodBuilder {
+    fn map_config_builder<F>(mut self, update: F) -> Self
+    where
+        F: FnOnce(CommonMethodConfigBuilder) -> CommonMethodConfigBuilder,
+    {
+        self.config_builder = update(self.config_builder);
+        self
+    }
+}
+
+pub type Result<Success> = std::result::Result<Success, ConcealError>;
+
+/// The main structure describing internal state for the Zero Width method.
+pub struct ZeroWidthMethod {
+    config: Rc<RefCell<CommonMethodConfig>>,
+    charset: Box<dyn GetCharacterSet>,
+    density: f64,
+}
+
+impl ZeroWidthMethod {
+    /// Returns a builder for [`ZeroWidthMethod`] algorithm.
+    pub fn builder() -> ZeroWidthMethodBuilder {
+        ZeroWidthMethodBuilder::default()
+    }
+
+    pub fn subscribe(&mut self, subscriber: Arc<RefCell<dyn Observer<MethodProgressStatus>>>) {
+        self.config.borrow_mut().notifier.subscribe(subscriber);
+    }
+
+    /// Returns how many bits can be concealed in the cover text, including the payload frame.
+    ///
+    /// # Examples
+    /// ```
+    /// use ptero_text::zero_width_method::ZeroWidthMethod;
+    ///
+    /// let method = ZeroWidthMethod::builder().build().unwrap();
+    ///
+    /// // Slots after every letter but the last one
+    /// assert_eq!(method.capacity("A panda"), 5 * 3);
+    /// ```
+    pub fn capacity(&self, cover: &str) -> usize {
+        self.slots(cover).len() * self.bitrate()
+    }
+
+    fn notify(&mut self, event: &MethodProgressStatus) {
+        self.config.borrow_mut().notifier.notify(event);
+    }
+
+    fn bitrate(&self) -> usize {
+        let amount_of_bits = std::mem::size_of::<usize>() * 8;
+        amount_of_bits - self.charset.size().leading_zeros() as usize
+    }
+
+    fn is_slot_used(&self, slot_index: usize) -> bool {
+        let used_before = (slot_index as f64 * self.density).floor();
+        let used_after = ((slot_index + 1) as f64 * self.density).floor();
+        used_after > used_before
+    }
+
+    /// Byte offsets of the used slots, i.e. places right after a single letter or digit grapheme.
+    /// The end of the text is not a slot, as trailing characters are often trimmed.
+    fn slots(&self, text: &str) -> Vec<usize> {
+        text.grapheme_indices(true)
+            .filter(|(_, grapheme)| {
+                let mut chars = grapheme.chars();
+                matches!((chars.next(), chars.next()), (Some(chr), None) if chr.is_alphanumeric())
+            })
+            .map(|(index, grapheme)| index + grapheme.len())
+            .filter(|offset| *offset < text.len())
+            .enumerate()
+            .filter(|(slot_index, _)| self.is_slot_used(*slot_index))
+            .map(|(_, offset)| offset)
+            .collect()
+    }
+
+    fn is_in_set(&self, chr: char) -> bool {
+        self.charset.character_to_bits(&chr) != 0
+    }
+
+    /// Removes the characters of the set inserted after a letter or digit and halves the doubled
+    /// ones that were already in the cover text. Inserted character is always the first one of
+    /// such run, so it's the one left without a pair.
+    ///
+    /// # Returns
+    /// The cover text and the removed characters by their byte offsets in that text.
+    fn strip_inserted(&self, text: &str) -> (String, HashMap<usize, char>) {
+        let mut stripped = String::with_capacity(text.len());
+        let mut inserted = HashMap::new();
+        let mut chars = text.chars().peekable();
+        while let Some(chr) = chars.next() {
+            stripped.push(chr);
+            if !chr.is_alphanumeric() {
+                continue;
+            }
+            let mut run = Vec::new();
+            while let Some(next) = chars.next_if(|next| self.is_in_set(*next)) {
+                run.push(next);
+            }
+            if run.len() % 2 == 1 {
+                inserted.insert(stripped.len(), run.remove(0));
+            }
+            stripped.extend(run.into_iter().step_by(2));
+        }
+        (stripped, inserted)
+    }
+}
+
+impl SteganographyMethod<&str, ConcealError> for ZeroWidthMethod {
+    type ConcealedOutput = String;
+
+    fn try_conceal<Order, Type>(
+        &mut self,
+        cover: &str,
+        data: &mut Iter<Order, Type>,
+    ) -> Result<Self::ConcealedOutput>
+    where
+        Order: BitOrder,
+        Type: BitStore,
+    {
+        let payload = self.config.borrow().wrap_payload(data);
+        let data = &mut payload.iter();
+        let bitrate = self.bitrate();
+        let mut slots = self.slots(cover).into_iter().peekable();
+
+        let mut result = String::with_capacity(cover.len());
+        let mut follows_letter = false;
+        for (offset, chr) in cover.char_indices() {
+            if slots.next_if_eq(&offset).is_some() && data.len() > 0 {
+                let bits = data
+                    .by_ref()
+                    .take(bitrate)
+                    .map(|bit| *bit)
+                    .collect::<Vec<bool>>();
+                // Missing bits at the end of the data are concealed as zeroes
+                let value = bits
+                    .iter()
+                    .fold(0, |value, bit| (value << 1) | *bit as usize)
+                    << (bitrate - bits.len());
+
+                if let Some(inserted) = self.charset.get_character(value) {
+                    result.push(*inserted);
+                }
+                self.notify(&MethodProgressStatus::DataWritten(bits.len() as u64));
+            }
+
+            let is_in_set = self.is_in_set(chr);
+            if follows_letter && is_in_set {
+                result.push(chr);
+            }
+            result.push(chr);
+            follows_letter = chr.is_alphanumeric() || (follows_letter && is_in_set);
+        }
+
+        if data.len() > 0 {
+            return Err(ConcealError::CoverTextTooSmall {
+                remaining_data_size: data.len(),
+            });
+        }
+        self.notify(&MethodProgressStatus::Finished);
+
+        Ok(result)
+    }
+
+    fn try_reveal<Order, Type>(&mut self, stego_text: &str) -> Result<BitVec<Order, Type>>
+    where
+        Order: BitOrder,
+        Type: BitStore,
+    {
+        let bitrate = self.bitrate();
+        let (cover, inserted) = self.strip_inserted(stego_text);
+
+        let mut revealed_data: BitVec<Order, Type> = BitVec::new();
+        for offset in self.slots(&cover) {
+            let value = inserted
+                .get(&offset)
+                .map_or(0, |chr| self.charset.character_to_bits(chr));
+            for position in (0..bitrate).rev() {
+                revealed_data.push((value >> position) & 1 == 1);
+            }
+        }
+
+        self.config
+            .borrow()
+            .unwrap_payload(revealed_data)
+            .context(InvalidPayload)
+    }
+}
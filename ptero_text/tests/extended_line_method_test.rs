@@ -3,18 +3,43 @@ use std::path::PathBuf;
 use rand::RngCore;
 use rstest::*;
 
+use ptero_common::config::ConfigurableBuilder;
 use ptero_text::extended_line_method::character_sets::GetCharacterSet;
 use ptero_text::extended_line_method::{ExtendedLineMethod, ExtendedLineMethodBuilder, Variant};
 use ptero_text::line_separator::LineSeparatorType;
 
 use crate::test_resource::ResourceLoader;
 
+#[cfg(test)]
+mod framing_test;
+
+#[cfg(test)]
+mod gap_index_test;
+
+#[cfg(test)]
+mod keyed_charset_test;
+
+#[cfg(test)]
+mod line_overflow_test;
+
+#[cfg(test)]
+mod line_selection_test;
+
+#[cfg(test)]
+mod line_spread_test;
+
+#[cfg(test)]
+mod multipart_test;
+
 #[cfg(test)]
 mod one_bit_test;
 
 #[cfg(test)]
 mod two_bit_test;
 
+#[cfg(test)]
+mod variant_rotation_test;
+
 const WITH_WORDS_TEXT: &str =
     "A little panda has fallen from a tree. The panda went rolling down the hill";
 const TINY_TEXT: &str = "TI NY COVER";
@@ -41,4 +66,5 @@ where
         .with_rng(rng)
         .with_variant(variant)
         .with_trailing_charset(charset)
+        .with_framing(false)
 }
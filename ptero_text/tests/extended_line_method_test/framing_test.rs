@@ -0,0 +1,294 @@
+use std::error::Error;
+
+use bitvec::prelude::*;
+use rand::rngs::mock::StepRng;
+use rstest::*;
+
+use ptero_common::config::ConfigurableBuilder;
+use ptero_common::ecc::EccLevel;
+use ptero_common::encryption::EncryptionError;
+use ptero_common::frame::RevealError;
+use ptero_common::method::{MethodError, SteganographyMethod};
+use ptero_common::public_key::Identity;
+use ptero_text::extended_line_method::character_sets::CharacterSetType;
+use ptero_text::extended_line_method::{ConcealError, ExtendedLineMethod, Variant};
+use ptero_text::line_separator::DEFAULT_LINE_SEPARATOR;
+
+use crate::extended_line_method_test::*;
+use crate::test_resource::ResourceLoader;
+
+fn framed_method_with(variant: Variant, charset: CharacterSetType) -> ExtendedLineMethodBuilder {
+    ExtendedLineMethod::builder()
+        .with_rng(StepRng::new(1, 1))
+        .with_variant(variant)
+        .with_trailing_charset(charset)
+        .with_pivot(20)
+}
+
+#[rstest]
+#[case::variant_1(Variant::V1, CharacterSetType::OneBit, b"Hello, panda!".to_vec())]
+#[case::variant_2(Variant::V2, CharacterSetType::TwoBit, vec![0, 255, 0, 255])]
+#[case::variant_3(Variant::V3, CharacterSetType::Full, vec![0; 5])]
+#[case::empty(Variant::V1, CharacterSetType::Twitter, vec![])]
+fn reveals_exactly_the_concealed_payload(
+    cover_text_loader: ResourceLoader,
+    #[case] variant: Variant,
+    #[case] charset: CharacterSetType,
+    #[case] payload: Vec<u8>,
+) -> Result<(), Box<dyn Error>> {
+    let cover = cover_text_loader.load_resource(&PathBuf::from("long_text"));
+    let mut method = framed_method_with(variant, charset).build()?;
+    let stego_text = method.try_conceal(&cover, &mut payload.view_bits::<Msb0>().iter())?;
+
+    let revealed: BitVec<Msb0, u8> = method.try_reveal(&stego_text)?;
+
+    assert_eq!(revealed.as_raw_slice(), payload.as_slice());
+    Ok(())
+}
+
+#[rstest]
+fn reveals_payload_ending_with_partial_trailing_chunk(
+    cover_text_loader: ResourceLoader,
+    #[values(Variant::V1, Variant::V2)] variant: Variant,
+    #[values(
+        CharacterSetType::OneBit,
+        CharacterSetType::TwoBit,
+        CharacterSetType::ThreeBit,
+        CharacterSetType::FourBit,
+        CharacterSetType::Full,
+        CharacterSetType::Twitter
+    )]
+    charset: CharacterSetType,
+) -> Result<(), Box<dyn Error>> {
+    let cover = cover_text_loader.load_resource(&PathBuf::from("long_text"));
+    let mut method = framed_method_with(variant, charset).build()?;
+    // Lengths cover every remainder of the bit count divided by the trailing bitrate
+    for length in 1..=5 {
+        let payload = (0..length).map(|byte| 0xa5 ^ byte).collect::<Vec<u8>>();
+        let stego_text = method.try_conceal(&cover, &mut payload.view_bits::<Msb0>().iter())?;
+
+        let revealed: BitVec<Msb0, u8> = method.try_reveal(&stego_text)?;
+
+        assert_eq!(revealed.as_raw_slice(), payload.as_slice());
+    }
+    Ok(())
+}
+
+#[rstest]
+fn errors_when_stego_text_is_truncated(
+    cover_text_loader: ResourceLoader,
+) -> Result<(), Box<dyn Error>> {
+    let cover = cover_text_loader.load_resource(&PathBuf::from("long_text"));
+    let payload = [42u8; 4];
+    let mut method = framed_method_with(Variant::V1, CharacterSetType::OneBit).build()?;
+    let stego_text = method.try_conceal(&cover, &mut payload.view_bits::<Msb0>().iter())?;
+
+    let separator: &str = DEFAULT_LINE_SEPARATOR.into();
+    let truncated_text = stego_text
+        .split(separator)
+        .take(8)
+        .collect::<Vec<&str>>()
+        .join(separator);
+    let result = method.try_reveal::<Msb0, u8>(&truncated_text);
+
+    assert_eq!(
+        result,
+        Err(ConcealError::Common {
+            source: MethodError::InvalidPayload {
+                source: RevealError::TruncatedPayload {
+                    expected: 4,
+                    available: 1
+                }
+            }
+        })
+    );
+    Ok(())
+}
+
+#[rstest]
+fn errors_when_stego_text_with_checksum_is_corrupted(
+    cover_text_loader: ResourceLoader,
+) -> Result<(), Box<dyn Error>> {
+    let cover = cover_text_loader.load_resource(&PathBuf::from("long_text"));
+    let payload = b"Hello, panda!";
+    let mut method = framed_method_with(Variant::V1, CharacterSetType::OneBit)
+        .with_checksum(true)
+        .build()?;
+    let stego_text = method.try_conceal(&cover, &mut payload.view_bits::<Msb0>().iter())?;
+    let revealed: BitVec<Msb0, u8> = method.try_reveal(&stego_text)?;
+    assert_eq!(revealed.as_raw_slice(), payload);
+
+    let separator: &str = DEFAULT_LINE_SEPARATOR.into();
+    let corrupted_text = stego_text
+        .split(separator)
+        .enumerate()
+        .map(|(index, line)| {
+            if index == 20 {
+                format!("{} ", line.trim_end())
+            } else {
+                line.to_string()
+            }
+        })
+        .collect::<Vec<String>>()
+        .join(separator);
+    let result = method.try_reveal::<Msb0, u8>(&corrupted_text);
+
+    assert!(matches!(
+        result,
+        Err(ConcealError::Common {
+            source: MethodError::InvalidPayload {
+                source: RevealError::ChecksumMismatch { .. }
+            }
+        })
+    ));
+    Ok(())
+}
+
+#[rstest]
+fn reveals_encrypted_payload_only_with_the_same_passphrase() -> Result<(), Box<dyn Error>> {
+    // Ciphertext is random, so the cover has only short words to fit any of the bits.
+    // Salt, nonce and authentication tag need much more space than the payload itself.
+    let cover = "a panda eats some bamboo ".repeat(60);
+    let payload = b"Hi";
+    let mut method = framed_method_with(Variant::V2, CharacterSetType::Full)
+        .with_passphrase("bamboo")
+        .build()?;
+    let stego_text = method.try_conceal(&cover, &mut payload.view_bits::<Msb0>().iter())?;
+
+    let revealed: BitVec<Msb0, u8> = method.try_reveal(&stego_text)?;
+    assert_eq!(revealed.as_raw_slice(), payload);
+
+    let mut wrong_method = framed_method_with(Variant::V2, CharacterSetType::Full)
+        .with_passphrase("eucalyptus")
+        .build()?;
+    assert_eq!(
+        wrong_method.try_reveal::<Msb0, u8>(&stego_text),
+        Err(ConcealError::Common {
+            source: MethodError::InvalidPayload {
+                source: RevealError::Decryption {
+                    source: EncryptionError::AuthenticationFailed
+                }
+            }
+        })
+    );
+
+    let mut plain_method = framed_method_with(Variant::V2, CharacterSetType::Full)
+        .build()?;
+    assert_eq!(
+        plain_method.try_reveal::<Msb0, u8>(&stego_text),
+        Err(ConcealError::Common {
+            source: MethodError::InvalidPayload {
+                source: RevealError::MissingPassphrase
+            }
+        })
+    );
+    Ok(())
+}
+
+#[rstest]
+fn reveals_payload_encrypted_to_recipient_only_with_its_identity() -> Result<(), Box<dyn Error>> {
+    // Ephemeral public key takes much more space than the payload itself.
+    let cover = "a panda eats some bamboo ".repeat(80);
+    let payload = b"Hi";
+    let identity = Identity::generate();
+    let mut method = framed_method_with(Variant::V2, CharacterSetType::Full)
+        .with_recipient(identity.recipient())
+        .build()?;
+    let stego_text = method.try_conceal(&cover, &mut payload.view_bits::<Msb0>().iter())?;
+
+    let mut revealing_method = framed_method_with(Variant::V2, CharacterSetType::Full)
+        .with_identity(identity)
+        .build()?;
+    let revealed: BitVec<Msb0, u8> = revealing_method.try_reveal(&stego_text)?;
+    assert_eq!(revealed.as_raw_slice(), payload);
+
+    let mut wrong_method = framed_method_with(Variant::V2, CharacterSetType::Full)
+        .with_identity(Identity::generate())
+        .build()?;
+    assert_eq!(
+        wrong_method.try_reveal::<Msb0, u8>(&stego_text),
+        Err(ConcealError::Common {
+            source: MethodError::InvalidPayload {
+                source: RevealError::Decryption {
+                    source: EncryptionError::AuthenticationFailed
+                }
+            }
+        })
+    );
+
+    let mut plain_method = framed_method_with(Variant::V2, CharacterSetType::Full)
+        .build()?;
+    assert_eq!(
+        plain_method.try_reveal::<Msb0, u8>(&stego_text),
+        Err(ConcealError::Common {
+            source: MethodError::InvalidPayload {
+                source: RevealError::MissingIdentity
+            }
+        })
+    );
+    Ok(())
+}
+
+#[rstest]
+fn compressed_payload_fits_into_smaller_cover_text(
+    cover_text_loader: ResourceLoader,
+) -> Result<(), Box<dyn Error>> {
+    let cover = cover_text_loader
+        .load_resource(&PathBuf::from("long_text"))
+        .split_whitespace()
+        .take(100)
+        .collect::<Vec<&str>>()
+        .join(" ");
+    let payload = b"Meet me at the old bridge at noon";
+
+    let mut method = framed_method_with(Variant::V1, CharacterSetType::Full).build()?;
+    let result = method.try_conceal(&cover, &mut payload.view_bits::<Msb0>().iter());
+    assert!(matches!(result, Err(ConcealError::CoverTextTooSmall { .. })));
+
+    let mut compressing_method = framed_method_with(Variant::V1, CharacterSetType::Full)
+        .with_compression(true)
+        .build()?;
+    let stego_text =
+        compressing_method.try_conceal(&cover, &mut payload.view_bits::<Msb0>().iter())?;
+    let revealed: BitVec<Msb0, u8> = compressing_method.try_reveal(&stego_text)?;
+
+    assert_eq!(revealed.as_raw_slice(), payload);
+    Ok(())
+}
+
+#[rstest]
+fn reveals_payload_from_stego_text_with_mangled_line(
+    cover_text_loader: ResourceLoader,
+) -> Result<(), Box<dyn Error>> {
+    let cover = cover_text_loader.load_resource(&PathBuf::from("long_text"));
+    let payload = b"Hello, panda!";
+    let mut method = framed_method_with(Variant::V1, CharacterSetType::Full)
+        .with_error_correction(EccLevel::new(2).unwrap())
+        .build()?;
+    let stego_text = method.try_conceal(&cover, &mut payload.view_bits::<Msb0>().iter())?;
+
+    let separator: &str = DEFAULT_LINE_SEPARATOR.into();
+    let normalise = |line: &str| line.split_whitespace().collect::<Vec<_>>().join(" ");
+    let mut lines = stego_text.split(separator).map(String::from).collect::<Vec<_>>();
+    let mangled_line = lines
+        .iter()
+        .position(|line| *line != normalise(line))
+        .expect("Stego text has no modified lines");
+    lines[mangled_line] = normalise(&lines[mangled_line]);
+    let mangled_text = lines.join(separator);
+
+    let revealed: BitVec<Msb0, u8> = method.try_reveal(&mangled_text)?;
+    assert_eq!(revealed.as_raw_slice(), payload);
+
+    let mut unprotected_method = framed_method_with(Variant::V1, CharacterSetType::Full).build()?;
+    let unprotected_text =
+        unprotected_method.try_conceal(&cover, &mut payload.view_bits::<Msb0>().iter())?;
+    let mut lines = unprotected_text.split(separator).map(String::from).collect::<Vec<_>>();
+    lines[mangled_line] = normalise(&lines[mangled_line]);
+    let result = unprotected_method.try_reveal::<Msb0, u8>(&lines.join(separator));
+    assert_ne!(
+        result.ok().map(|revealed| revealed.as_raw_slice().to_vec()),
+        Some(payload.to_vec())
+    );
+    Ok(())
+}
@@ -0,0 +1,131 @@
+use std::error::Error;
+
+use bitvec::prelude::*;
+use rand::rngs::mock::StepRng;
+use rstest::*;
+
+use ptero_common::config::ConfigurableBuilder;
+use ptero_common::method::SteganographyMethod;
+use ptero_common::selection::SpreadMode;
+use ptero_text::extended_line_method::character_sets::CharacterSetType;
+use ptero_text::extended_line_method::{ExtendedLineMethod, RandomWhitespaceMode, Variant};
+use ptero_text::line_separator::DEFAULT_LINE_SEPARATOR;
+
+use crate::extended_line_method_test::*;
+use crate::test_resource::ResourceLoader;
+
+fn gap_index_method(variant: Variant) -> ExtendedLineMethodBuilder {
+    ExtendedLineMethod::builder()
+        .with_rng(StepRng::new(1, 1))
+        .with_variant(variant)
+        .with_trailing_charset(CharacterSetType::TwoBit)
+        .with_pivot(20)
+        .with_random_whitespace_mode(RandomWhitespaceMode::GapIndex)
+}
+
+#[rstest]
+#[case::variant_1(Variant::V1)]
+#[case::variant_2(Variant::V2)]
+#[case::variant_3(Variant::V3)]
+fn reveals_payload_concealed_in_gap_indices(
+    cover_text_loader: ResourceLoader,
+    #[case] variant: Variant,
+) -> Result<(), Box<dyn Error>> {
+    let cover = cover_text_loader.load_resource(&PathBuf::from("long_text"));
+    let payload = b"Hi, panda! Meet me at noon.";
+    let mut method = gap_index_method(variant).with_checksum(true).build()?;
+
+    let stego_text = method.try_conceal(&cover, &mut payload.view_bits::<Msb0>().iter())?;
+    let revealed: BitVec<Msb0, u8> = method.try_reveal(&stego_text)?;
+
+    assert_eq!(revealed.as_raw_slice(), payload);
+    Ok(())
+}
+
+#[rstest]
+#[case::variant_1(Variant::V1)]
+#[case::variant_2(Variant::V2)]
+#[case::variant_3(Variant::V3)]
+fn reveals_every_gap_index(#[case] variant: Variant) -> Result<(), Box<dyn Error>> {
+    // Every line has 3 gaps, so it carries 2 bits of random whitespace
+    let cover = "aa bb cc dd ee ff gg hh ii jj kk ll mm nn oo pp qq rr ss tt uu vv ww xx yy zz";
+    let bytes = [0b0001_0110u8, 0b1111_0111, 0b1000_0000];
+    let data = &bytes.view_bits::<Msb0>()[..20];
+    let mut method = ExtendedLineMethod::builder()
+        .with_rng(StepRng::new(1, 1))
+        .with_variant(variant)
+        .with_trailing_charset(CharacterSetType::OneBit)
+        .with_pivot(11)
+        .with_random_whitespace_mode(RandomWhitespaceMode::GapIndex)
+        .with_framing(false)
+        .build()?;
+
+    let stego_text = method.try_conceal(cover, &mut data.iter())?;
+    let revealed: BitVec<Msb0, u8> = method.try_reveal(&stego_text)?;
+
+    assert_eq!(&revealed[..data.len()], data);
+    Ok(())
+}
+
+#[rstest]
+fn conceals_more_bits_in_lines_with_many_gaps(
+    cover_text_loader: ResourceLoader,
+) -> Result<(), Box<dyn Error>> {
+    let cover = cover_text_loader.load_resource(&PathBuf::from("long_text"));
+    let payload = [0b1010_0101u8; 16];
+    let mut single = ExtendedLineMethod::builder()
+        .with_rng(StepRng::new(1, 1))
+        .with_trailing_charset(CharacterSetType::TwoBit)
+        .with_pivot(20)
+        .with_framing(false)
+        .build()?;
+    let mut gap_index = gap_index_method(Variant::V1).with_framing(false).build()?;
+
+    let single_text = single.try_conceal(&cover, &mut payload.view_bits::<Msb0>().iter())?;
+    let gap_index_text = gap_index.try_conceal(&cover, &mut payload.view_bits::<Msb0>().iter())?;
+    let separator: &str = DEFAULT_LINE_SEPARATOR.into();
+    let modified_lines = |text: &str| {
+        text.split(separator)
+            .take_while(|line| line.trim_end() != *line || line.contains("  "))
+            .count()
+    };
+
+    assert!(gap_index.capacity(&cover) > single.capacity(&cover));
+    assert!(modified_lines(&gap_index_text) < modified_lines(&single_text));
+    Ok(())
+}
+
+#[rstest]
+fn spreads_lines_with_variable_bitrate(
+    cover_text_loader: ResourceLoader,
+) -> Result<(), Box<dyn Error>> {
+    let cover = cover_text_loader.load_resource(&PathBuf::from("long_text"));
+    let payload = b"Hi, panda!";
+    let mut method = gap_index_method(Variant::V3)
+        .with_line_spread(SpreadMode::Random)
+        .build()?;
+
+    let stego_text = method.try_conceal(&cover, &mut payload.view_bits::<Msb0>().iter())?;
+    let revealed: BitVec<Msb0, u8> = method.try_reveal(&stego_text)?;
+
+    assert_eq!(revealed.as_raw_slice(), payload);
+    Ok(())
+}
+
+#[rstest]
+fn skips_lines_without_gaps() -> Result<(), Box<dyn Error>> {
+    let mut method = ExtendedLineMethod::builder()
+        .with_rng(StepRng::new(1, 1))
+        .with_trailing_charset(CharacterSetType::OneBit)
+        .with_pivot(6)
+        .with_random_whitespace_mode(RandomWhitespaceMode::GapIndex)
+        .with_framing(false)
+        .build()?;
+
+    // Single word lines carry only the bits of line extension and trailing whitespace
+    assert_eq!(method.capacity("Panda bamboo forest"), 3 * 2);
+    let stego_text = method.try_conceal("Panda bamboo forest", &mut bits![0, 1].iter())?;
+
+    assert_eq!(stego_text, "Panda \nbamboo\nforest");
+    Ok(())
+}
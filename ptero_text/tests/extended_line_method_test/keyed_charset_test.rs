@@ -0,0 +1,81 @@
+use std::error::Error;
+
+use bitvec::prelude::*;
+use rand::rngs::mock::StepRng;
+use rstest::*;
+
+use ptero_common::method::SteganographyMethod;
+use ptero_text::extended_line_method::character_sets::{
+    CharacterSetType, GetCharacterSet, KeyedCharacterSet,
+};
+use ptero_text::extended_line_method::{ExtendedLineMethod, Variant};
+
+use crate::extended_line_method_test::*;
+use crate::test_resource::ResourceLoader;
+
+fn keyed_method(variant: Variant, passphrase: &str, salt: &str) -> ExtendedLineMethodBuilder {
+    ExtendedLineMethod::builder()
+        .with_rng(StepRng::new(1, 1))
+        .with_variant(variant)
+        .with_trailing_charset(KeyedCharacterSet::new(
+            CharacterSetType::FourBit.get_set(),
+            passphrase,
+            salt,
+        ))
+        .with_pivot(20)
+}
+
+#[rstest]
+#[case::variant_1(Variant::V1)]
+#[case::variant_2(Variant::V2)]
+#[case::variant_3(Variant::V3)]
+fn reveals_payload_concealed_with_keyed_charset(
+    cover_text_loader: ResourceLoader,
+    #[case] variant: Variant,
+) -> Result<(), Box<dyn Error>> {
+    let cover = cover_text_loader.load_resource(&PathBuf::from("long_text"));
+    let payload = b"Panda";
+    let mut method = keyed_method(variant, "bamboo", "forest").build()?;
+
+    let stego_text = method.try_conceal(&cover, &mut payload.view_bits::<Msb0>().iter())?;
+    let revealed: BitVec<Msb0, u8> = method.try_reveal(&stego_text)?;
+
+    assert_eq!(revealed.as_raw_slice(), payload);
+    Ok(())
+}
+
+#[rstest]
+fn uses_different_characters_than_plain_charset(
+    cover_text_loader: ResourceLoader,
+) -> Result<(), Box<dyn Error>> {
+    let cover = cover_text_loader.load_resource(&PathBuf::from("long_text"));
+    let payload = b"Panda";
+    let mut keyed = keyed_method(Variant::V1, "bamboo", "forest").build()?;
+    let mut plain = ExtendedLineMethod::builder()
+        .with_rng(StepRng::new(1, 1))
+        .with_trailing_charset(CharacterSetType::FourBit)
+        .with_pivot(20)
+        .build()?;
+
+    let keyed_stego_text = keyed.try_conceal(&cover, &mut payload.view_bits::<Msb0>().iter())?;
+    let plain_stego_text = plain.try_conceal(&cover, &mut payload.view_bits::<Msb0>().iter())?;
+
+    assert_ne!(keyed_stego_text, plain_stego_text);
+    Ok(())
+}
+
+#[rstest]
+fn fails_to_reveal_payload_with_different_key(
+    cover_text_loader: ResourceLoader,
+) -> Result<(), Box<dyn Error>> {
+    let cover = cover_text_loader.load_resource(&PathBuf::from("long_text"));
+    let payload = b"Panda";
+    let mut method = keyed_method(Variant::V1, "bamboo", "forest").build()?;
+    let mut other_method = keyed_method(Variant::V1, "bamboo", "mountain").build()?;
+
+    let stego_text = method.try_conceal(&cover, &mut payload.view_bits::<Msb0>().iter())?;
+    let revealed: Result<BitVec<Msb0, u8>, _> = other_method.try_reveal(&stego_text);
+
+    assert!(!matches!(revealed, Ok(data) if data.as_raw_slice() == payload));
+    Ok(())
+}
@@ -0,0 +1,139 @@
+use std::error::Error;
+
+use bitvec::prelude::*;
+use rand::rngs::mock::StepRng;
+use rstest::*;
+
+use ptero_common::config::ConfigurableBuilder;
+use ptero_common::method::SteganographyMethod;
+use ptero_text::extended_line_method::character_sets::CharacterSetType;
+use ptero_text::extended_line_method::{
+    ConcealError, ExtendedLineMethod, RandomWhitespaceMode, Variant,
+};
+use ptero_text::line_separator::DEFAULT_LINE_SEPARATOR;
+
+use crate::extended_line_method_test::*;
+use crate::test_resource::ResourceLoader;
+
+fn overflow_method(variant: Variant, max_overflow: usize) -> ExtendedLineMethodBuilder {
+    ExtendedLineMethod::builder()
+        .with_rng(StepRng::new(1, 1))
+        .with_variant(variant)
+        .with_trailing_charset(CharacterSetType::TwoBit)
+        .with_pivot(20)
+        .with_max_line_overflow(max_overflow)
+}
+
+#[rstest]
+#[case::variant_1(Variant::V1)]
+#[case::variant_2(Variant::V2)]
+#[case::variant_3(Variant::V3)]
+fn reveals_payload_concealed_in_extension_length(
+    cover_text_loader: ResourceLoader,
+    #[case] variant: Variant,
+    #[values(3, 7)] max_overflow: usize,
+) -> Result<(), Box<dyn Error>> {
+    let cover = cover_text_loader.load_resource(&PathBuf::from("long_text"));
+    let payload = b"Panda";
+    let mut method = overflow_method(variant, max_overflow).build()?;
+
+    let stego_text = method.try_conceal(&cover, &mut payload.view_bits::<Msb0>().iter())?;
+    let revealed: BitVec<Msb0, u8> = method.try_reveal(&stego_text)?;
+
+    assert_eq!(revealed.as_raw_slice(), payload);
+    Ok(())
+}
+
+#[rstest]
+#[case::variant_1(Variant::V1)]
+#[case::variant_2(Variant::V2)]
+#[case::variant_3(Variant::V3)]
+fn reveals_payload_with_gap_indices(
+    cover_text_loader: ResourceLoader,
+    #[case] variant: Variant,
+) -> Result<(), Box<dyn Error>> {
+    let cover = cover_text_loader.load_resource(&PathBuf::from("long_text"));
+    let payload = b"Hi, panda!";
+    let mut method = overflow_method(variant, 3)
+        .with_random_whitespace_mode(RandomWhitespaceMode::GapIndex)
+        .build()?;
+
+    let stego_text = method.try_conceal(&cover, &mut payload.view_bits::<Msb0>().iter())?;
+    let revealed: BitVec<Msb0, u8> = method.try_reveal(&stego_text)?;
+
+    assert_eq!(revealed.as_raw_slice(), payload);
+    Ok(())
+}
+
+#[rstest]
+fn appends_as_many_words_as_the_concealed_number() -> Result<(), Box<dyn Error>> {
+    let cover = "aa bb cc dd ee ff gg hh ii jj kk ll mm nn oo pp qq rr ss tt uu vv ww xx yy zz";
+    // Line extension takes the first two bits and conceals them as number of words
+    let byte = [0b1100_0000u8];
+    let data = &byte.view_bits::<Msb0>()[..3];
+    let mut method = ExtendedLineMethod::builder()
+        .with_rng(StepRng::new(1, 1))
+        .with_variant(Variant::V1)
+        .with_trailing_charset(CharacterSetType::OneBit)
+        .with_pivot(8)
+        .with_max_line_overflow(3)
+        .with_framing(false)
+        .build()?;
+
+    let stego_text = method.try_conceal(cover, &mut data.iter())?;
+    let separator: &str = DEFAULT_LINE_SEPARATOR.into();
+    let first_line = stego_text.split(separator).next().unwrap_or_default();
+
+    assert_eq!(first_line, "aa bb cc dd ee ff");
+    Ok(())
+}
+
+#[rstest]
+fn conceals_data_as_large_as_reported_capacity(
+    cover_text_loader: ResourceLoader,
+    #[values(3, 7)] max_overflow: usize,
+) -> Result<(), Box<dyn Error>> {
+    let cover = cover_text_loader.load_resource(&PathBuf::from("long_text"));
+    let mut method = overflow_method(Variant::V1, max_overflow)
+        .with_framing(false)
+        .build()?;
+    let capacity = method.capacity(&cover);
+    // Words taken by the extension aren't available to the next lines
+    let data = (0..capacity)
+        .map(|index| (index * 7) % 3 == 0)
+        .collect::<BitVec<Msb0, u8>>();
+
+    assert!(method.try_conceal(&cover, &mut data.iter()).is_ok());
+    Ok(())
+}
+
+#[rstest]
+#[case::variant_2(Variant::V2)]
+#[case::variant_3(Variant::V3)]
+fn errors_with_cover_too_small_when_extension_leaves_single_word_line(
+    cover_text_loader: ResourceLoader,
+    #[case] variant: Variant,
+) -> Result<(), Box<dyn Error>> {
+    let cover = cover_text_loader.load_resource(&PathBuf::from("long_text"));
+    let mut method = overflow_method(variant, 3).build()?;
+    for size in 1..=20 {
+        let payload = vec![0xA5 ^ size as u8; size];
+
+        let result = method.try_conceal(&cover, &mut payload.view_bits::<Msb0>().iter());
+
+        assert!(
+            matches!(result, Ok(_) | Err(ConcealError::CoverTextTooSmall { .. })),
+            "{} bytes failed with {:?}",
+            size,
+            result
+        );
+    }
+    Ok(())
+}
+
+#[rstest]
+fn fails_when_overflow_is_zero() {
+    let result = overflow_method(Variant::V1, 0).build();
+
+    assert!(result.is_err());
+}
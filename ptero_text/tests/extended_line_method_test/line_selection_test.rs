@@ -0,0 +1,113 @@
+use std::error::Error;
+
+use bitvec::prelude::*;
+use rand::rngs::mock::StepRng;
+use rstest::*;
+
+use ptero_common::config::ConfigurableBuilder;
+use ptero_common::method::SteganographyMethod;
+use ptero_text::extended_line_method::character_sets::CharacterSetType;
+use ptero_text::extended_line_method::{ExtendedLineMethod, Variant};
+use ptero_text::line_separator::DEFAULT_LINE_SEPARATOR;
+
+use crate::extended_line_method_test::*;
+use crate::test_resource::ResourceLoader;
+
+fn keyed_method(variant: Variant, secret: &str) -> ExtendedLineMethodBuilder {
+    ExtendedLineMethod::builder()
+        .with_rng(StepRng::new(1, 1))
+        .with_variant(variant)
+        .with_trailing_charset(CharacterSetType::TwoBit)
+        .with_pivot(20)
+        .with_line_selection_key(secret)
+}
+
+#[rstest]
+#[case::variant_1(Variant::V1)]
+#[case::variant_2(Variant::V2)]
+#[case::variant_3(Variant::V3)]
+fn reveals_payload_concealed_in_selected_lines(
+    cover_text_loader: ResourceLoader,
+    #[case] variant: Variant,
+) -> Result<(), Box<dyn Error>> {
+    let cover = cover_text_loader.load_resource(&PathBuf::from("long_text"));
+    let payload = b"Hi, panda!";
+    let mut method = keyed_method(variant, "bamboo").build()?;
+    let stego_text = method.try_conceal(&cover, &mut payload.view_bits::<Msb0>().iter())?;
+
+    let revealed: BitVec<Msb0, u8> = method.try_reveal(&stego_text)?;
+
+    assert_eq!(revealed.as_raw_slice(), payload);
+    Ok(())
+}
+
+#[rstest]
+fn leaves_decoy_lines_between_the_selected_ones(
+    cover_text_loader: ResourceLoader,
+) -> Result<(), Box<dyn Error>> {
+    let cover = cover_text_loader.load_resource(&PathBuf::from("long_text"));
+    // All ones make every carrying line visibly modified
+    let payload = [255u8; 4];
+    let mut keyed = keyed_method(Variant::V1, "bamboo")
+        .with_framing(false)
+        .build()?;
+    let mut unkeyed = ExtendedLineMethod::builder()
+        .with_rng(StepRng::new(1, 1))
+        .with_trailing_charset(CharacterSetType::TwoBit)
+        .with_pivot(20)
+        .with_framing(false)
+        .build()?;
+
+    let keyed_text = keyed.try_conceal(&cover, &mut payload.view_bits::<Msb0>().iter())?;
+    let unkeyed_text = unkeyed.try_conceal(&cover, &mut payload.view_bits::<Msb0>().iter())?;
+    let separator: &str = DEFAULT_LINE_SEPARATOR.into();
+    let modified_lines = |text: &str| {
+        text.split(separator)
+            .map(|line| line.trim_end() != line || line.contains("  "))
+            .collect::<Vec<bool>>()
+    };
+    let unkeyed_lines = modified_lines(&unkeyed_text);
+    let keyed_lines = modified_lines(&keyed_text);
+    let last_modified = keyed_lines
+        .iter()
+        .rposition(|is_modified| *is_modified)
+        .unwrap();
+
+    assert!(
+        unkeyed_lines
+            .iter()
+            .take_while(|is_modified| **is_modified)
+            .count()
+            >= 8
+    );
+    assert!(keyed_lines[..last_modified].contains(&false));
+    Ok(())
+}
+
+#[rstest]
+fn does_not_reveal_payload_with_different_secret(
+    cover_text_loader: ResourceLoader,
+) -> Result<(), Box<dyn Error>> {
+    let cover = cover_text_loader.load_resource(&PathBuf::from("long_text"));
+    let payload = b"Hi, panda!";
+    let mut method = keyed_method(Variant::V1, "bamboo").build()?;
+    let stego_text = method.try_conceal(&cover, &mut payload.view_bits::<Msb0>().iter())?;
+
+    let mut other_method = keyed_method(Variant::V1, "eucalyptus").build()?;
+    let mut unkeyed_method = ExtendedLineMethod::builder()
+        .with_rng(StepRng::new(1, 1))
+        .with_trailing_charset(CharacterSetType::TwoBit)
+        .with_pivot(20)
+        .build()?;
+
+    let expected: BitVec<Msb0, u8> = BitVec::from_slice(payload)?;
+    assert_ne!(
+        other_method.try_reveal::<Msb0, u8>(&stego_text),
+        Ok(expected.clone())
+    );
+    assert_ne!(
+        unkeyed_method.try_reveal::<Msb0, u8>(&stego_text),
+        Ok(expected)
+    );
+    Ok(())
+}
@@ -0,0 +1,96 @@
+use std::error::Error;
+
+use bitvec::prelude::*;
+use rand::rngs::mock::StepRng;
+use rstest::*;
+
+use ptero_common::config::ConfigurableBuilder;
+use ptero_common::method::SteganographyMethod;
+use ptero_common::selection::{SelectionError, SpreadMode, SPREAD_DESCRIPTOR_LENGTH};
+use ptero_text::extended_line_method::character_sets::CharacterSetType;
+use ptero_text::extended_line_method::{ConcealError, ExtendedLineMethod, Variant};
+use ptero_text::line_separator::DEFAULT_LINE_SEPARATOR;
+
+use crate::extended_line_method_test::*;
+use crate::test_resource::ResourceLoader;
+
+fn spread_method(variant: Variant, mode: SpreadMode) -> ExtendedLineMethodBuilder {
+    ExtendedLineMethod::builder()
+        .with_rng(StepRng::new(1, 1))
+        .with_variant(variant)
+        .with_trailing_charset(CharacterSetType::TwoBit)
+        .with_pivot(20)
+        .with_line_spread(mode)
+}
+
+#[rstest]
+#[case::uniform_variant_1(Variant::V1, SpreadMode::Uniform)]
+#[case::uniform_variant_2(Variant::V2, SpreadMode::Uniform)]
+#[case::uniform_variant_3(Variant::V3, SpreadMode::Uniform)]
+#[case::random_variant_1(Variant::V1, SpreadMode::Random)]
+#[case::random_variant_2(Variant::V2, SpreadMode::Random)]
+#[case::random_variant_3(Variant::V3, SpreadMode::Random)]
+fn reveals_payload_spread_through_the_cover(
+    cover_text_loader: ResourceLoader,
+    #[case] variant: Variant,
+    #[case] mode: SpreadMode,
+) -> Result<(), Box<dyn Error>> {
+    let cover = cover_text_loader.load_resource(&PathBuf::from("long_text"));
+    let payload = b"Hi, panda!";
+    let mut method = spread_method(variant, mode).build()?;
+    let stego_text = method.try_conceal(&cover, &mut payload.view_bits::<Msb0>().iter())?;
+
+    let revealed: BitVec<Msb0, u8> = method.try_reveal(&stego_text)?;
+
+    assert_eq!(revealed.as_raw_slice(), payload);
+    Ok(())
+}
+
+#[rstest]
+fn spreads_modified_lines_till_the_end_of_the_cover(
+    cover_text_loader: ResourceLoader,
+) -> Result<(), Box<dyn Error>> {
+    let cover = cover_text_loader.load_resource(&PathBuf::from("long_text"));
+    // All ones make every carrying line visibly modified
+    let payload = [255u8; 4];
+    let mut spread = spread_method(Variant::V1, SpreadMode::Uniform)
+        .with_framing(false)
+        .build()?;
+    let mut dense = ExtendedLineMethod::builder()
+        .with_rng(StepRng::new(1, 1))
+        .with_trailing_charset(CharacterSetType::TwoBit)
+        .with_pivot(20)
+        .with_framing(false)
+        .build()?;
+
+    let spread_text = spread.try_conceal(&cover, &mut payload.view_bits::<Msb0>().iter())?;
+    let dense_text = dense.try_conceal(&cover, &mut payload.view_bits::<Msb0>().iter())?;
+    let separator: &str = DEFAULT_LINE_SEPARATOR.into();
+    let last_modified_line = |text: &str, skipped_lines: usize| {
+        text.split(separator)
+            .enumerate()
+            .skip(skipped_lines)
+            .filter(|(_, line)| line.trim_end() != *line || line.contains("  "))
+            .map(|(index, _)| index)
+            .last()
+            .unwrap()
+    };
+    let line_count = dense_text.split(separator).count();
+
+    assert!(last_modified_line(&dense_text, 0) < line_count / 4);
+    assert!(last_modified_line(&spread_text, SPREAD_DESCRIPTOR_LENGTH) > line_count / 2);
+    Ok(())
+}
+
+#[rstest]
+fn fails_to_reveal_text_without_spread_descriptor() -> Result<(), Box<dyn Error>> {
+    let mut method = spread_method(Variant::V1, SpreadMode::Random).build()?;
+
+    assert_eq!(
+        method.try_reveal::<Msb0, u8>("A little panda\nhas fallen"),
+        Err(ConcealError::InvalidSpread {
+            source: SelectionError::InvalidSpread { byte: 0 }
+        })
+    );
+    Ok(())
+}
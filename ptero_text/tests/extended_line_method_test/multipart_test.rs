@@ -0,0 +1,106 @@
+use std::error::Error;
+
+use bitvec::prelude::*;
+use rand::rngs::mock::StepRng;
+use rstest::*;
+
+use ptero_common::method::SteganographyMethod;
+use ptero_common::multipart::MultipartError;
+use ptero_text::extended_line_method::character_sets::CharacterSetType;
+use ptero_text::extended_line_method::{ConcealError, ExtendedLineMethod, Variant};
+
+use crate::extended_line_method_test::*;
+use crate::test_resource::ResourceLoader;
+
+/// Splits the long text into 3 covers, each too small for the payload on its own.
+fn split_covers(cover_text_loader: ResourceLoader) -> Vec<String> {
+    let cover = cover_text_loader.load_resource(&PathBuf::from("long_text"));
+    let words = cover.split_whitespace().collect::<Vec<&str>>();
+    words
+        .chunks(words.len().div_ceil(3))
+        .map(|chunk| chunk.join(" "))
+        .collect()
+}
+
+fn multipart_method(variant: Variant) -> Result<ExtendedLineMethod, Box<dyn Error>> {
+    Ok(ExtendedLineMethod::builder()
+        .with_rng(StepRng::new(1, 1))
+        .with_variant(variant)
+        .with_trailing_charset(CharacterSetType::Full)
+        .with_pivot(20)
+        .build()?)
+}
+
+#[rstest]
+#[case::variant_1(Variant::V1)]
+#[case::variant_2(Variant::V2)]
+#[case::variant_3(Variant::V3)]
+fn reveals_payload_split_across_cover_texts_given_in_any_order(
+    cover_text_loader: ResourceLoader,
+    #[case] variant: Variant,
+) -> Result<(), Box<dyn Error>> {
+    let covers = split_covers(cover_text_loader);
+    let covers = covers.iter().map(String::as_str).collect::<Vec<&str>>();
+    let payload = b"Meet me at the old bridge";
+    let mut method = multipart_method(variant)?;
+
+    // A single cover is too small for the payload
+    assert!(method
+        .try_conceal(covers[0], &mut payload.view_bits::<Msb0>().iter())
+        .is_err());
+    let stego_texts =
+        method.try_conceal_in_parts(&covers, &mut payload.view_bits::<Msb0>().iter())?;
+    let mut shuffled = stego_texts
+        .iter()
+        .map(String::as_str)
+        .collect::<Vec<&str>>();
+    shuffled.rotate_left(1);
+
+    assert!(stego_texts.len() > 1);
+    assert_eq!(method.try_reveal_parts(&shuffled)?, payload.to_vec());
+    Ok(())
+}
+
+#[rstest]
+fn reports_missing_parts(cover_text_loader: ResourceLoader) -> Result<(), Box<dyn Error>> {
+    let covers = split_covers(cover_text_loader);
+    let covers = covers.iter().map(String::as_str).collect::<Vec<&str>>();
+    let payload = b"Meet me at the old bridge";
+    let mut method = multipart_method(Variant::V1)?;
+
+    let stego_texts =
+        method.try_conceal_in_parts(&covers, &mut payload.view_bits::<Msb0>().iter())?;
+    let total = stego_texts.len();
+    let without_first = stego_texts[1..]
+        .iter()
+        .map(String::as_str)
+        .collect::<Vec<&str>>();
+
+    assert!(matches!(
+        method.try_reveal_parts(&without_first),
+        Err(ConcealError::InvalidParts {
+            source: MultipartError::MissingParts { missing, total: parts_total, .. }
+        }) if missing == vec![1] && parts_total == total
+    ));
+    Ok(())
+}
+
+#[rstest]
+fn errors_when_all_cover_texts_are_too_small(
+    cover_text_loader: ResourceLoader,
+) -> Result<(), Box<dyn Error>> {
+    let covers = split_covers(cover_text_loader);
+    let covers = covers.iter().map(String::as_str).collect::<Vec<&str>>();
+    let payload = [42u8; 200];
+    let mut method = multipart_method(Variant::V1)?;
+
+    let result = method.try_conceal_in_parts(&covers, &mut payload.view_bits::<Msb0>().iter());
+
+    assert!(matches!(
+        result,
+        Err(ConcealError::InvalidParts {
+            source: MultipartError::CoversTooSmall { .. }
+        })
+    ));
+    Ok(())
+}
@@ -0,0 +1,94 @@
+use std::error::Error;
+
+use bitvec::prelude::*;
+use rand::rngs::mock::StepRng;
+use rstest::*;
+
+use ptero_common::method::SteganographyMethod;
+use ptero_text::extended_line_method::character_sets::CharacterSetType;
+use ptero_text::extended_line_method::{ExtendedLineMethod, RandomWhitespaceMode, Variant};
+
+use crate::extended_line_method_test::*;
+use crate::test_resource::ResourceLoader;
+
+fn rotating_method(secret: &str) -> ExtendedLineMethodBuilder {
+    ExtendedLineMethod::builder()
+        .with_rng(StepRng::new(1, 1))
+        .with_trailing_charset(CharacterSetType::TwoBit)
+        .with_pivot(20)
+        .with_variant_rotation_key(secret)
+}
+
+#[rstest]
+fn reveals_payload_concealed_with_rotated_variants(
+    cover_text_loader: ResourceLoader,
+    #[values(RandomWhitespaceMode::Single, RandomWhitespaceMode::GapIndex)]
+    mode: RandomWhitespaceMode,
+    #[values(1, 3)] max_overflow: usize,
+) -> Result<(), Box<dyn Error>> {
+    let cover = cover_text_loader.load_resource(&PathBuf::from("long_text"));
+    let payload = b"Panda";
+    let mut method = rotating_method("bamboo")
+        .with_random_whitespace_mode(mode)
+        .with_max_line_overflow(max_overflow)
+        .build()?;
+
+    let stego_text = method.try_conceal(&cover, &mut payload.view_bits::<Msb0>().iter())?;
+    let revealed: BitVec<Msb0, u8> = method.try_reveal(&stego_text)?;
+
+    assert_eq!(revealed.as_raw_slice(), payload);
+    Ok(())
+}
+
+#[rstest]
+fn reveals_payload_regardless_of_the_variant(
+    cover_text_loader: ResourceLoader,
+) -> Result<(), Box<dyn Error>> {
+    let cover = cover_text_loader.load_resource(&PathBuf::from("long_text"));
+    let payload = b"Panda";
+    let mut method = rotating_method("bamboo")
+        .with_variant(Variant::V2)
+        .build()?;
+    let mut other_variant_method = rotating_method("bamboo")
+        .with_variant(Variant::V1)
+        .build()?;
+
+    let stego_text = method.try_conceal(&cover, &mut payload.view_bits::<Msb0>().iter())?;
+    let revealed: BitVec<Msb0, u8> = other_variant_method.try_reveal(&stego_text)?;
+
+    assert_eq!(revealed.as_raw_slice(), payload);
+    Ok(())
+}
+
+#[rstest]
+fn reveals_payload_in_selected_lines(
+    cover_text_loader: ResourceLoader,
+) -> Result<(), Box<dyn Error>> {
+    let cover = cover_text_loader.load_resource(&PathBuf::from("long_text"));
+    let payload = b"Hi";
+    let mut method = rotating_method("bamboo")
+        .with_line_selection_key("eucalyptus")
+        .build()?;
+
+    let stego_text = method.try_conceal(&cover, &mut payload.view_bits::<Msb0>().iter())?;
+    let revealed: BitVec<Msb0, u8> = method.try_reveal(&stego_text)?;
+
+    assert_eq!(revealed.as_raw_slice(), payload);
+    Ok(())
+}
+
+#[rstest]
+fn fails_to_reveal_payload_with_different_secret(
+    cover_text_loader: ResourceLoader,
+) -> Result<(), Box<dyn Error>> {
+    let cover = cover_text_loader.load_resource(&PathBuf::from("long_text"));
+    let payload = b"Panda";
+    let mut method = rotating_method("bamboo").build()?;
+    let mut other_method = rotating_method("eucalyptus").build()?;
+
+    let stego_text = method.try_conceal(&cover, &mut payload.view_bits::<Msb0>().iter())?;
+    let revealed: Result<BitVec<Msb0, u8>, _> = other_method.try_reveal(&stego_text);
+
+    assert!(!matches!(revealed, Ok(data) if data.as_raw_slice() == payload));
+    Ok(())
+}
@@ -0,0 +1,106 @@
+use std::error::Error;
+use std::path::PathBuf;
+
+use bitvec::prelude::*;
+use rstest::*;
+
+use ptero_common::config::ConfigurableBuilder;
+use ptero_common::method::SteganographyMethod;
+use ptero_text::homoglyph_method::homoglyph_tables::{GetHomoglyphTable, HomoglyphTableType};
+use ptero_text::homoglyph_method::{ConcealError, HomoglyphMethod};
+
+use crate::test_resource::{Listener, ResourceLoader};
+
+#[fixture]
+pub fn cover_text_loader() -> ResourceLoader {
+    let dir_path = PathBuf::new().join("resources").join("cover_texts");
+
+    ResourceLoader::new(&dir_path)
+}
+
+struct OnlyLetterO;
+
+impl GetHomoglyphTable for OnlyLetterO {
+    fn get_table(&self) -> &[(char, char)] {
+        &[('o', '\u{03BF}')]
+    }
+}
+
+#[rstest]
+#[case::cyrillic(HomoglyphTableType::Cyrillic)]
+#[case::greek(HomoglyphTableType::Greek)]
+fn reveals_payload_without_changing_the_layout(
+    cover_text_loader: ResourceLoader,
+    #[case] table: HomoglyphTableType,
+) -> Result<(), Box<dyn Error>> {
+    let cover = cover_text_loader.load_resource(&PathBuf::from("long_text"));
+    // Greek table mostly covers the capital letters
+    let payload = b"Hi!";
+    let mut method = HomoglyphMethod::builder()
+        .with_homoglyph_table(table)
+        .with_checksum(true)
+        .build()?;
+
+    let stego_text = method.try_conceal(&cover, &mut payload.view_bits::<Msb0>().iter())?;
+    let revealed: BitVec<Msb0, u8> = method.try_reveal(&stego_text)?;
+
+    assert_eq!(revealed.as_raw_slice(), payload);
+    assert_ne!(stego_text, cover);
+    assert!(stego_text
+        .lines()
+        .zip(cover.lines())
+        .all(
+            |(stego_line, cover_line)| stego_line.chars().count() == cover_line.chars().count()
+                && stego_line.split_whitespace().count() == cover_line.split_whitespace().count()
+        ));
+    Ok(())
+}
+
+#[rstest]
+fn reports_progress_through_the_notifier(
+    cover_text_loader: ResourceLoader,
+) -> Result<(), Box<dyn Error>> {
+    let cover = cover_text_loader.load_resource(&PathBuf::from("long_text"));
+    let listener = Listener::shared();
+    let mut method = HomoglyphMethod::builder().build()?;
+    method.subscribe(listener.clone());
+
+    method.try_conceal(&cover, &mut b"panda".view_bits::<Msb0>().iter())?;
+
+    // Payload frame header is concealed as well
+    assert_eq!(listener.borrow().amount_written, 7 * 8);
+    assert!(listener.borrow().has_finished);
+    Ok(())
+}
+
+#[rstest]
+fn uses_custom_homoglyph_table() -> Result<(), Box<dyn Error>> {
+    let cover = "Look, a foolproof pool of wood, o o o o o o o o o o o o o o o o o o o o o";
+    let mut method = HomoglyphMethod::builder()
+        .with_homoglyph_table(OnlyLetterO)
+        .with_framing(false)
+        .build()?;
+
+    assert_eq!(method.capacity(cover), 32);
+    let stego_text = method.try_conceal(cover, &mut [0b1010_0000u8].view_bits::<Msb0>().iter())?;
+
+    assert!(stego_text.starts_with("L\u{03BF}ok, a f\u{03BF}olproof"));
+    let revealed: BitVec<Msb0, u8> = method.try_reveal(&stego_text)?;
+    assert_eq!(revealed.as_raw_slice()[0], 0b1010_0000);
+    Ok(())
+}
+
+#[rstest]
+fn errors_when_cover_text_is_too_small() -> Result<(), Box<dyn Error>> {
+    let mut method = HomoglyphMethod::builder().build()?;
+
+    let result = method.try_conceal("A panda", &mut b"panda".view_bits::<Msb0>().iter());
+
+    assert_eq!(
+        result,
+        Err(ConcealError::CoverTextTooSmall {
+            remaining_data_size: 7 * 8 - 4
+        })
+    );
+    Ok(())
+}
@@ -0,0 +1,166 @@
+use std::error::Error;
+use std::path::PathBuf;
+
+use bitvec::prelude::*;
+use rand::rngs::mock::StepRng;
+use rstest::*;
+use unicode_segmentation::UnicodeSegmentation;
+
+use ptero_common::config::ConfigurableBuilder;
+use ptero_common::method::{MethodError, SteganographyMethod};
+use ptero_text::justification_method::{ConcealError, JustificationMethod};
+use ptero_text::line_separator::LineSeparatorType;
+
+use crate::test_resource::{Listener, ResourceLoader};
+
+#[fixture]
+pub fn cover_text_loader() -> ResourceLoader {
+    let dir_path = PathBuf::new().join("resources").join("cover_texts");
+
+    ResourceLoader::new(&dir_path)
+}
+
+fn justification_method(pivot: usize) -> JustificationMethod {
+    JustificationMethod::builder()
+        .with_rng(StepRng::new(1, 1))
+        .with_pivot(pivot)
+        .with_line_separator(LineSeparatorType::Unix)
+        .build()
+        .unwrap()
+}
+
+#[rstest]
+#[case("long_text", 40)]
+#[case("long_text", 80)]
+#[case("unicode_text", 60)]
+fn reveals_payload_concealed_in_justified_lines(
+    cover_text_loader: ResourceLoader,
+    #[case] resource: &str,
+    #[case] pivot: usize,
+) -> Result<(), Box<dyn Error>> {
+    let cover = cover_text_loader.load_resource(&PathBuf::from(resource));
+    let payload = b"Hi";
+    let mut method = justification_method(pivot);
+
+    let stego_text = method.try_conceal(&cover, &mut payload.view_bits::<Msb0>().iter())?;
+    let revealed: BitVec<Msb0, u8> = method.try_reveal(&stego_text)?;
+
+    assert_eq!(revealed.as_raw_slice(), payload);
+    Ok(())
+}
+
+#[rstest]
+fn justifies_every_line_but_the_last_one(
+    cover_text_loader: ResourceLoader,
+) -> Result<(), Box<dyn Error>> {
+    let cover = cover_text_loader.load_resource(&PathBuf::from("long_text"));
+    let mut method = justification_method(40);
+
+    let stego_text = method.try_conceal(&cover, &mut b"Hi!".view_bits::<Msb0>().iter())?;
+    let lines = stego_text.lines().collect::<Vec<&str>>();
+
+    for line in &lines[..lines.len() - 1] {
+        assert_eq!(line.graphemes(true).count(), 40);
+    }
+    assert!(!lines[lines.len() - 1].contains("  "));
+    assert_eq!(
+        stego_text.split_whitespace().collect::<Vec<&str>>(),
+        cover.split_whitespace().collect::<Vec<&str>>()
+    );
+    Ok(())
+}
+
+#[rstest]
+fn spreads_extra_spaces_as_evenly_as_possible() -> Result<(), Box<dyn Error>> {
+    // "aa bb cc dd" needs 4 extra spaces for 3 gaps - every gap gets one and one of them two
+    let cover = "aa bb cc dd eeeee";
+    let mut method = JustificationMethod::builder()
+        .with_pivot(15)
+        .with_line_separator(LineSeparatorType::Unix)
+        .with_framing(false)
+        .build()?;
+
+    let stego_text = method.try_conceal(cover, &mut bits![0].iter())?;
+    let revealed: BitVec<Msb0, u8> = method.try_reveal(&stego_text)?;
+
+    assert_eq!(stego_text, "aa   bb  cc  dd\neeeee");
+    assert_eq!(method.capacity(cover), 1);
+    assert_eq!(revealed, bits![0]);
+    Ok(())
+}
+
+#[rstest]
+fn conceals_every_rank_of_the_line() -> Result<(), Box<dyn Error>> {
+    // 8 gaps with 4 extra spaces give C(8, 4) = 70 choices, so the line carries 6 bits
+    let cover = "a b c d e f g h i panda";
+    let mut method = JustificationMethod::builder()
+        .with_pivot(21)
+        .with_line_separator(LineSeparatorType::Unix)
+        .with_framing(false)
+        .build()?;
+    assert_eq!(method.capacity(cover), 6);
+
+    for rank in 0u8..64 {
+        let bytes = [rank << 2];
+        let data = &bytes.view_bits::<Msb0>()[..6];
+        let stego_text = method.try_conceal(cover, &mut data.iter())?;
+        let revealed: BitVec<Msb0, u8> = method.try_reveal(&stego_text)?;
+
+        assert_eq!(revealed, data);
+    }
+    Ok(())
+}
+
+#[rstest]
+fn reports_progress_through_the_notifier(
+    cover_text_loader: ResourceLoader,
+) -> Result<(), Box<dyn Error>> {
+    let cover = cover_text_loader.load_resource(&PathBuf::from("long_text"));
+    let listener = Listener::shared();
+    let mut method = JustificationMethod::builder()
+        .with_pivot(40)
+        .with_framing(false)
+        .build()?;
+    method.subscribe(listener.clone());
+
+    method.try_conceal(&cover, &mut bits![1, 1, 0, 1, 0].iter())?;
+
+    assert_eq!(listener.borrow().amount_written, 5);
+    assert!(listener.borrow().has_finished);
+    Ok(())
+}
+
+#[rstest]
+fn errors_when_cover_text_is_too_small() -> Result<(), Box<dyn Error>> {
+    let mut method = JustificationMethod::builder()
+        .with_pivot(15)
+        .with_framing(false)
+        .build()?;
+
+    let result = method.try_conceal("aa bb cc dd eeeee", &mut bits![1, 0, 1].iter());
+
+    assert_eq!(
+        result,
+        Err(MethodError::CoverTextTooSmall {
+            remaining_data_size: 2
+        }
+        .into())
+    );
+    Ok(())
+}
+
+#[rstest]
+fn errors_when_word_is_longer_than_pivot() -> Result<(), Box<dyn Error>> {
+    let mut method = JustificationMethod::builder().with_pivot(5).build()?;
+
+    let result = method.try_conceal("A little panda", &mut b"!".view_bits::<Msb0>().iter());
+
+    assert_eq!(
+        result,
+        Err(ConcealError::PivotTooSmall {
+            word: "little".to_string(),
+            pivot: 5
+        })
+    );
+    Ok(())
+}
@@ -0,0 +1,119 @@
+use std::error::Error;
+
+use bitvec::prelude::*;
+use rstest::*;
+use unicode_normalization::UnicodeNormalization;
+use unicode_segmentation::UnicodeSegmentation;
+
+use ptero_common::config::ConfigurableBuilder;
+use ptero_common::method::{MethodError, SteganographyMethod};
+use ptero_text::normalization_method::{ConcealError, NormalizationForm, NormalizationMethod};
+
+use crate::test_resource::Listener;
+
+const POLISH_POST: &str =
+    "Wczoraj wieczorem poszliśmy na spacer nad rzekę. Księżyc świecił jasno, \
+    a w oddali słychać było żaby. Później zjedliśmy pyszną szarlotkę z bitą śmietaną \
+    i wypiliśmy gorącą herbatę. Następnym razem zabierzemy też psa, bo uwielbia łąki! \
+    Pogoda ma się utrzymać przez cały tydzień, więc może wybierzemy się też nad jezioro.";
+const FRENCH_POST: &str = "Après une journée très chargée, j'ai enfin pris un café crème \
+    à la terrasse. Le garçon m'a conseillé la crème brûlée, qui était délicieuse. \
+    Ensuite, je suis allé à la librairie où j'ai trouvé un vieux roman illustré. \
+    Le libraire, très aimable, m'a même prêté une édition rare des poèmes de Verlaine, annotée à la main.";
+
+#[rstest]
+#[case(POLISH_POST)]
+#[case(FRENCH_POST)]
+fn reveals_payload_concealed_in_accented_text(#[case] cover: &str) -> Result<(), Box<dyn Error>> {
+    let mut method = NormalizationMethod::builder().build()?;
+
+    let stego_text = method.try_conceal(cover, &mut b"!".view_bits::<Msb0>().iter())?;
+    let revealed: BitVec<Msb0, u8> = method.try_reveal(&stego_text)?;
+
+    assert_eq!(revealed.as_raw_slice(), b"!");
+    assert_ne!(stego_text, cover);
+    assert_eq!(stego_text.nfc().collect::<String>(), cover);
+    assert_eq!(
+        stego_text.graphemes(true).count(),
+        cover.graphemes(true).count()
+    );
+    Ok(())
+}
+
+#[rstest]
+fn conceals_in_decomposed_cover() -> Result<(), Box<dyn Error>> {
+    let cover = POLISH_POST.nfd().collect::<String>();
+    let mut method = NormalizationMethod::builder().with_framing(false).build()?;
+    let data: BitVec<Msb0, u8> = (0..method.capacity(&cover))
+        .map(|index| index % 3 != 0)
+        .collect();
+
+    let stego_text = method.try_conceal(&cover, &mut data.iter())?;
+    let revealed: BitVec<Msb0, u8> = method.try_reveal(&stego_text)?;
+
+    assert_eq!(revealed, data);
+    assert_eq!(stego_text.nfd().collect::<String>(), cover);
+    Ok(())
+}
+
+#[rstest]
+#[case(NormalizationForm::Nfc)]
+#[case(NormalizationForm::Nfd)]
+fn detects_normalised_stego_text(#[case] form: NormalizationForm) -> Result<(), Box<dyn Error>> {
+    let mut method = NormalizationMethod::builder().build()?;
+    let stego_text = method.try_conceal(POLISH_POST, &mut b"!".view_bits::<Msb0>().iter())?;
+
+    let normalised = match form {
+        NormalizationForm::Nfc => stego_text.nfc().collect::<String>(),
+        NormalizationForm::Nfd => stego_text.nfd().collect::<String>(),
+    };
+
+    assert_eq!(method.detect_normalization(&stego_text), None);
+    assert_eq!(method.detect_normalization(&normalised), Some(form));
+    assert_eq!(
+        method.try_reveal::<Msb0, u8>(&normalised),
+        Err(ConcealError::TextNormalized { form })
+    );
+    Ok(())
+}
+
+#[rstest]
+fn reports_progress_through_the_notifier() -> Result<(), Box<dyn Error>> {
+    let listener = Listener::shared();
+    let mut method = NormalizationMethod::builder().with_framing(false).build()?;
+    method.subscribe(listener.clone());
+
+    method.try_conceal(FRENCH_POST, &mut bits![1, 1, 0, 1, 0].iter())?;
+
+    // The marker is not a part of the data
+    assert_eq!(listener.borrow().amount_written, 5);
+    assert!(listener.borrow().has_finished);
+    Ok(())
+}
+
+#[rstest]
+fn errors_when_cover_text_is_too_small() -> Result<(), Box<dyn Error>> {
+    let mut method = NormalizationMethod::builder().build()?;
+
+    let result = method.try_conceal(
+        "Zażółć gęślą jaźń",
+        &mut b"panda".view_bits::<Msb0>().iter(),
+    );
+    let plain_result = method.try_conceal("Plain panda", &mut b"panda".view_bits::<Msb0>().iter());
+
+    assert_eq!(
+        result,
+        Err(MethodError::CoverTextTooSmall {
+            remaining_data_size: 7 * 8 - 6
+        }
+        .into())
+    );
+    assert_eq!(
+        plain_result,
+        Err(MethodError::CoverTextTooSmall {
+            remaining_data_size: 2 + 7 * 8
+        }
+        .into())
+    );
+    Ok(())
+}
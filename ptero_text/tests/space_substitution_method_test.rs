@@ -0,0 +1,145 @@
+use std::error::Error;
+use std::path::PathBuf;
+
+use bitvec::prelude::*;
+use rstest::*;
+
+use ptero_common::config::ConfigurableBuilder;
+use ptero_common::method::SteganographyMethod;
+use ptero_text::extended_line_method::character_sets::{CharacterSetType, GetCharacterSet};
+use ptero_text::space_substitution_method::{ConcealError, SpaceSubstitutionMethod};
+
+use crate::test_resource::{Listener, ResourceLoader};
+
+#[fixture]
+pub fn cover_text_loader() -> ResourceLoader {
+    let dir_path = PathBuf::new().join("resources").join("cover_texts");
+
+    ResourceLoader::new(&dir_path)
+}
+
+struct FigureSpaces;
+
+impl GetCharacterSet for FigureSpaces {
+    fn get_set(&self) -> &[char] {
+        &['\u{2007}', '\u{2008}', '\u{00A0}']
+    }
+}
+
+fn with_regular_spaces(text: &str) -> String {
+    text.replace(CharacterSetType::NarrowSpace.get_set(), " ")
+}
+
+#[rstest]
+#[case::no_break_space(CharacterSetType::NoBreakSpace)]
+#[case::narrow_space(CharacterSetType::NarrowSpace)]
+fn reveals_payload_concealed_in_word_gaps(
+    cover_text_loader: ResourceLoader,
+    #[case] charset: CharacterSetType,
+) -> Result<(), Box<dyn Error>> {
+    let cover = cover_text_loader.load_resource(&PathBuf::from("long_text"));
+    let payload = b"Hi, panda!";
+    let mut method = SpaceSubstitutionMethod::builder()
+        .with_charset(charset)
+        .with_checksum(true)
+        .build()?;
+
+    let stego_text = method.try_conceal(&cover, &mut payload.view_bits::<Msb0>().iter())?;
+    let revealed: BitVec<Msb0, u8> = method.try_reveal(&stego_text)?;
+
+    assert_eq!(revealed.as_raw_slice(), payload);
+    assert_eq!(with_regular_spaces(&stego_text), cover);
+    Ok(())
+}
+
+#[rstest]
+fn reveals_payload_with_custom_charset() -> Result<(), Box<dyn Error>> {
+    let cover = "A little panda has fallen from a tree. The panda went rolling down the hill";
+    let mut method = SpaceSubstitutionMethod::builder()
+        .with_charset(FigureSpaces)
+        .with_framing(false)
+        .build()?;
+    let data = &[0b1110_0100u8, 0b0110_0000][..];
+    let data = &data.view_bits::<Msb0>()[..12];
+
+    let stego_text = method.try_conceal(cover, &mut data.iter())?;
+    let revealed: BitVec<Msb0, u8> = method.try_reveal(&stego_text)?;
+
+    assert!(stego_text
+        .starts_with("A\u{00A0}little\u{2008}panda\u{2007}has fallen\u{2007}from\u{2008}a"));
+    assert_eq!(&revealed[..data.len()], data);
+    Ok(())
+}
+
+#[rstest]
+fn leaves_line_breaks_and_space_runs_untouched() -> Result<(), Box<dyn Error>> {
+    let cover = "  Panda\tbamboo  forest\n\nhas fallen";
+    let mut method = SpaceSubstitutionMethod::builder()
+        .with_framing(false)
+        .build()?;
+
+    let stego_text = method.try_conceal(cover, &mut bits![1].iter())?;
+
+    assert_eq!(method.capacity(cover), 1);
+    assert_eq!(stego_text, "  Panda\tbamboo  forest\n\nhas\u{00A0}fallen");
+    Ok(())
+}
+
+#[rstest]
+fn replaces_characters_of_the_set_in_cover() -> Result<(), Box<dyn Error>> {
+    let cover = "A\u{00A0}little\u{00A0}panda";
+    let mut method = SpaceSubstitutionMethod::builder()
+        .with_framing(false)
+        .build()?;
+
+    let stego_text = method.try_conceal(cover, &mut bits![0].iter())?;
+
+    assert_eq!(stego_text, "A little panda");
+    Ok(())
+}
+
+#[rstest]
+fn conceals_more_in_longer_covers(cover_text_loader: ResourceLoader) -> Result<(), Box<dyn Error>> {
+    let long_text = cover_text_loader.load_resource(&PathBuf::from("long_text"));
+    let method = SpaceSubstitutionMethod::builder()
+        .with_charset(CharacterSetType::NarrowSpace)
+        .build()?;
+
+    let words = long_text.split_whitespace().count();
+
+    assert!(method.capacity(&long_text) > words);
+    assert_eq!(method.capacity("Panda"), 0);
+    Ok(())
+}
+
+#[rstest]
+fn reports_progress_through_the_notifier() -> Result<(), Box<dyn Error>> {
+    let listener = Listener::shared();
+    let mut method = SpaceSubstitutionMethod::builder()
+        .with_charset(CharacterSetType::NarrowSpace)
+        .with_framing(false)
+        .build()?;
+    method.subscribe(listener.clone());
+
+    method.try_conceal(
+        "A little panda has fallen from a tree",
+        &mut bits![1, 1, 0, 1, 0].iter(),
+    )?;
+
+    assert_eq!(listener.borrow().amount_written, 5);
+    assert!(listener.borrow().has_finished);
+    Ok(())
+}
+
+#[rstest]
+fn errors_when_cover_text_is_too_small() -> Result<(), Box<dyn Error>> {
+    let mut method = SpaceSubstitutionMethod::builder().build()?;
+
+    let result = method.try_conceal("A little panda", &mut b"!".view_bits::<Msb0>().iter());
+
+    assert!(matches!(
+        result,
+        Err(ConcealError::CoverTextTooSmall { .. })
+    ));
+    Ok(())
+}
@@ -0,0 +1,140 @@
+use std::error::Error;
+use std::path::PathBuf;
+
+use bitvec::prelude::*;
+use rstest::*;
+
+use ptero_common::config::ConfigurableBuilder;
+use ptero_common::method::SteganographyMethod;
+use ptero_text::synonym_method::word_list::{WordList, WordListError};
+use ptero_text::synonym_method::{ConcealError, SynonymMethod};
+
+use crate::test_resource::{Listener, ResourceLoader};
+
+const SHORT_STORY: &str = "It was a BIG day. We began the trip early and walked up the hill.\n\
+    Finally, we started to look around - the view was very beautiful!\n\
+    It was difficult, but the kids were happy and the photos are (really) important.";
+
+#[fixture]
+pub fn cover_text_loader() -> ResourceLoader {
+    let dir_path = PathBuf::new().join("resources").join("cover_texts");
+
+    ResourceLoader::new(&dir_path)
+}
+
+#[rstest]
+fn reveals_payload_concealed_in_short_story() -> Result<(), Box<dyn Error>> {
+    let mut method = SynonymMethod::builder().build()?;
+
+    let stego_text = method.try_conceal(SHORT_STORY, &mut b"Y".view_bits::<Msb0>().iter())?;
+    let revealed: BitVec<Msb0, u8> = method.try_reveal(&stego_text)?;
+
+    assert_eq!(revealed.as_raw_slice(), b"Y");
+    assert_ne!(stego_text, SHORT_STORY);
+    assert_eq!(stego_text.lines().count(), SHORT_STORY.lines().count());
+    Ok(())
+}
+
+#[rstest]
+fn keeps_capitalisation_punctuation_and_whitespace() -> Result<(), Box<dyn Error>> {
+    let word_list: WordList = "big, large\nhappy, glad".parse()?;
+    let mut method = SynonymMethod::builder()
+        .with_word_list(word_list)
+        .with_framing(false)
+        .build()?;
+
+    let stego_text = method.try_conceal(
+        "BIG  (Happy)\n\tbig, iPhone big...",
+        &mut bits![1, 1, 0, 1].iter(),
+    )?;
+
+    assert_eq!(stego_text, "LARGE  (Glad)\n\tbig, iPhone large...");
+    let revealed: BitVec<Msb0, u8> = method.try_reveal(&stego_text)?;
+    assert_eq!(revealed, bits![1, 1, 0, 1]);
+    Ok(())
+}
+
+#[rstest]
+fn uses_only_power_of_two_words_of_the_group() -> Result<(), Box<dyn Error>> {
+    let word_list: WordList = "fast, quick, rapid".parse()?;
+    let mut method = SynonymMethod::builder()
+        .with_word_list(word_list)
+        .with_framing(false)
+        .build()?;
+
+    assert_eq!(method.capacity("fast quick rapid"), 2);
+    let stego_text = method.try_conceal("fast quick rapid", &mut bits![1, 0].iter())?;
+
+    assert_eq!(stego_text, "quick fast rapid");
+    Ok(())
+}
+
+#[rstest]
+fn fills_whole_capacity_of_long_text(
+    cover_text_loader: ResourceLoader,
+) -> Result<(), Box<dyn Error>> {
+    let cover = cover_text_loader.load_resource(&PathBuf::from("long_text"));
+    let mut method = SynonymMethod::builder().with_framing(false).build()?;
+    let capacity = method.capacity(&cover);
+    let data: BitVec<Msb0, u8> = (0..capacity).map(|index| index % 3 == 0).collect();
+
+    let stego_text = method.try_conceal(&cover, &mut data.iter())?;
+    let revealed: BitVec<Msb0, u8> = method.try_reveal(&stego_text)?;
+
+    assert!(capacity > 0);
+    assert_eq!(revealed, data);
+    assert!(method
+        .try_conceal(&cover, &mut bitvec![Msb0, u8; 1; capacity + 1].iter())
+        .is_err());
+    Ok(())
+}
+
+#[rstest]
+fn fails_on_malformed_word_list() {
+    assert_eq!(
+        "big, large\nhuge, ,vast".parse::<WordList>(),
+        Err(WordListError::InvalidWord {
+            word: "".to_string(),
+            line: 2
+        })
+    );
+    assert_eq!(
+        "big, large\nvery big, huge".parse::<WordList>(),
+        Err(WordListError::InvalidWord {
+            word: "very big".to_string(),
+            line: 2
+        })
+    );
+    assert!(WordList::english()
+        .groups()
+        .iter()
+        .all(|group| group.len().is_power_of_two() && group.len() > 1));
+}
+
+#[rstest]
+fn reports_progress_through_the_notifier() -> Result<(), Box<dyn Error>> {
+    let listener = Listener::shared();
+    let mut method = SynonymMethod::builder().with_framing(false).build()?;
+    method.subscribe(listener.clone());
+
+    method.try_conceal("big and slow", &mut bits![1, 0, 1].iter())?;
+
+    assert_eq!(listener.borrow().amount_written, 3);
+    assert!(listener.borrow().has_finished);
+    Ok(())
+}
+
+#[rstest]
+fn errors_when_cover_text_is_too_small() -> Result<(), Box<dyn Error>> {
+    let mut method = SynonymMethod::builder().build()?;
+
+    let result = method.try_conceal("A big panda", &mut b"panda".view_bits::<Msb0>().iter());
+
+    assert_eq!(
+        result,
+        Err(ConcealError::CoverTextTooSmall {
+            remaining_data_size: 7 * 8 - 2
+        })
+    );
+    Ok(())
+}
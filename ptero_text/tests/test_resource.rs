@@ -1,7 +1,11 @@
+use std::cell::RefCell;
 use std::env;
 use std::fs::read_to_string;
 use std::path::{Path, PathBuf};
+use std::sync::Arc;
 
+use ptero_common::method::MethodProgressStatus;
+use ptero_common::observer::Observer;
 use ptero_text::line_separator::DEFAULT_LINE_SEPARATOR;
 
 pub struct ResourceLoader {
@@ -24,3 +28,27 @@ impl ResourceLoader {
         string.replace("\r\n", DEFAULT_LINE_SEPARATOR.into())
     }
 }
+
+/// Observer collecting the progress reported by the method.
+#[derive(Default)]
+pub struct Listener {
+    pub amount_written: u64,
+    pub has_finished: bool,
+}
+
+impl Listener {
+    /// Creates the listener ready to be subscribed to the method.
+    #[allow(clippy::arc_with_non_send_sync)]
+    pub fn shared() -> Arc<RefCell<Self>> {
+        Arc::new(RefCell::new(Listener::default()))
+    }
+}
+
+impl Observer<MethodProgressStatus> for Listener {
+    fn on_notify(&mut self, event: &MethodProgressStatus) {
+        match event {
+            MethodProgressStatus::DataWritten(amount) => self.amount_written += amount,
+            MethodProgressStatus::Finished => self.has_finished = true,
+        }
+    }
+}
@@ -1,4 +1,28 @@
 #[cfg(test)]
 mod extended_line_method_test;
 
+#[cfg(test)]
+mod homoglyph_method_test;
+
+#[cfg(test)]
+mod justification_method_test;
+
+#[cfg(test)]
+mod normalization_method_test;
+
+#[cfg(test)]
+mod space_substitution_method_test;
+
+#[cfg(test)]
+mod synonym_method_test;
+
+#[cfg(test)]
+mod typographic_variant_method_test;
+
+#[cfg(test)]
+mod variation_selector_method_test;
+
+#[cfg(test)]
+mod zero_width_method_test;
+
 mod test_resource;
\ No newline at end of file
@@ -0,0 +1,135 @@
+use std::error::Error;
+use std::path::PathBuf;
+
+use bitvec::prelude::*;
+use rstest::*;
+
+use ptero_common::config::ConfigurableBuilder;
+use ptero_common::method::SteganographyMethod;
+use ptero_text::typographic_variant_method::{ConcealError, TypographicVariantMethod};
+
+use crate::test_resource::{Listener, ResourceLoader};
+
+const SOCIAL_POST: &str =
+    "\"Don't tell me you're still asleep...\" - that's what I heard at 5 AM. \
+    We'd planned it for weeks: 'the panda's hill' - it's called like that because it's \
+    where the pandas can't resist rolling down... I didn't believe it, but they're \
+    rolling! \"Isn't it cute?\" she asked. Well, it's... it's the cutest thing I've seen.";
+
+#[fixture]
+pub fn cover_text_loader() -> ResourceLoader {
+    let dir_path = PathBuf::new().join("resources").join("cover_texts");
+
+    ResourceLoader::new(&dir_path)
+}
+
+fn canonical(text: &str) -> String {
+    text.replace(['‘', '’'], "'")
+        .replace(['“', '”'], "\"")
+        .replace(['–', '—'], "-")
+        .replace('…', "...")
+}
+
+#[rstest]
+fn reveals_payload_concealed_in_social_post() -> Result<(), Box<dyn Error>> {
+    let mut method = TypographicVariantMethod::builder().build()?;
+
+    let stego_text = method.try_conceal(SOCIAL_POST, &mut b"!".view_bits::<Msb0>().iter())?;
+    let revealed: BitVec<Msb0, u8> = method.try_reveal(&stego_text)?;
+
+    assert_eq!(revealed.as_raw_slice(), b"!");
+    assert_ne!(stego_text, SOCIAL_POST);
+    assert_eq!(canonical(&stego_text), SOCIAL_POST);
+    assert_eq!(method.capacity(&stego_text), method.capacity(SOCIAL_POST));
+    Ok(())
+}
+
+#[rstest]
+fn finds_sites_in_canonical_form(cover_text_loader: ResourceLoader) -> Result<(), Box<dyn Error>> {
+    // The text already uses the typographic apostrophes
+    let cover = cover_text_loader.load_resource(&PathBuf::from("long_text"));
+    let mut method = TypographicVariantMethod::builder()
+        .with_framing(false)
+        .build()?;
+    let capacity = method.capacity(&cover);
+    let data: BitVec<Msb0, u8> = (0..capacity).map(|index| index % 2 == 0).collect();
+
+    let stego_text = method.try_conceal(&cover, &mut data.iter())?;
+    let revealed: BitVec<Msb0, u8> = method.try_reveal(&stego_text)?;
+
+    assert!(capacity > 0);
+    assert_eq!(revealed, data);
+    assert_eq!(canonical(&stego_text), canonical(&cover));
+    Ok(())
+}
+
+#[rstest]
+#[case("\"panda\"", "“panda”")]
+#[case("(\"panda\")", "(“panda”)")]
+#[case("'panda' can't", "‘panda’ can’t")]
+#[case("panda - bamboo", "panda — bamboo")]
+#[case("panda – bamboo", "panda – bamboo")]
+#[case("Wait... what?", "Wait… what?")]
+fn uses_typographic_variants_for_ones(
+    #[case] cover: &str,
+    #[case] expected: &str,
+) -> Result<(), Box<dyn Error>> {
+    let mut method = TypographicVariantMethod::builder()
+        .with_framing(false)
+        .build()?;
+
+    let ones = bitvec![1; method.capacity(cover)];
+
+    let stego_text = method.try_conceal(cover, &mut ones.iter())?;
+
+    assert_eq!(stego_text, expected);
+    Ok(())
+}
+
+#[rstest]
+#[case("well-known panda")]
+#[case("- bullet point\n  - nested one")]
+#[case("panda -bamboo")]
+#[case("Zzz.... or zzz.…")]
+#[case("Panda.. bamboo")]
+fn skips_places_which_are_not_sites(#[case] cover: &str) -> Result<(), Box<dyn Error>> {
+    let method = TypographicVariantMethod::builder().build()?;
+
+    assert_eq!(method.capacity(cover), 0);
+    Ok(())
+}
+
+#[rstest]
+fn reports_progress_through_the_notifier() -> Result<(), Box<dyn Error>> {
+    let listener = Listener::shared();
+    let mut method = TypographicVariantMethod::builder()
+        .with_framing(false)
+        .build()?;
+    method.subscribe(listener.clone());
+
+    let stego_text = method.try_conceal(SOCIAL_POST, &mut bits![0, 1, 1, 0].iter())?;
+
+    // Sites following the data are left as they were
+    assert!(stego_text.starts_with("\"Don’t tell me you’re still asleep...\""));
+    assert_eq!(listener.borrow().amount_written, 4);
+    assert!(listener.borrow().has_finished);
+    Ok(())
+}
+
+#[rstest]
+fn errors_when_cover_text_is_too_small() -> Result<(), Box<dyn Error>> {
+    let mut method = TypographicVariantMethod::builder().build()?;
+
+    let result = method.try_conceal(
+        "\"It's a panda...\"",
+        &mut b"panda".view_bits::<Msb0>().iter(),
+    );
+
+    assert_eq!(
+        result,
+        Err(ConcealError::CoverTextTooSmall {
+            remaining_data_size: 7 * 8 - 4
+        })
+    );
+    Ok(())
+}
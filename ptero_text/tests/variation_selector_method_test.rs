@@ -0,0 +1,161 @@
+use std::error::Error;
+use std::path::PathBuf;
+
+use bitvec::prelude::*;
+use rstest::*;
+
+use ptero_common::config::ConfigurableBuilder;
+use ptero_common::method::SteganographyMethod;
+use ptero_text::variation_selector_method::{
+    BaseCharacters, ConcealError, VariationSelectorMethod,
+};
+
+use crate::test_resource::{Listener, ResourceLoader};
+
+const SINGLE_LINE_POST: &str =
+    "Finally made it to the top of the hill 🏔️ the view is worth every step! 👨‍👩‍👧 #hiking #weekend";
+
+#[fixture]
+pub fn cover_text_loader() -> ResourceLoader {
+    let dir_path = PathBuf::new().join("resources").join("cover_texts");
+
+    ResourceLoader::new(&dir_path)
+}
+
+fn without_selectors(text: &str) -> String {
+    text.chars()
+        .filter(|chr| {
+            !('\u{FE00}'..='\u{FE0F}').contains(chr) && !('\u{E0100}'..='\u{E01EF}').contains(chr)
+        })
+        .collect()
+}
+
+#[rstest]
+fn reveals_payload_concealed_in_single_line_post() -> Result<(), Box<dyn Error>> {
+    let payload = b"Meet me at the old bridge at 5";
+    let mut method = VariationSelectorMethod::builder()
+        .with_checksum(true)
+        .build()?;
+
+    let stego_text =
+        method.try_conceal(SINGLE_LINE_POST, &mut payload.view_bits::<Msb0>().iter())?;
+    let revealed: BitVec<Msb0, u8> = method.try_reveal(&stego_text)?;
+
+    assert_eq!(revealed.as_raw_slice(), payload);
+    assert_eq!(
+        without_selectors(&stego_text),
+        without_selectors(SINGLE_LINE_POST)
+    );
+    assert!(stego_text.contains("🏔️"));
+    assert!(stego_text.contains("👨‍👩‍👧"));
+    Ok(())
+}
+
+#[rstest]
+fn keeps_emoji_presentation_apart_from_the_data() -> Result<(), Box<dyn Error>> {
+    let data = [14u8, 15, 15, 14, 200, 0];
+    let mut method = VariationSelectorMethod::builder()
+        .with_base_characters(BaseCharacters::Emoji)
+        .with_selectors_per_base(2)
+        .with_framing(false)
+        .build()?;
+
+    let stego_text = method.try_conceal("🐼 🏔️ ❤", &mut data.view_bits::<Msb0>().iter())?;
+    let revealed: BitVec<Msb0, u8> = method.try_reveal(&stego_text)?;
+
+    assert_eq!(revealed.as_raw_slice(), &data);
+    assert_eq!(
+        stego_text,
+        "🐼\u{FE0F}\u{FE0E}\u{FE0F} 🏔\u{FE0F}\u{FE0F}\u{FE0E} ❤\u{E01B8}\u{FE00}"
+    );
+    Ok(())
+}
+
+#[rstest]
+fn replaces_selectors_already_present_in_the_cover() -> Result<(), Box<dyn Error>> {
+    let cover = "a\u{FE0E}b\u{E0100}\u{E0101} panda";
+    let mut method = VariationSelectorMethod::builder()
+        .with_framing(false)
+        .build()?;
+
+    let stego_text = method.try_conceal(cover, &mut [1u8, 2].view_bits::<Msb0>().iter())?;
+    let revealed: BitVec<Msb0, u8> = method.try_reveal(&stego_text)?;
+
+    assert_eq!(stego_text, "a\u{FE01}b\u{FE02} panda");
+    assert_eq!(revealed.as_raw_slice(), &[1, 2]);
+    Ok(())
+}
+
+#[rstest]
+fn uses_only_custom_base_characters(
+    cover_text_loader: ResourceLoader,
+) -> Result<(), Box<dyn Error>> {
+    let cover = cover_text_loader.load_resource(&PathBuf::from("long_text"));
+    let payload = b"Hi, panda!";
+    let mut method = VariationSelectorMethod::builder()
+        .with_base_characters(BaseCharacters::Custom(vec!['e', 'o']))
+        .with_selectors_per_base(3)
+        .build()?;
+
+    let base_count = cover
+        .chars()
+        .filter(|chr| *chr == 'e' || *chr == 'o')
+        .count();
+    assert_eq!(method.capacity(&cover), base_count * 3 * 8);
+    let stego_text = method.try_conceal(&cover, &mut payload.view_bits::<Msb0>().iter())?;
+
+    let is_selector = |chr: char| without_selectors(&chr.to_string()).is_empty();
+    let carriers = stego_text
+        .chars()
+        .zip(stego_text.chars().skip(1))
+        .filter(|(base, next)| !is_selector(*base) && is_selector(*next))
+        .map(|(base, _)| base)
+        .collect::<Vec<char>>();
+    assert!(carriers.iter().all(|base| *base == 'e' || *base == 'o'));
+    let revealed: BitVec<Msb0, u8> = method.try_reveal(&stego_text)?;
+    assert_eq!(revealed.as_raw_slice(), payload);
+    Ok(())
+}
+
+#[rstest]
+fn reports_progress_through_the_notifier() -> Result<(), Box<dyn Error>> {
+    let listener = Listener::shared();
+    let mut method = VariationSelectorMethod::builder().build()?;
+    method.subscribe(listener.clone());
+
+    method.try_conceal(SINGLE_LINE_POST, &mut b"panda".view_bits::<Msb0>().iter())?;
+
+    // Payload frame header is concealed as well
+    assert_eq!(listener.borrow().amount_written, 7 * 8);
+    assert!(listener.borrow().has_finished);
+    Ok(())
+}
+
+#[rstest]
+fn errors_when_there_are_not_enough_base_characters() -> Result<(), Box<dyn Error>> {
+    let mut method = VariationSelectorMethod::builder().build()?;
+    let mut emoji_method = VariationSelectorMethod::builder()
+        .with_base_characters(BaseCharacters::Emoji)
+        .with_selectors_per_base(2)
+        .build()?;
+
+    let result = method.try_conceal("A panda", &mut b"panda".view_bits::<Msb0>().iter());
+    let emoji_result =
+        emoji_method.try_conceal("A panda 🐼", &mut b"panda".view_bits::<Msb0>().iter());
+
+    assert_eq!(
+        result,
+        Err(ConcealError::NotEnoughBaseCharacters {
+            required: 7,
+            available: 6
+        })
+    );
+    assert_eq!(
+        emoji_result,
+        Err(ConcealError::NotEnoughBaseCharacters {
+            required: 4,
+            available: 1
+        })
+    );
+    Ok(())
+}
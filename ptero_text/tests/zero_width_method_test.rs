@@ -0,0 +1,154 @@
+use std::error::Error;
+use std::path::PathBuf;
+
+use bitvec::prelude::*;
+use rstest::*;
+
+use ptero_common::config::ConfigurableBuilder;
+use ptero_common::method::SteganographyMethod;
+use ptero_text::extended_line_method::character_sets::{CharacterSetType, GetCharacterSet};
+use ptero_text::zero_width_method::{ConcealError, ZeroWidthMethod};
+
+use crate::test_resource::{Listener, ResourceLoader};
+
+const SINGLE_LINE_POST: &str =
+    "Finally made it to the top of the hill 🏔️ the view is worth every step! 👨‍👩‍👧 #hiking #weekend";
+
+#[fixture]
+pub fn cover_text_loader() -> ResourceLoader {
+    let dir_path = PathBuf::new().join("resources").join("cover_texts");
+
+    ResourceLoader::new(&dir_path)
+}
+
+struct ZeroWidthSpaceOnly;
+
+impl GetCharacterSet for ZeroWidthSpaceOnly {
+    fn get_set(&self) -> &[char] {
+        &['\u{200B}']
+    }
+}
+
+fn without_inserted(text: &str) -> String {
+    let mut previous = ' ';
+    text.chars()
+        .filter(|chr| {
+            let is_inserted = previous.is_alphanumeric()
+                && CharacterSetType::ZeroWidth.character_to_bits(chr) != 0;
+            previous = *chr;
+            !is_inserted
+        })
+        .collect()
+}
+
+#[rstest]
+fn reveals_payload_concealed_in_single_line_post() -> Result<(), Box<dyn Error>> {
+    let payload = b"Meet at 5";
+    let mut method = ZeroWidthMethod::builder().with_checksum(true).build()?;
+
+    let stego_text =
+        method.try_conceal(SINGLE_LINE_POST, &mut payload.view_bits::<Msb0>().iter())?;
+    let revealed: BitVec<Msb0, u8> = method.try_reveal(&stego_text)?;
+
+    assert_eq!(revealed.as_raw_slice(), payload);
+    assert_eq!(without_inserted(&stego_text), SINGLE_LINE_POST);
+    Ok(())
+}
+
+#[rstest]
+fn leaves_emoji_sequences_and_combining_marks_intact() -> Result<(), Box<dyn Error>> {
+    let cover = "Cafe\u{301} 👨‍👩‍👧 naïve 🏳️‍🌈 panda and bamboo, then more pandas and bamboo";
+    let mut method = ZeroWidthMethod::builder().with_framing(false).build()?;
+
+    let stego_text = method.try_conceal(cover, &mut [255u8; 8].view_bits::<Msb0>().iter())?;
+
+    assert!(stego_text.contains("e\u{301}"));
+    assert!(stego_text.contains("👨‍👩‍👧"));
+    assert!(stego_text.contains("🏳️‍🌈"));
+    let revealed: BitVec<Msb0, u8> = method.try_reveal(&stego_text)?;
+    assert_eq!(&revealed.as_raw_slice()[..8], &[255u8; 8]);
+    Ok(())
+}
+
+#[rstest]
+fn inserts_less_characters_with_lower_density(
+    cover_text_loader: ResourceLoader,
+) -> Result<(), Box<dyn Error>> {
+    let cover = cover_text_loader.load_resource(&PathBuf::from("long_text"));
+    let payload = b"Hi, panda!";
+    let mut dense = ZeroWidthMethod::builder().build()?;
+    let mut sparse = ZeroWidthMethod::builder()
+        .with_charset(ZeroWidthSpaceOnly)
+        .with_density(0.25)
+        .build()?;
+
+    // A quarter of the slots with a third of the bitrate
+    assert_eq!(sparse.capacity(&cover), dense.capacity(&cover) / 3 / 4);
+    let dense_text = dense.try_conceal(&cover, &mut payload.view_bits::<Msb0>().iter())?;
+    let sparse_text = sparse.try_conceal(&cover, &mut payload.view_bits::<Msb0>().iter())?;
+
+    let last_insertion = |text: &str| {
+        text.char_indices()
+            .filter(|(_, chr)| CharacterSetType::ZeroWidth.character_to_bits(chr) != 0)
+            .map(|(index, _)| index)
+            .next_back()
+            .unwrap()
+    };
+    assert!(last_insertion(&sparse_text) > last_insertion(&dense_text));
+    let expected: BitVec<Msb0, u8> = BitVec::from_slice(payload)?;
+    assert_eq!(
+        sparse.try_reveal::<Msb0, u8>(&sparse_text),
+        Ok(expected.clone())
+    );
+    assert_ne!(dense.try_reveal::<Msb0, u8>(&sparse_text), Ok(expected));
+    Ok(())
+}
+
+#[rstest]
+fn reports_progress_through_the_notifier() -> Result<(), Box<dyn Error>> {
+    let listener = Listener::shared();
+    let mut method = ZeroWidthMethod::builder().build()?;
+    method.subscribe(listener.clone());
+
+    method.try_conceal(SINGLE_LINE_POST, &mut b"panda".view_bits::<Msb0>().iter())?;
+
+    // Payload frame header is concealed as well
+    assert_eq!(listener.borrow().amount_written, 7 * 8);
+    assert!(listener.borrow().has_finished);
+    Ok(())
+}
+
+#[rstest]
+fn errors_when_cover_text_is_too_small() -> Result<(), Box<dyn Error>> {
+    let mut method = ZeroWidthMethod::builder().build()?;
+
+    let result = method.try_conceal("A panda", &mut b"panda".view_bits::<Msb0>().iter());
+
+    assert_eq!(
+        result,
+        Err(ConcealError::CoverTextTooSmall {
+            remaining_data_size: 7 * 8 - 5 * 3
+        })
+    );
+    Ok(())
+}
+
+#[rstest]
+fn keeps_zero_width_characters_already_in_cover_text() -> Result<(), Box<dyn Error>> {
+    let cover =
+        "من می\u{200C}خواهم کتاب\u{200C}های تازه\u{200C}ای را بخوانم و بعد به خانه\u{200C}ام بروم";
+    let payload = b"Hi";
+    let mut method = ZeroWidthMethod::builder().build()?;
+
+    let stego_text = method.try_conceal(cover, &mut payload.view_bits::<Msb0>().iter())?;
+    let revealed: BitVec<Msb0, u8> = method.try_reveal(&stego_text)?;
+
+    assert_eq!(revealed.as_raw_slice(), payload);
+    let mut method = ZeroWidthMethod::builder().with_framing(false).build()?;
+    let untouched_text = method.try_conceal(cover, &mut BitVec::<Msb0, u8>::new().iter())?;
+    assert_eq!(
+        untouched_text.replace("\u{200C}\u{200C}", "\u{200C}"),
+        cover
+    );
+    Ok(())
+}
@@ -1,4 +1,4 @@
-use std::{error::Error, fs::File, io::Write, process};
+use std::{error::Error, fs::{File, OpenOptions}, io::Write, process};
 
 use clap::{ArgGroup, Clap, crate_version};
 use colored::Colorize;
@@ -6,7 +6,7 @@ use colored::Colorize;
 use ptero::{
     cli::{
         capacity::GetCapacityCommand, decoder::DecodeSubCommand,
-        encoder::EncodeSubCommand, writer::Writer,
+        encoder::EncodeSubCommand, keygen::KeygenSubCommand, writer::Writer,
     },
     log::{get_file_logger, get_stdout_logger, verbosity_to_level_filter},
 };
@@ -68,10 +68,12 @@ struct Opts {
 enum SubCommand {
     #[clap(name = "encode", group = ArgGroup::new("method_args").required(true))]
     Encode(EncodeSubCommand),
-    #[clap(name = "decode", group = ArgGroup::new("method_args").required(true))]
+    #[clap(name = "decode", group = ArgGroup::new("method_args"))]
     Decode(DecodeSubCommand),
     #[clap(name = "capacity", group = ArgGroup::new("method_args").required(true))]
     GetCapacity(GetCapacityCommand),
+    #[clap(name = "keygen")]
+    Keygen(KeygenSubCommand),
 }
 
 #[cfg(not(tarpaulin_include))]
@@ -105,9 +107,34 @@ fn run_subcommand(subcommand: SubCommand) -> Result<Vec<u8>, Box<dyn Error>> {
             let output_str = format!("{} b", capacity);
             output_str.as_bytes().into()
         }
+        SubCommand::Keygen(command) => command.run()?,
     };
     Ok(result)
 }
+/// Creates the output file, readable only by the owner when it holds a secret, e.g. the key file.
+#[cfg(not(tarpaulin_include))]
+fn create_output_file(path: &str, is_secret: bool) -> Result<File, Box<dyn Error>> {
+    let mut options = OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        if is_secret {
+            options.mode(0o600);
+        }
+    }
+    let output_file = options.open(path)?;
+    #[cfg(unix)]
+    {
+        use std::{fs::Permissions, os::unix::fs::PermissionsExt};
+        // The mode is applied only to a new file, the existing one keeps its permissions
+        if is_secret {
+            output_file.set_permissions(Permissions::from_mode(0o600))?;
+        }
+    }
+    Ok(output_file)
+}
+
 #[cfg(not(tarpaulin_include))]
 fn main() -> Result<(), Box<dyn Error>> {
     let opts: Opts = Opts::parse();
@@ -115,6 +142,7 @@ fn main() -> Result<(), Box<dyn Error>> {
     enable_logging(opts.verbose, opts.log_file)?;
     Writer::print(&BANNER.purple().bold().to_string());
 
+    let is_secret_output = matches!(opts.subcommand, SubCommand::Keygen(_));
     let result = run_subcommand(opts.subcommand);
 
     if let Err(error) = &result {
@@ -124,7 +152,7 @@ fn main() -> Result<(), Box<dyn Error>> {
     } else {
         let cli_output = &result?;
         if let Some(path) = &opts.output {
-            let mut output_file = File::create(path)?;
+            let mut output_file = create_output_file(path, is_secret_output)?;
             output_file.write_all(&cli_output)?;
             Writer::info(&format!("Saved to '{}'", &path));
         } else {
@@ -1,6 +1,7 @@
 use clap::Clap;
 use context::ContextErrorKind;
 use log::debug;
+use ptero_common::frame::{frame_payload, FrameOptions};
 use std::{error::Error, fs::{File}, io::Read, sync::mpsc::channel};
 
 use crate::{
@@ -9,7 +10,7 @@ use crate::{
     method::complex::{eluv::ELUVMethod, extended_line::ExtendedLineMethod},
 };
 
-use super::{encoder::{determine_pivot_size, validate_pivot_smaller_than_text}, progress::{new_progress_bar, spawn_progress_thread, ProgressStatus}, writer::Writer};
+use super::{encoder::{determine_pivot_size, parse_ecc_level, validate_pivot_smaller_than_text}, progress::{new_progress_bar, spawn_progress_thread, ProgressStatus}, writer::Writer};
 
 /// Calculate the minimal capacity for the cover text and given pivot
 #[derive(Clap)]
@@ -34,13 +35,56 @@ pub struct GetCapacityCommand {
     #[clap(long = "eline", group = "method_args")]
     #[allow(dead_code)]
     extended_line: bool,
+
+    /// Path to secret data file.
+    ///
+    /// If present, the capacity required by the data is reported as well.
+    #[clap(short, long)]
+    data: Option<String>,
+
+    /// Take compression of the secret data into account - see `encode --compress`.
+    #[clap(long, requires = "data")]
+    compress: bool,
+
+    /// Take error correcting code of given level into account - see `encode --ecc`.
+    #[clap(long, requires = "data")]
+    ecc: Option<u8>,
 }
 
 impl GetCapacityCommand {
     pub fn run(&self) -> Result<u32, Box<dyn Error>> {
         let cover_file_input = File::open(&self.cover)?;
+        let capacity = self.get_cover_text_capacity(cover_file_input)?;
+
+        if let Some(data_path) = &self.data {
+            let required_capacity = self.get_required_capacity(File::open(data_path)?)?;
+            Writer::info(&format!(
+                "Secret data requires capacity of {} b",
+                required_capacity
+            ));
+            if required_capacity > capacity {
+                Writer::warn("The cover text is too small for the secret data!");
+            }
+        }
+        Ok(capacity)
+    }
+
+    /// Calculates the capacity needed to encode the secret data, including the frame header
+    /// and the compression or error correction if requested.
+    pub(crate) fn get_required_capacity(
+        &self,
+        mut data_input: impl Read,
+    ) -> Result<u32, Box<dyn Error>> {
+        let mut data = vec![];
+        data_input.read_to_end(&mut data)?;
 
-        self.get_cover_text_capacity(cover_file_input)
+        let frame_options = FrameOptions {
+            compression: self.compress,
+            error_correction: parse_ecc_level(self.ecc)?,
+            ..FrameOptions::default()
+        };
+        let payload = frame_payload(&data, &frame_options);
+        Ok(payload.len() as u32 * 8)
     }
 
     pub(crate) fn get_cover_text_capacity(
@@ -122,6 +166,9 @@ mod test {
             pivot: 3,
             eluv: false,
             extended_line: true,
+            data: None,
+            compress: false,
+            ecc: None,
         };
 
         let result = command.get_cover_text_capacity(cover_input.as_bytes());
@@ -130,6 +177,29 @@ mod test {
     }
 
 
+    #[test]
+    fn returns_required_capacity_of_compressed_data() -> Result<(), Box<dyn Error>> {
+        let data_input = "Meet me at the old bridge at noon";
+
+        let mut command = GetCapacityCommand {
+            cover: "stub".into(),
+            pivot: 3,
+            eluv: false,
+            extended_line: true,
+            data: Some("stub".into()),
+            compress: false,
+            ecc: None,
+        };
+
+        let required_capacity = command.get_required_capacity(data_input.as_bytes())?;
+        assert_eq!(required_capacity, (2 + data_input.len() as u32) * 8);
+
+        command.compress = true;
+        let compressed_capacity = command.get_required_capacity(data_input.as_bytes())?;
+        assert!(compressed_capacity < required_capacity);
+        Ok(())
+    }
+
     #[test]
     fn fails_when_pivot_is_too_large() -> Result<(), Box<dyn Error>> {
         let stego_input = "aaaaa";
@@ -139,6 +209,9 @@ mod test {
             pivot: 6,
             eluv: false,
             extended_line: true,
+            data: None,
+            compress: false,
+            ecc: None,
         };
 
         let result = command.get_cover_text_capacity(stego_input.as_bytes());
@@ -1,26 +1,66 @@
-use std::{convert::TryInto, error::Error, fs::File, io::Read, sync::mpsc::channel};
+use std::{
+    convert::TryInto,
+    error::Error,
+    fs::{self, File},
+    io::Read,
+    sync::mpsc::{channel, Sender},
+};
 
 use clap::Clap;
-use log::info;
+use log::{info, warn};
+use ptero_common::{
+    frame::FrameOptions,
+    multipart::{reassemble, Part},
+    public_key::Identity,
+    rotation::VariantRotation,
+    selection::{LineSelector, SPREAD_DESCRIPTOR_LENGTH},
+};
 
 use crate::{
     context::PivotByRawLineContext,
     decoder::Decoder,
-    method::complex::{eluv::ELUVMethodBuilder, extended_line::ExtendedLineMethodBuilder},
+    deniable::decode_deniable,
+    header::{MethodId, StegoHeader, HEADER_LENGTH},
+    method::{
+        complex::{eluv::ELUVMethodBuilder, extended_line::ExtendedLineMethodBuilder},
+        trailing_unicode::character_sets::CharacterSetType,
+    },
+    spread::read_spread,
+};
+
+use super::{
+    discovery::{candidate_parameters, discover},
+    encoder::{
+        get_character_set_type, parse_ecc_level, read_passphrase, validate_pivot_smaller_than_text,
+        ELUVCharacterSet,
+    },
+    progress::{new_progress_bar, spawn_progress_thread, ProgressStatus},
 };
 
-use super::{encoder::{ELUVCharacterSet, get_character_set_type, validate_pivot_smaller_than_text}, progress::{new_progress_bar, spawn_progress_thread, ProgressStatus}};
+/// Amount of the best matches listed by `--auto`.
+const AUTO_MATCH_COUNT: usize = 5;
+
+/// State derived from the secrets passed to the command.
+/// Derived once, as the key derivation is slow on purpose.
+struct DerivedSecrets {
+    line_selector: Option<LineSelector>,
+    variant_rotation: Option<VariantRotation>,
+}
 
 /// Decode secret from the stegotext
 #[derive(Clap)]
 pub struct DecodeSubCommand {
     /// Path to stegotext from which data will be decoded
-    #[clap(short, long)]
-    text: String,
+    ///
+    /// Can be repeated to decode the secret data split into parts, in any order.
+    #[clap(short, long, required = true)]
+    text: Vec<String>,
 
-    /// Pivot i.e. line length used to encode with extended line algorithm
+    /// Pivot i.e. line length used to encode with extended line algorithm.
+    ///
+    /// Only needed when the stego text has no header.
     #[clap(short, long)]
-    pivot: usize,
+    pivot: Option<usize>,
 
     /// Use ELUV method for encoding.
     ///
@@ -38,66 +78,357 @@ pub struct DecodeSubCommand {
     /// Variant of the method. See concrete method for possible values.
     ///
     /// Variant is a permutation of methods that can be used during decoding.
-    #[clap(long, default_value = "1")]
-    variant: u8,
+    /// Defaults to 1 when the stego text has no header.
+    #[clap(long)]
+    variant: Option<u8>,
 
     /// Use Extended Line method for encoding.
-    /// 
+    ///
     /// This method has 3 variants.
     #[clap(long = "eline", group = "method_args")]
-    #[allow(dead_code)]
     extended_line: bool,
+
+    /// Passphrase used to encrypt the secret data.
+    ///
+    /// Decoding fails if the passphrase is wrong or the stego text has been tampered with.
+    #[clap(long, conflicts_with = "passphrase-file")]
+    passphrase: Option<String>,
+
+    /// Path to the file with the passphrase used to encrypt the secret data.
+    ///
+    /// Single trailing newline is ignored.
+    #[clap(long)]
+    passphrase_file: Option<String>,
+
+    /// Path to the key file generated by `keygen`, for the secret data encrypted with `encode --recipient`.
+    #[clap(long)]
+    identity: Option<String>,
+
+    /// Level of error correcting code used to encode the secret data (1-8).
+    #[clap(long)]
+    ecc: Option<u8>,
+
+    /// Ignore the header at the beginning of the stego text and use only the passed parameters.
+    ///
+    /// Needed for stego texts encoded with `--no-header`, if the header is not found
+    /// the passed parameters are used anyway.
+    #[clap(long)]
+    no_header: bool,
+
+    /// Decode the raw bits, for stego texts encoded before the data was framed with its length.
+    ///
+    /// The decoded data is followed by whatever the rest of the stego text decodes to, usually zeroes.
+    #[clap(long, conflicts_with_all = &["passphrase", "passphrase-file", "identity", "ecc", "parts", "key"])]
+    no_framing: bool,
+
+    /// Find the method, variant, set and pivot by trying all of them.
+    ///
+    /// Meant for stego texts without the header. Instead of the secret data, the best matches are printed
+    /// together with their parameters, ranked by the valid framing, checksum, ratio of printable characters
+    /// and entropy of the decoded data.
+    #[clap(long, conflicts_with_all = &["eluv", "extended-line", "pivot", "variant", "no-header", "parts", "line-key", "variant-key", "spread", "key", "no-framing"])]
+    auto: bool,
+
+    /// Treat the stego texts as parts of the secret data encoded with `--parts-dir` and reassemble them.
+    ///
+    /// Implied when more than one stego text is given. Decoding fails listing the missing parts
+    /// if some of them are not given.
+    #[clap(long)]
+    parts: bool,
+
+    /// Secret used to choose the lines carrying the data when encoding.
+    #[clap(long)]
+    line_key: Option<String>,
+
+    /// Secret used to rotate the order of the method's submethods when encoding.
+    #[clap(long, conflicts_with = "variant")]
+    variant_key: Option<String>,
+
+    /// Read the spacing of the lines carrying the data, for stego texts encoded with `--spread`.
+    #[clap(long, conflicts_with = "line-key")]
+    spread: bool,
+
+    /// Secret revealing the data encoded deniably, either `--deniable-key` or `--decoy-key` used when encoding.
+    ///
+    /// Only the data encrypted with this secret is decoded, the stego text doesn't tell whether there's any other.
+    /// The `--key` of `encode` only decides its random choices and isn't needed here.
+    #[clap(long, conflicts_with_all = &["passphrase", "passphrase-file", "parts", "line-key", "spread"])]
+    key: Option<String>,
 }
 
 impl DecodeSubCommand {
     pub fn run(&self) -> Result<Vec<u8>, Box<dyn Error>> {
-        let stego_text_file_input = File::open(&self.text)?;
+        if self.parts || self.text.len() > 1 {
+            let stego_inputs = self
+                .text
+                .iter()
+                .map(File::open)
+                .collect::<Result<Vec<File>, _>>()?;
+            return self.do_decode_parts(stego_inputs);
+        }
+        let stego_text_file_input = File::open(&self.text[0])?;
 
         self.do_decode(stego_text_file_input)
     }
 
-    pub(crate) fn get_method(
+    /// Determines the method parameters - from the header of the stego text if present,
+    /// otherwise from the passed arguments.
+    ///
+    /// # Returns
+    /// The parameters and the stego text following the header.
+    pub(crate) fn resolve_header(
         &self,
-    ) -> Result<Box<dyn Decoder<PivotByRawLineContext>>, Box<dyn Error>> {
+        stego_text: &str,
+    ) -> Result<(StegoHeader, String), Box<dyn Error>> {
+        if !self.no_header {
+            let mut context = PivotByRawLineContext::new(stego_text, 0);
+            match StegoHeader::read(&mut context) {
+                Ok(header) => {
+                    self.validate_against_header(&header)?;
+                    return Ok((header, skip_lines(stego_text, HEADER_LENGTH)));
+                }
+                Err(error) => {
+                    if !self.has_method_args() {
+                        return Err(format!(
+                            "{} The method and the pivot have to be provided.",
+                            error
+                        )
+                        .into());
+                    }
+                    warn!("{} Using the provided parameters.", error);
+                }
+            }
+        }
+        Ok((self.header_from_args()?, stego_text.to_string()))
+    }
+
+    fn has_method_args(&self) -> bool {
+        (self.eluv || self.extended_line) && self.pivot.is_some()
+    }
+
+    fn header_from_args(&self) -> Result<StegoHeader, Box<dyn Error>> {
+        let pivot = self
+            .pivot
+            .ok_or("Pivot has to be provided when the header is not used.")?;
+        let variant = self.variant.unwrap_or(1);
         Ok(if self.eluv {
-            Box::new(
-                ELUVMethodBuilder::new()
-                    .character_set(get_character_set_type(&self.set))
-                    .variant(self.variant.try_into()?)
-                    .build(),
+            StegoHeader::new(
+                MethodId::ELUV,
+                variant,
+                Some(get_character_set_type(&self.set)),
+                pivot,
             )
+        } else if self.extended_line {
+            StegoHeader::new(MethodId::ExtendedLine, variant, None, pivot)
         } else {
-            Box::new(
-                ExtendedLineMethodBuilder::new()
-                    .variant(self.variant.try_into()?)
-                    .build(),
-            )
+            return Err("Method has to be provided when the header is not used.".into());
         })
     }
 
+    fn validate_against_header(&self, header: &StegoHeader) -> Result<(), Box<dyn Error>> {
+        let method_conflicts = (self.eluv && header.method != MethodId::ELUV)
+            || (self.extended_line && header.method != MethodId::ExtendedLine);
+        let set_conflicts =
+            self.set.is_some() && Some(get_character_set_type(&self.set)) != header.character_set;
+        if method_conflicts
+            || set_conflicts
+            || matches!(self.pivot, Some(pivot) if pivot != header.pivot)
+            || matches!(self.variant, Some(variant) if variant != header.variant)
+        {
+            return Err(format!(
+                "Provided parameters conflict with the stego text header {:?}. Use `--no-header` to ignore it.",
+                header
+            )
+            .into());
+        }
+        Ok(())
+    }
+
     pub fn do_decode(&self, mut stego_input: impl Read) -> Result<Vec<u8>, Box<dyn Error>> {
         let mut stego_text = String::new();
 
         stego_input.read_to_string(&mut stego_text)?;
 
-        validate_pivot_smaller_than_text(self.pivot, &stego_text)?;
-
-        let decoder = self.get_method()?;
-        info!("Using method variant {}", self.variant);
-        let mut context = PivotByRawLineContext::new(stego_text.as_str(), self.pivot);
+        if self.auto {
+            return self.discover_parameters(&stego_text);
+        }
 
         let progress_bar = new_progress_bar(stego_text.len() as u64);
         let (tx, rx) = channel::<ProgressStatus>();
         progress_bar.set_message("Decoding cover text...");
         spawn_progress_thread(progress_bar.clone(), rx);
 
-        let result = decoder.decode(&mut context, Some(&tx));
+        let secrets = self.get_secrets();
+        let result = self.decode_text(&stego_text, &secrets, Some(&tx));
 
         tx.send(ProgressStatus::Finished).ok();
         progress_bar.finish_with_message("Finished decoding");
 
         result
     }
+
+    /// Decodes the parts from all the stego texts and joins them back into the secret data.
+    pub(crate) fn do_decode_parts(
+        &self,
+        stego_inputs: Vec<impl Read>,
+    ) -> Result<Vec<u8>, Box<dyn Error>> {
+        if self.auto {
+            return Err("Parameters can be discovered only for a single stego text.".into());
+        }
+
+        let progress_bar = new_progress_bar(stego_inputs.len() as u64);
+        let (tx, rx) = channel::<ProgressStatus>();
+        progress_bar.set_message("Decoding parts...");
+        spawn_progress_thread(progress_bar.clone(), rx);
+
+        let secrets = self.get_secrets();
+        let mut parts = Vec::with_capacity(stego_inputs.len());
+        for mut stego_input in stego_inputs {
+            let mut stego_text = String::new();
+            stego_input.read_to_string(&mut stego_text)?;
+            let data = self.decode_text(&stego_text, &secrets, None)?;
+            parts.push(Part::from_bytes(&data)?);
+            tx.send(ProgressStatus::Step(1)).ok();
+        }
+
+        tx.send(ProgressStatus::Finished).ok();
+        progress_bar.finish_with_message("Finished decoding");
+
+        info!("Reassembling {} parts", parts.len());
+        Ok(reassemble(parts)?)
+    }
+
+    fn decode_text(
+        &self,
+        stego_text: &str,
+        secrets: &DerivedSecrets,
+        progress_channel: Option<&Sender<ProgressStatus>>,
+    ) -> Result<Vec<u8>, Box<dyn Error>> {
+        let (header, stego_text) = self.resolve_header(stego_text)?;
+        let mut line_selector = secrets.line_selector.clone();
+        let stego_text = if self.spread {
+            let spread = read_spread(&mut PivotByRawLineContext::new(&stego_text, 0))?;
+            line_selector = Some(LineSelector::spread(spread));
+            skip_lines(&stego_text, SPREAD_DESCRIPTOR_LENGTH)
+        } else {
+            stego_text
+        };
+        validate_pivot_smaller_than_text(header.pivot, &stego_text)?;
+
+        let frame_options = self.get_frame_options()?;
+        let decoder = get_decoder(&header)?;
+        info!("Using method variant {}", header.variant);
+        let new_context = |line_selector: Option<LineSelector>| {
+            let mut context = PivotByRawLineContext::new(stego_text.as_str(), header.pivot);
+            if let Some(line_selector) = line_selector {
+                context.select_lines_with(line_selector);
+            }
+            if let Some(variant_rotation) = &secrets.variant_rotation {
+                context.rotate_variants_with(variant_rotation.clone());
+            }
+            context
+        };
+
+        if self.no_framing {
+            return decoder.decode_raw(&mut new_context(line_selector), progress_channel);
+        }
+        if let Some(key) = &self.key {
+            return decode_deniable(
+                decoder.as_ref(),
+                new_context,
+                key.as_bytes(),
+                &frame_options,
+            );
+        }
+        decoder.decode_with(
+            &mut new_context(line_selector),
+            &frame_options,
+            progress_channel,
+        )
+    }
+
+    fn get_frame_options(&self) -> Result<FrameOptions, Box<dyn Error>> {
+        Ok(FrameOptions {
+            passphrase: read_passphrase(&self.passphrase, &self.passphrase_file)?,
+            error_correction: parse_ecc_level(self.ecc)?,
+            identity: read_identity(&self.identity)?,
+            ..FrameOptions::default()
+        })
+    }
+
+    fn get_secrets(&self) -> DerivedSecrets {
+        DerivedSecrets {
+            line_selector: self.line_key.as_ref().map(LineSelector::new),
+            variant_rotation: self.variant_key.as_ref().map(VariantRotation::new),
+        }
+    }
+}
+
+/// Reads the identity from the key file generated by `keygen`.
+pub(crate) fn read_identity(path: &Option<String>) -> Result<Option<Identity>, Box<dyn Error>> {
+    match path {
+        Some(path) => Ok(Some(Identity::from_key_file(&fs::read_to_string(path)?)?)),
+        None => Ok(None),
+    }
+}
+
+fn skip_lines(text: &str, count: usize) -> String {
+    text.lines()
+        .skip(count)
+        .map(|line| format!("{}\n", line))
+        .collect()
+}
+
+impl DecodeSubCommand {
+    /// Decodes the stego text with every plausible set of parameters and lists the best matches.
+    pub(crate) fn discover_parameters(&self, stego_text: &str) -> Result<Vec<u8>, Box<dyn Error>> {
+        let frame_options = self.get_frame_options()?;
+
+        let progress_bar = new_progress_bar(candidate_parameters(stego_text).len() as u64);
+        let (tx, rx) = channel::<ProgressStatus>();
+        progress_bar.set_message("Trying the parameters...");
+        spawn_progress_thread(progress_bar.clone(), rx);
+
+        let candidates = discover(stego_text, &frame_options, Some(&tx));
+
+        tx.send(ProgressStatus::Finished).ok();
+        progress_bar.finish_with_message("Finished trying the parameters");
+
+        if candidates.is_empty() {
+            return Err("Couldn't decode the stego text with any of the parameters.".into());
+        }
+        let report = candidates
+            .iter()
+            .take(AUTO_MATCH_COUNT)
+            .enumerate()
+            .map(|(index, candidate)| format!("{}. {}", index + 1, candidate))
+            .collect::<Vec<String>>()
+            .join("\n");
+        Ok(report.into_bytes())
+    }
+}
+
+/// Creates the decoder of the method described by the header.
+pub(crate) fn get_decoder(
+    header: &StegoHeader,
+) -> Result<Box<dyn Decoder<PivotByRawLineContext>>, Box<dyn Error>> {
+    Ok(match header.method {
+        MethodId::ELUV => Box::new(
+            ELUVMethodBuilder::new()
+                .character_set(
+                    header
+                        .character_set
+                        .unwrap_or(CharacterSetType::FullUnicodeSet),
+                )
+                .variant(header.variant.try_into()?)
+                .build(),
+        ),
+        MethodId::ExtendedLine => Box::new(
+            ExtendedLineMethodBuilder::new()
+                .variant(header.variant.try_into()?)
+                .build(),
+        ),
+    })
 }
 
 #[allow(unused_imports)]
@@ -108,34 +439,88 @@ mod test {
     use super::DecodeSubCommand;
 
     #[test]
-    fn decodes_zeroes_if_not_data_encoded_extended_line() -> Result<(), Box<dyn Error>> {
-        let stego_input = "a b";
+    fn decodes_empty_payload_if_no_data_encoded_extended_line() -> Result<(), Box<dyn Error>> {
+        let stego_input = "a b\n".repeat(6);
 
         let command = DecodeSubCommand {
-            text: "stub".into(),
-            pivot: 3,
+            text: vec!["stub".into()],
+            pivot: Some(3),
             eluv: false,
             extended_line: true,
             set: None,
-            variant: 1,
+            variant: None,
+            passphrase: None,
+            passphrase_file: None,
+            identity: None,
+            ecc: None,
+            no_header: false,
+            no_framing: false,
+            auto: false,
+            parts: false,
+            line_key: None,
+            variant_key: None,
+            spread: false,
+            key: None,
         };
 
         let result = command.do_decode(stego_input.as_bytes());
-        assert_eq!(result.ok(), Some(vec![0]));
+        assert_eq!(result.ok(), Some(vec![]));
         Ok(())
     }
 
     #[test]
-    fn decodes_zeroes_if_not_data_encoded_eluv() -> Result<(), Box<dyn Error>> {
-        let stego_input = "a b";
+    fn decodes_empty_payload_if_no_data_encoded_eluv() -> Result<(), Box<dyn Error>> {
+        let stego_input = "a b\n".repeat(3);
 
         let command = DecodeSubCommand {
-            text: "stub".into(),
-            pivot: 3,
+            text: vec!["stub".into()],
+            pivot: Some(3),
             eluv: true,
             extended_line: false,
             set: None,
-            variant: 1,
+            variant: None,
+            passphrase: None,
+            passphrase_file: None,
+            identity: None,
+            ecc: None,
+            no_header: false,
+            no_framing: false,
+            auto: false,
+            parts: false,
+            line_key: None,
+            variant_key: None,
+            spread: false,
+            key: None,
+        };
+
+        let result = command.do_decode(stego_input.as_bytes());
+        assert_eq!(result.ok(), Some(vec![]));
+        Ok(())
+    }
+
+    #[test]
+    fn decodes_zeroes_if_not_data_encoded_without_framing() -> Result<(), Box<dyn Error>> {
+        let stego_input = "a b";
+
+        let command = DecodeSubCommand {
+            text: vec!["stub".into()],
+            pivot: Some(3),
+            eluv: false,
+            extended_line: true,
+            set: None,
+            variant: None,
+            passphrase: None,
+            passphrase_file: None,
+            identity: None,
+            ecc: None,
+            no_header: false,
+            no_framing: true,
+            auto: false,
+            parts: false,
+            line_key: None,
+            variant_key: None,
+            spread: false,
+            key: None,
         };
 
         let result = command.do_decode(stego_input.as_bytes());
@@ -148,12 +533,24 @@ mod test {
         let stego_input = "aaaaa";
 
         let command = DecodeSubCommand {
-            text: "stub".into(),
-            pivot: 6,
+            text: vec!["stub".into()],
+            pivot: Some(6),
             eluv: false,
             extended_line: true,
             set: None,
-            variant: 1,
+            variant: None,
+            passphrase: None,
+            passphrase_file: None,
+            identity: None,
+            ecc: None,
+            no_header: false,
+            no_framing: false,
+            auto: false,
+            parts: false,
+            line_key: None,
+            variant_key: None,
+            spread: false,
+            key: None,
         };
 
         let result = command.do_decode(stego_input.as_bytes());
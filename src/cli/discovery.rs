@@ -0,0 +1,440 @@
+//! # Description
+//!
+//! Brute force discovery of the decoding parameters for stego texts without a header.
+//! Every method variant, character set and pivot from the plausible range is tried
+//! and the results are ranked by how likely they are the concealed data.
+
+use std::{error::Error, fmt, sync::mpsc::Sender};
+
+use bitvec::prelude::*;
+use log::{debug, trace};
+use ptero_common::frame::{unframe_payload, FrameOptions, FrameReader, RevealError, CHECKSUM_FLAG};
+
+use crate::{
+    context::PivotByRawLineContext,
+    header::{MethodId, StegoHeader},
+    method::trailing_unicode::character_sets::CharacterSetType,
+};
+
+use super::{decoder::get_decoder, encoder::determine_pivot_size, progress::ProgressStatus};
+
+const VARIANTS: [u8; 3] = [1, 2, 3];
+const CHARACTER_SETS: [CharacterSetType; 5] = [
+    CharacterSetType::FullUnicodeSet,
+    CharacterSetType::FourBitUnicodeSet,
+    CharacterSetType::ThreeBitUnicodeSet,
+    CharacterSetType::TwoBitUnicodeSet,
+    CharacterSetType::TwitterUnicodeSet,
+];
+// Weights of the plausibility signals, they sum up to 1
+const FRAMING_WEIGHT: f64 = 0.35;
+const QUIET_TAIL_WEIGHT: f64 = 0.15;
+const CHECKSUM_WEIGHT: f64 = 0.25;
+const PRINTABLE_WEIGHT: f64 = 0.15;
+const ENTROPY_WEIGHT: f64 = 0.1;
+const PREVIEW_LENGTH: usize = 40;
+/// Amount of zero bytes in a row taken as the start of the untouched cover text.
+const QUIET_RUN_LENGTH: usize = 4;
+/// Chance of the random byte being printable, see [printable_ratio].
+const PRINTABLE_BY_CHANCE: f64 = 100.0 / 256.0;
+
+/// How well the decoded bits form the payload frame.
+///
+/// Short frames are easy to read from the noise by chance, so the frame counts only if its checksum
+/// matches or it spans all the decoded data up to the zeroes of the untouched cover text.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Framing {
+    /// Frame has been read, `checksum` tells if it carried a (matching) checksum.
+    Valid { checksum: bool },
+    /// Frame is valid but encrypted, the passphrase is needed to read the payload.
+    Encrypted { checksum: bool },
+    /// Decoded bits don't form a frame, they are the data itself - encoded before the framing.
+    Invalid,
+}
+
+/// Single set of tried parameters together with its plausibility.
+#[derive(Debug, Clone)]
+pub struct Candidate {
+    pub parameters: StegoHeader,
+    pub framing: Framing,
+    /// Payload if the frame is valid, otherwise the decoded bytes up to the untouched cover text.
+    pub data: Vec<u8>,
+    /// Plausibility from 0 to 1.
+    pub score: f64,
+}
+
+/// Lists all the parameter combinations worth trying for given stego text.
+/// Pivot is bounded below by the longest word, like in the encoder, and above by the longest line.
+pub fn candidate_parameters(stego_text: &str) -> Vec<StegoHeader> {
+    let min_pivot = determine_pivot_size(stego_text.split_whitespace()).max(1);
+    let max_pivot = stego_text
+        .lines()
+        .map(|line| line.trim_end().chars().count())
+        .max()
+        .unwrap_or(0)
+        .max(min_pivot);
+
+    let mut parameters = Vec::new();
+    for pivot in min_pivot..=max_pivot {
+        for variant in VARIANTS.iter() {
+            parameters.push(StegoHeader::new(
+                MethodId::ExtendedLine,
+                *variant,
+                None,
+                pivot,
+            ));
+            for character_set in CHARACTER_SETS.iter() {
+                parameters.push(StegoHeader::new(
+                    MethodId::ELUV,
+                    *variant,
+                    Some(*character_set),
+                    pivot,
+                ));
+            }
+        }
+    }
+    parameters
+}
+
+/// Decodes the stego text with every plausible set of parameters.
+///
+/// # Returns
+/// Candidates sorted from the most plausible one, candidates with the same method, variant, set
+/// and data as a better one are skipped.
+pub fn discover(
+    stego_text: &str,
+    frame_options: &FrameOptions,
+    progress_channel: Option<&Sender<ProgressStatus>>,
+) -> Vec<Candidate> {
+    let mut candidates: Vec<Candidate> = Vec::new();
+    for parameters in candidate_parameters(stego_text) {
+        if let Some(tx) = progress_channel {
+            tx.send(ProgressStatus::Step(1)).ok();
+        }
+        match try_parameters(stego_text, &parameters, frame_options) {
+            Ok(candidate) => candidates.push(candidate),
+            Err(error) => trace!("Skipping {:?}: {}", parameters, error),
+        }
+    }
+    debug!("Decoded {} candidates", candidates.len());
+
+    // Stable sort keeps the smaller pivot first among equally good candidates
+    candidates.sort_by(|left, right| right.score.partial_cmp(&left.score).unwrap());
+    let mut unique: Vec<Candidate> = Vec::new();
+    for candidate in candidates {
+        let is_duplicate = unique.iter().any(|other| {
+            other.parameters.method == candidate.parameters.method
+                && other.parameters.variant == candidate.parameters.variant
+                && other.parameters.character_set == candidate.parameters.character_set
+                && other.data == candidate.data
+        });
+        if !is_duplicate {
+            unique.push(candidate);
+        }
+    }
+    unique
+}
+
+fn try_parameters(
+    stego_text: &str,
+    parameters: &StegoHeader,
+    frame_options: &FrameOptions,
+) -> Result<Candidate, Box<dyn Error>> {
+    let decoder = get_decoder(parameters)?;
+    let mut context = PivotByRawLineContext::new(stego_text, parameters.pivot);
+    let raw = decoder.decode_raw(&mut context, None)?;
+    let quiet_tail = quiet_tail_ratio(&raw);
+    let has_checksum = matches!(raw.first(), Some(flags) if flags & CHECKSUM_FLAG != 0);
+    let data_length = significant_length(&raw);
+    // Frame read from the noise by chance rarely ends right where the untouched cover text starts
+    let spans_data = matches!(
+        read_frame_extent(&raw, frame_options),
+        Some((payload_length, frame_length)) if payload_length > 0
+            && frame_length >= data_length
+            && significant_length(&raw[..frame_length]) + QUIET_RUN_LENGTH > frame_length
+    );
+
+    let (framing, data) = match unframe_payload(&raw, frame_options) {
+        Ok(payload) if !payload.is_empty() && (has_checksum || spans_data) => (
+            Framing::Valid {
+                checksum: has_checksum,
+            },
+            payload,
+        ),
+        Err(RevealError::MissingPassphrase) if spans_data => (
+            Framing::Encrypted {
+                checksum: has_checksum,
+            },
+            vec![],
+        ),
+        _ => (Framing::Invalid, raw[..data_length].to_vec()),
+    };
+    let score = score(framing, &data, quiet_tail);
+    Ok(Candidate {
+        parameters: parameters.clone(),
+        framing,
+        data,
+        score,
+    })
+}
+
+/// Reads the frame header from the decoded data.
+///
+/// # Returns
+/// The declared payload length and the length of the whole frame in bytes,
+/// or `None` if the data doesn't start with a complete frame.
+fn read_frame_extent(raw: &[u8], frame_options: &FrameOptions) -> Option<(usize, usize)> {
+    let mut reader = FrameReader::with_options(frame_options.clone());
+    for (index, bit) in raw.view_bits::<Msb0>().iter().enumerate() {
+        if reader.is_complete() {
+            return Some((reader.payload_length()?, index / 8));
+        }
+        reader.push_bit(*bit).ok()?;
+    }
+    if reader.is_complete() {
+        return Some((reader.payload_length()?, raw.len()));
+    }
+    None
+}
+
+/// Rates the plausibility of the decoded data, the higher the better.
+///
+/// Besides the frame itself, the `quiet_tail` is taken into account - the cover text after the data
+/// is left untouched by the encoder, so the right parameters decode it to zeroes only.
+pub fn score(framing: Framing, data: &[u8], quiet_tail: f64) -> f64 {
+    let (framed, checksum) = match framing {
+        // Empty frame is too easy to get by chance
+        Framing::Valid { checksum } => (!data.is_empty(), checksum),
+        Framing::Encrypted { checksum } => (true, checksum),
+        Framing::Invalid => (false, false),
+    };
+    let mut score = 0.0;
+    if framed {
+        score += FRAMING_WEIGHT;
+    }
+    if framed || !data.is_empty() {
+        score += QUIET_TAIL_WEIGHT * quiet_tail;
+    }
+    if checksum {
+        score += CHECKSUM_WEIGHT;
+    }
+    if !data.is_empty() {
+        // Few bytes are printable by chance too
+        let confidence = 1.0 - PRINTABLE_BY_CHANCE.powi(data.len().min(64) as i32);
+        score += PRINTABLE_WEIGHT * printable_ratio(data) * confidence;
+        // Short data can't reach the entropy of 8 bits, it's compared to the highest possible one
+        let max_entropy = (data.len().min(256) as f64).log2();
+        if max_entropy > 0.0 {
+            score += ENTROPY_WEIGHT * (1.0 - entropy(data) / max_entropy);
+        }
+    }
+    score
+}
+
+/// Length of the decoded data up to the untouched cover text, which starts with
+/// [QUIET_RUN_LENGTH] zero bytes or the trailing zeroes.
+/// A few stray bits may follow, e.g. from the trailing whitespace of the last line of the cover.
+pub fn significant_length(raw: &[u8]) -> usize {
+    raw.windows(QUIET_RUN_LENGTH)
+        .position(|window| window.iter().all(|byte| *byte == 0))
+        .unwrap_or_else(|| raw.len() - raw.iter().rev().take_while(|byte| **byte == 0).count())
+}
+
+/// Ratio of the zero bytes following the decoded data, see [significant_length].
+pub fn quiet_tail_ratio(raw: &[u8]) -> f64 {
+    if raw.is_empty() {
+        return 0.0;
+    }
+    let zeroes = raw[significant_length(raw)..]
+        .iter()
+        .filter(|byte| **byte == 0)
+        .count();
+    zeroes as f64 / raw.len() as f64
+}
+
+/// Ratio of the bytes being printable ASCII or whitespace.
+pub fn printable_ratio(data: &[u8]) -> f64 {
+    if data.is_empty() {
+        return 0.0;
+    }
+    let printable = data
+        .iter()
+        .filter(|byte| byte.is_ascii_graphic() || byte.is_ascii_whitespace())
+        .count();
+    printable as f64 / data.len() as f64
+}
+
+/// Shannon entropy of the bytes in bits per byte.
+pub fn entropy(data: &[u8]) -> f64 {
+    if data.is_empty() {
+        return 0.0;
+    }
+    let mut counts = [0usize; 256];
+    for byte in data {
+        counts[*byte as usize] += 1;
+    }
+    counts
+        .iter()
+        .filter(|count| **count > 0)
+        .map(|count| {
+            let probability = *count as f64 / data.len() as f64;
+            -probability * probability.log2()
+        })
+        .sum()
+}
+
+impl fmt::Display for Candidate {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let method_args = match self.parameters.method {
+            MethodId::ExtendedLine => "--eline".to_string(),
+            MethodId::ELUV => format!(
+                "--eluv --set {}",
+                character_set_arg(
+                    self.parameters
+                        .character_set
+                        .unwrap_or(CharacterSetType::FullUnicodeSet)
+                )
+            ),
+        };
+        // Data encoded before the framing is revealed only without reading the frame
+        let framing_arg = match self.framing {
+            Framing::Invalid => " --no-framing",
+            _ => "",
+        };
+        let framing = match self.framing {
+            Framing::Valid { checksum: true } => "valid frame with checksum",
+            Framing::Valid { checksum: false } => "valid frame",
+            Framing::Encrypted { checksum: true } => "encrypted frame with checksum",
+            Framing::Encrypted { checksum: false } => "encrypted frame",
+            Framing::Invalid => "no frame",
+        };
+        let preview = String::from_utf8_lossy(&self.data)
+            .chars()
+            .take(PREVIEW_LENGTH)
+            .collect::<String>();
+        write!(
+            f,
+            "score {:.2} | {} --variant {} --pivot {}{} | {} | {:?}",
+            self.score,
+            method_args,
+            self.parameters.variant,
+            self.parameters.pivot,
+            framing_arg,
+            framing,
+            preview
+        )
+    }
+}
+
+fn character_set_arg(character_set: CharacterSetType) -> &'static str {
+    match character_set {
+        CharacterSetType::FullUnicodeSet => "full",
+        CharacterSetType::FourBitUnicodeSet => "four-bit",
+        CharacterSetType::ThreeBitUnicodeSet => "three-bit",
+        CharacterSetType::TwoBitUnicodeSet => "two-bit",
+        CharacterSetType::TwitterUnicodeSet => "twitter",
+    }
+}
+
+#[allow(unused_imports)]
+mod test {
+    use std::error::Error;
+
+    use crate::{header::MethodId, method::trailing_unicode::character_sets::CharacterSetType};
+
+    use ptero_common::frame::FrameOptions;
+
+    use crate::{
+        binary::BitIterator, context::PivotByLineContext, encoder::Encoder,
+        method::complex::extended_line::ExtendedLineMethod,
+    };
+
+    use super::{
+        candidate_parameters, entropy, printable_ratio, quiet_tail_ratio, score,
+        significant_length, try_parameters, Framing,
+    };
+
+    #[test]
+    fn bounds_pivot_by_longest_word_and_line() -> Result<(), Box<dyn Error>> {
+        let stego_text = "abc de\nfghi\n";
+
+        let parameters = candidate_parameters(stego_text);
+        let pivots = parameters
+            .iter()
+            .map(|parameters| parameters.pivot)
+            .collect::<Vec<usize>>();
+
+        assert_eq!(parameters.len(), 2 * 3 * 6);
+        assert_eq!(pivots.iter().min(), Some(&5));
+        assert_eq!(pivots.iter().max(), Some(&6));
+        assert_eq!(
+            parameters
+                .iter()
+                .filter(|parameters| parameters.method == MethodId::ELUV
+                    && parameters.character_set == Some(CharacterSetType::TwitterUnicodeSet))
+                .count(),
+            2 * 3
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn prefers_framed_printable_data() -> Result<(), Box<dyn Error>> {
+        let text = b"Meet me at noon.";
+        let noise = [0xff, 0x13, 0x00, 0x9a, 0x42, 0x07, 0xe1, 0x88];
+
+        let with_checksum = score(Framing::Valid { checksum: true }, text, 0.5);
+        let without_checksum = score(Framing::Valid { checksum: false }, text, 0.5);
+        let noisy_tail = score(Framing::Valid { checksum: false }, text, 0.1);
+        let framed_noise = score(Framing::Valid { checksum: false }, &noise, 0.5);
+        let unframed_text = score(Framing::Invalid, text, 0.5);
+
+        assert!(with_checksum > without_checksum);
+        assert!(without_checksum > noisy_tail);
+        assert!(without_checksum > framed_noise);
+        assert!(framed_noise > unframed_text);
+        assert_eq!(score(Framing::Valid { checksum: false }, &[], 0.5), 0.0);
+        Ok(())
+    }
+
+    #[test]
+    fn calculates_printable_ratio_and_entropy() -> Result<(), Box<dyn Error>> {
+        assert_eq!(printable_ratio(b"ab\n\x00"), 0.75);
+        assert_eq!(entropy(b"aaaa"), 0.0);
+        assert_eq!(entropy(b"abcd"), 2.0);
+        assert_eq!(entropy(&[]), 0.0);
+        assert_eq!(quiet_tail_ratio(&[1, 0, 2, 0, 0]), 0.4);
+        Ok(())
+    }
+
+    #[test]
+    fn ends_the_data_where_the_untouched_cover_text_starts() -> Result<(), Box<dyn Error>> {
+        assert_eq!(significant_length(&[7, 0, 0, 0, 0, 0, 1]), 1);
+        assert_eq!(significant_length(&[7, 0, 8, 0, 0]), 3);
+        assert_eq!(quiet_tail_ratio(&[7, 0, 0, 0, 0, 0, 1]), 5.0 / 7.0);
+        Ok(())
+    }
+
+    #[test]
+    fn ranks_data_encoded_without_frame_by_its_content() -> Result<(), Box<dyn Error>> {
+        let cover_input = "a b c ".repeat(200);
+        let method = ExtendedLineMethod::default();
+        let mut context = PivotByLineContext::new(&cover_input, 3);
+        let stego_text = method.encode(&mut context, &mut BitIterator::new(b"Some."), None)?;
+        let parameters = candidate_parameters(&stego_text)
+            .into_iter()
+            .find(|parameters| {
+                parameters.method == MethodId::ExtendedLine
+                    && parameters.variant == 1
+                    && parameters.pivot == 3
+            })
+            .unwrap();
+
+        let candidate = try_parameters(&stego_text, &parameters, &FrameOptions::default())?;
+
+        assert_eq!(candidate.framing, Framing::Invalid);
+        assert_eq!(candidate.data, b"Some.");
+        assert!(candidate.to_string().contains("--pivot 3 --no-framing"));
+        Ok(())
+    }
+}
@@ -1,16 +1,38 @@
-use std::{convert::TryInto, error::Error, fs::File, io::Read, sync::mpsc::channel};
+use std::{
+    cell::Cell,
+    convert::TryInto,
+    error::Error,
+    fs::{self, File},
+    io::Read,
+    path::Path,
+    sync::mpsc::{channel, Sender},
+};
 
 use clap::Clap;
-use log::{info, trace};
+use log::{debug, info, trace};
+use ptero_common::{
+    config::keyed_rng,
+    ecc::{EccLevel, MAX_ECC_LEVEL},
+    encryption::DELIMITED_SEALING_OVERHEAD,
+    frame::{frame_payload, FrameOptions},
+    multipart::{conceal_in_parts, Attempt},
+    rotation::VariantRotation,
+    selection::{LineSelector, Spread, SpreadMode, POOL_COUNT, SPREAD_DESCRIPTOR_LENGTH},
+};
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha20Rng;
 
 use crate::{
-    binary::BitIterator,
-    context::PivotByLineContext,
-    encoder::Encoder,
+    binary::{Bit, BitIterator},
+    context::{Context, ContextError, ContextErrorKind, PivotByLineContext},
+    deniable::{encode_deniable, KeyedPayload, RECORD_LENGTH},
+    encoder::{Encoder, EncodingError},
+    header::{MethodId, StegoHeader, HEADER_LENGTH, MAX_HEADER_PIVOT},
     method::{
         complex::{eluv::ELUVMethodBuilder, extended_line::ExtendedLineMethodBuilder},
         trailing_unicode::character_sets::CharacterSetType,
     },
+    spread::write_spread,
 };
 
 use super::{
@@ -18,6 +40,11 @@ use super::{
     writer::Writer,
 };
 
+/// Salt of the RNG derived from `--key`, fixed so the key alone reproduces the stego text.
+const KEY_SALT: &[u8] = b"ptero-cli encode";
+/// Salt of the RNG filling the pools with `--deniable-key`, so the filler doesn't repeat the random choices.
+const DENIABLE_SALT: &[u8] = b"ptero-cli encode deniable";
+
 #[derive(Clap, Debug, PartialEq)]
 pub enum ELUVCharacterSet {
     FourBit,
@@ -27,14 +54,29 @@ pub enum ELUVCharacterSet {
     Twitter,
 }
 
+#[derive(Clap, Debug, PartialEq, Clone, Copy)]
+pub enum LineSpread {
+    Uniform,
+    Random,
+}
+
+/// State derived from the secrets passed to the command.
+/// Derived once, as the key derivation is slow on purpose.
+struct DerivedSecrets {
+    line_selector: Option<LineSelector>,
+    variant_rotation: Option<VariantRotation>,
+    rng: Option<ChaCha20Rng>,
+}
+
 /// Encode the secret into given cover text
 #[derive(Clap)]
 pub struct EncodeSubCommand {
     /// Path to cover text used to encoding.
     ///
+    /// Can be repeated together with `--parts-dir` to split the secret data across multiple cover texts.
     /// Please note that original whitespace may not be preserved!
-    #[clap(short, long)]
-    cover: String,
+    #[clap(short, long, required = true)]
+    cover: Vec<String>,
 
     /// Path to secret data file which will be encoded.
     #[clap(short, long)]
@@ -84,6 +126,119 @@ pub struct EncodeSubCommand {
     #[clap(long = "eline", group = "method_args")]
     #[allow(dead_code)]
     extended_line: bool,
+
+    /// Append CRC32 checksum to the secret data.
+    ///
+    /// Decoding detects the checksum on its own and fails if the stego text got corrupted,
+    /// instead of returning garbage.
+    #[clap(long)]
+    checksum: bool,
+
+    /// Encrypt the secret data with given passphrase.
+    ///
+    /// The key is derived with a memory-hard function (Argon2id) and the data is sealed
+    /// with an authenticated cipher (ChaCha20-Poly1305). The same passphrase is required to decode.
+    /// Please note that the passphrase may be visible to other users of the system,
+    /// consider using `--passphrase-file` instead.
+    #[clap(long, conflicts_with = "passphrase-file")]
+    passphrase: Option<String>,
+
+    /// Path to the file with the passphrase used to encrypt the secret data.
+    ///
+    /// Single trailing newline is ignored.
+    #[clap(long)]
+    passphrase_file: Option<String>,
+
+    /// Encrypt the secret data to the recipient's public key (`ptero-pk-...`), see `keygen`.
+    ///
+    /// Only the owner of the matching key file can decode it, with `decode --identity`. No secret has to be shared,
+    /// every encoding uses a fresh ephemeral key exchanged with X25519 and the data is sealed with ChaCha20-Poly1305.
+    /// Can be combined with `--passphrase`, then both are needed to decode.
+    #[clap(long)]
+    recipient: Option<String>,
+
+    /// Compress the secret data before encoding.
+    ///
+    /// Short English messages are compressed with a built-in Huffman code, anything else with Deflate.
+    /// The data is left as is if compression doesn't make it smaller.
+    /// Decoding detects the compression on its own.
+    #[clap(long)]
+    compress: bool,
+
+    /// Protect the secret data with error correcting code of given level (1-8).
+    ///
+    /// Every 16 bytes of the data get `2 * level` parity bytes, so up to `level` bytes per block
+    /// can be recovered when some lines of the stego text get mangled.
+    /// The same level has to be passed when decoding.
+    #[clap(long)]
+    ecc: Option<u8>,
+
+    /// Don't put the header describing the method, variant, set and pivot at the beginning of the stego text.
+    ///
+    /// By default the first 24 lines of the stego text carry the header, so decoding doesn't need any
+    /// of these parameters. Without the header they have to be passed to the decoder again.
+    #[clap(long)]
+    no_header: bool,
+
+    /// Split the secret data into parts, each encoded in the next cover text, and save them to given directory.
+    ///
+    /// Every part carries the message ID, its index and the total count of parts, so decoding can take
+    /// the stego texts in any order. Cover texts are filled as much as possible, so the unused ones
+    /// are skipped. Parts are saved as `part_<index>_of_<total>.txt`.
+    #[clap(long)]
+    parts_dir: Option<String>,
+
+    /// Secret choosing the lines which carry the data, others are left untouched as decoys.
+    ///
+    /// Without it the data is encoded line by line from the top, which leaves the recognizable
+    /// pattern of modified beginning and pristine end of the stego text. The header is not affected.
+    /// Lowers the capacity of the cover text about twice. The same secret has to be passed when decoding.
+    #[clap(long)]
+    line_key: Option<String>,
+
+    /// Space the lines carrying the data evenly (`uniform`) or pseudo-randomly (`random`) through the whole cover text.
+    ///
+    /// The distance between the lines is worked out from the size of the data and the capacity of the cover text,
+    /// the lines in between are left untouched. No secret is needed - the distance is recorded in the 8 lines
+    /// following the header, but `--spread` has to be passed when decoding.
+    #[clap(long, arg_enum, conflicts_with = "line-key")]
+    spread: Option<LineSpread>,
+
+    /// Secret drawing the order of the method's submethods for every line, instead of the fixed `--variant`.
+    ///
+    /// Every line uses one of all the possible orders, not only the three of the variants. `--variant` is ignored
+    /// and doesn't have to be shared then, but the same secret has to be passed when decoding.
+    #[clap(long)]
+    variant_key: Option<String>,
+
+    /// Secret deciding every random choice made while encoding, e.g. where the double whitespace goes.
+    ///
+    /// By default the choices are random, so encoding the same data twice gives different stego texts.
+    /// With the key they are made by the cryptographically secure RNG derived from it, so the same key,
+    /// cover text and data always give the same stego text. It isn't needed for decoding.
+    #[clap(long)]
+    key: Option<String>,
+
+    /// Secret encrypting the data deniably, revealed with `decode --key`.
+    ///
+    /// The lines are dealt into two pools by the seed concealed under the key in the first lines, the data
+    /// takes one of them at random. The data is encrypted and the pools are filled up with random bits,
+    /// so without the key there's no telling which pool carries anything - or whether both do, see `--decoy-data`.
+    /// Lowers the capacity of the cover text about twice. It's not `--key` here, as that one decides the random
+    /// choices and can be given along.
+    #[clap(long, conflicts_with_all = &["passphrase", "passphrase-file", "parts-dir", "line-key", "spread"])]
+    deniable_key: Option<String>,
+
+    /// Path to the decoy data encoded in the other pool, revealed by `--decoy-key` instead of `--deniable-key`.
+    ///
+    /// Both pools look the same without the key - the decoy can be revealed without giving away
+    /// that there's anything else.
+    #[clap(long, requires_all = &["deniable-key", "decoy-key"])]
+    decoy_data: Option<String>,
+
+    /// Secret encrypting the decoy data, revealed with `decode --key` as well. Has to differ from `--deniable-key`.
+    #[clap(long, requires = "decoy-data")]
+    decoy_key: Option<String>,
 }
 
 pub fn validate_pivot_smaller_than_text(
@@ -100,9 +255,26 @@ pub fn validate_pivot_smaller_than_text(
 
 impl EncodeSubCommand {
     pub fn run(&self) -> Result<Vec<u8>, Box<dyn Error>> {
-        let cover_file_input = File::open(&self.cover)?;
         let data_file_input = File::open(&self.data)?;
 
+        if let Some(parts_dir) = &self.parts_dir {
+            let cover_inputs = self
+                .cover
+                .iter()
+                .map(File::open)
+                .collect::<Result<Vec<File>, _>>()?;
+            let stego_texts = self.do_encode_in_parts(cover_inputs, data_file_input)?;
+            return save_parts(parts_dir, &stego_texts);
+        }
+        if self.cover.len() > 1 {
+            return Err("Multiple cover texts can only be used with `--parts-dir`.".into());
+        }
+        let cover_file_input = File::open(&self.cover[0])?;
+
+        if self.deniable_key.is_some() {
+            let decoy_file_input = self.decoy_data.as_ref().map(File::open).transpose()?;
+            return self.do_encode_deniable(cover_file_input, data_file_input, decoy_file_input);
+        }
         self.do_encode(cover_file_input, data_file_input)
     }
 
@@ -119,36 +291,393 @@ impl EncodeSubCommand {
 
         trace!("text: {:?}", data);
 
-        let pivot = pick_pivot_from(
-            self.pivot,
-            determine_pivot_size(cover_text.split_whitespace()),
-        )?;
-
-        validate_pivot_smaller_than_text(pivot, &cover_text)?;
-
+        let pivot = self.pick_pivot(&[cover_text.as_str()])?;
+        let payload = frame_payload(&data, &self.get_frame_options()?);
         let capacity_msg = format!(
             "Required cover text capacity: {}",
-            BitIterator::new(&data).count()
+            BitIterator::new(&payload).count()
         );
         Writer::warn(&capacity_msg);
         info!("Encoding secret data");
 
-        let mut data_iterator = BitIterator::new(&data);
+        let progress_bar = new_progress_bar(BitIterator::new(&payload).count() as u64);
+        let (tx, rx) = channel::<ProgressStatus>();
+        progress_bar.set_message("Encoding..");
+        spawn_progress_thread(progress_bar.clone(), rx);
 
-        let progress_bar = new_progress_bar(data_iterator.count() as u64);
+        let stego_result = self.encode_payload(
+            &cover_text,
+            pivot,
+            &payload,
+            &self.get_secrets(),
+            &Cell::new(0),
+            Some(&tx),
+        );
+
+        tx.send(ProgressStatus::Finished).ok();
+        progress_bar.finish_with_message("Finished encoding");
+
+        Ok(stego_result?.as_bytes().into())
+    }
+
+    /// Encodes the secret data and the optional decoy data into separate pools of the lines,
+    /// each encrypted with its own key. See [crate::deniable] for details.
+    pub(crate) fn do_encode_deniable(
+        &self,
+        mut cover_input: impl Read,
+        mut data_input: impl Read,
+        decoy_input: Option<impl Read>,
+    ) -> Result<Vec<u8>, Box<dyn Error>> {
+        let key = self
+            .deniable_key
+            .as_ref()
+            .ok_or("`--deniable-key` is needed to encode the data deniably.")?;
+        let mut cover_text = String::new();
+        let mut data = vec![];
+
+        cover_input.read_to_string(&mut cover_text)?;
+        data_input.read_to_end(&mut data)?;
+        let decoy = match (decoy_input, &self.decoy_key) {
+            (Some(_), Some(decoy_key)) if decoy_key == key => {
+                return Err("Decoy key has to differ from the deniable key.".into())
+            }
+            (Some(mut decoy_input), Some(decoy_key)) => {
+                let mut decoy_data = vec![];
+                decoy_input.read_to_end(&mut decoy_data)?;
+                Some((decoy_data, decoy_key))
+            }
+            (Some(_), None) => {
+                return Err("`--decoy-key` is needed to encode the decoy data.".into())
+            }
+            (None, _) => None,
+        };
+
+        let pivot = self.pick_pivot(&[cover_text.as_str()])?;
+        let frame_options = self.get_frame_options()?;
+        let mut payloads = vec![(frame_payload(&data, &frame_options), key)];
+        if let Some((decoy_data, decoy_key)) = &decoy {
+            payloads.push((frame_payload(decoy_data, &frame_options), decoy_key));
+        }
+        // Both pools are about the same size, so the larger payload decides
+        let pool_bits = payloads
+            .iter()
+            .map(|(payload, _)| (payload.len() + DELIMITED_SEALING_OVERHEAD) * 8)
+            .max()
+            .unwrap_or_default();
+        Writer::warn(&format!(
+            "Required cover text capacity: {}",
+            RECORD_LENGTH * 8 + POOL_COUNT * pool_bits
+        ));
+        if decoy.is_some() {
+            info!("Encoding secret data along with the decoy data");
+        } else {
+            info!("Encoding secret data deniably");
+        }
+
+        let total_bits = payloads
+            .iter()
+            .map(|(payload, _)| (payload.len() + DELIMITED_SEALING_OVERHEAD) * 8)
+            .sum::<usize>();
+        let progress_bar = new_progress_bar(total_bits as u64);
         let (tx, rx) = channel::<ProgressStatus>();
         progress_bar.set_message("Encoding..");
         spawn_progress_thread(progress_bar.clone(), rx);
 
-        let method = self.get_method()?;
-        info!("Using method variant {}", self.variant);
+        let secrets = self.get_secrets();
         let mut context = PivotByLineContext::new(&cover_text, pivot);
-        let stego_result = method.encode(&mut context, &mut data_iterator, Some(&tx));
+        if let Some(rng) = &secrets.rng {
+            context.randomize_with(rng.clone());
+        }
+        let mut stego_text = String::new();
+        if !self.no_header {
+            stego_text.push_str(&self.get_header(pivot).write(&mut context)?);
+        }
+        if let Some(variant_rotation) = &secrets.variant_rotation {
+            context.rotate_variants_with(variant_rotation.clone());
+        }
+        let method = self.get_method()?;
+        info!("Using method variant {}", self.get_variant());
+        let keyed_payloads = payloads
+            .iter()
+            .map(|(payload, key)| KeyedPayload {
+                data: payload,
+                key: key.as_bytes(),
+            })
+            .collect::<Vec<KeyedPayload>>();
+        let mut rng = match &self.key {
+            Some(key) => keyed_rng(key, DENIABLE_SALT),
+            None => ChaCha20Rng::from_entropy(),
+        };
+        let stego_result = encode_deniable(
+            method.as_ref(),
+            &mut context,
+            &keyed_payloads,
+            &mut rng,
+            Some(&tx),
+        );
 
         tx.send(ProgressStatus::Finished).ok();
         progress_bar.finish_with_message("Finished encoding");
 
-        Ok(stego_result?.as_bytes().into())
+        stego_text.push_str(&stego_result?);
+        Ok(stego_text.into_bytes())
+    }
+
+    /// Splits the data into parts and encodes each of them in the next cover text.
+    /// All the parts use the same pivot, so they can be decoded with the same parameters.
+    ///
+    /// # Returns
+    /// Stego texts of the parts, ordered by the part index.
+    pub(crate) fn do_encode_in_parts(
+        &self,
+        cover_inputs: Vec<impl Read>,
+        mut data_input: impl Read,
+    ) -> Result<Vec<String>, Box<dyn Error>> {
+        let mut cover_texts = Vec::with_capacity(cover_inputs.len());
+        for mut cover_input in cover_inputs {
+            let mut cover_text = String::new();
+            cover_input.read_to_string(&mut cover_text)?;
+            cover_texts.push(cover_text);
+        }
+        let mut data = vec![];
+        data_input.read_to_end(&mut data)?;
+
+        let pivot = self.pick_pivot(
+            &cover_texts
+                .iter()
+                .map(String::as_str)
+                .collect::<Vec<&str>>(),
+        )?;
+        let frame_options = self.get_frame_options()?;
+        let secrets = self.get_secrets();
+        let message_id = rand::thread_rng().gen::<u32>();
+        info!(
+            "Encoding secret data in parts of the message {:08x}",
+            message_id
+        );
+
+        let progress_bar = new_progress_bar(cover_texts.len() as u64);
+        let (tx, rx) = channel::<ProgressStatus>();
+        progress_bar.set_message("Encoding parts..");
+        spawn_progress_thread(progress_bar.clone(), rx);
+
+        let stego_result =
+            conceal_in_parts(&data, message_id, cover_texts.len(), |cover_index, part| {
+                let payload = frame_payload(part, &frame_options);
+                let total_bits = BitIterator::new(&payload).count();
+                let consumed_bits = Cell::new(0);
+
+                match self.encode_payload(
+                    &cover_texts[cover_index],
+                    pivot,
+                    &payload,
+                    &secrets,
+                    &consumed_bits,
+                    None,
+                ) {
+                    Ok(stego_text) => {
+                        tx.send(ProgressStatus::Step(1)).ok();
+                        Ok(Attempt::Concealed(stego_text))
+                    }
+                    Err(error) => {
+                        let remaining_bits = total_bits - consumed_bits.get();
+                        if remaining_bits == 0 || !is_capacity_error(error.as_ref()) {
+                            return Err(error);
+                        }
+                        debug!(
+                            "Cover text {} is too small, {} bits left: {}",
+                            cover_index + 1,
+                            remaining_bits,
+                            error
+                        );
+                        Ok(Attempt::TooSmall { remaining_bits })
+                    }
+                }
+            });
+
+        tx.send(ProgressStatus::Finished).ok();
+        progress_bar.finish_with_message("Finished encoding");
+
+        stego_result
+    }
+
+    /// Encodes the payload in the cover text. With the spread requested, the widest one is tried first
+    /// and narrowed down until the payload fits.
+    ///
+    /// `consumed_bits` is set to the amount of bits encoded by the last attempt.
+    fn encode_payload(
+        &self,
+        cover_text: &str,
+        pivot: usize,
+        payload: &[u8],
+        secrets: &DerivedSecrets,
+        consumed_bits: &Cell<usize>,
+        progress_channel: Option<&Sender<ProgressStatus>>,
+    ) -> Result<String, Box<dyn Error>> {
+        let mut last_error = None;
+        for spread in self.candidate_spreads(cover_text, pivot, payload)? {
+            consumed_bits.set(0);
+            let mut data_iterator =
+                BitIterator::new(payload).inspect(|_| consumed_bits.set(consumed_bits.get() + 1));
+            match self.encode_cover(
+                cover_text,
+                pivot,
+                &mut data_iterator,
+                secrets,
+                spread,
+                progress_channel,
+            ) {
+                Ok(stego_text) => return Ok(stego_text),
+                Err(error) => {
+                    if let Some(spread) = spread {
+                        debug!("Data doesn't fit with {:?}: {}", spread, error);
+                    }
+                    last_error = Some(error);
+                }
+            }
+        }
+        Err(last_error.unwrap_or_else(|| "No way to encode the data has been found.".into()))
+    }
+
+    /// Lists the spreads to try, from the widest one estimated by the capacity of the cover text.
+    /// Without the spread requested, the data is just encoded line by line.
+    fn candidate_spreads(
+        &self,
+        cover_text: &str,
+        pivot: usize,
+        payload: &[u8],
+    ) -> Result<Vec<Option<Spread>>, Box<dyn Error>> {
+        let mode = match self.spread {
+            None => return Ok(vec![None]),
+            Some(LineSpread::Uniform) => SpreadMode::Uniform,
+            Some(LineSpread::Random) => SpreadMode::Random,
+        };
+        let mut context = PivotByLineContext::new(cover_text, pivot);
+        let mut line_count: usize = 0;
+        while context.load_text().is_ok() {
+            line_count += 1;
+        }
+        let descriptive_lines = if self.no_header {
+            SPREAD_DESCRIPTOR_LENGTH
+        } else {
+            HEADER_LENGTH + SPREAD_DESCRIPTOR_LENGTH
+        };
+        let needed_lines = BitIterator::new(payload)
+            .count()
+            .div_ceil(self.get_method()?.bitrate());
+        let widest = Spread::fitting(
+            mode,
+            needed_lines,
+            line_count.saturating_sub(descriptive_lines),
+        );
+        debug!("Estimated the widest spread as {:?}", widest);
+        Ok((1..=widest.stride)
+            .rev()
+            .map(|stride| Some(Spread { mode, stride }))
+            .collect())
+    }
+
+    /// Encodes the data in the cover text, preceded by the header unless disabled
+    /// and by the spread descriptor if requested.
+    fn encode_cover(
+        &self,
+        cover_text: &str,
+        pivot: usize,
+        data: &mut dyn Iterator<Item = Bit>,
+        secrets: &DerivedSecrets,
+        spread: Option<Spread>,
+        progress_channel: Option<&Sender<ProgressStatus>>,
+    ) -> Result<String, Box<dyn Error>> {
+        let mut context = PivotByLineContext::new(cover_text, pivot);
+        if let Some(rng) = &secrets.rng {
+            // Every attempt starts from the same state, so the result depends only on the key
+            context.randomize_with(rng.clone());
+        }
+        let mut stego_text = String::new();
+        if !self.no_header {
+            stego_text.push_str(&self.get_header(pivot).write(&mut context)?);
+        }
+        if let Some(spread) = spread {
+            stego_text.push_str(&write_spread(&spread, &mut context)?);
+            context.select_lines_with(LineSelector::spread(spread));
+        }
+        if let Some(line_selector) = &secrets.line_selector {
+            context.select_lines_with(line_selector.clone());
+        }
+        if let Some(variant_rotation) = &secrets.variant_rotation {
+            context.rotate_variants_with(variant_rotation.clone());
+        }
+
+        let method = self.get_method()?;
+        info!("Using method variant {}", self.get_variant());
+        stego_text.push_str(&method.encode(&mut context, data, progress_channel)?);
+        Ok(stego_text)
+    }
+
+    /// Picks the pivot suitable for all the cover texts.
+    fn pick_pivot(&self, cover_texts: &[&str]) -> Result<usize, Box<dyn Error>> {
+        let pivot = pick_pivot_from(
+            self.pivot,
+            determine_pivot_size(cover_texts.iter().flat_map(|text| text.split_whitespace())),
+        )?;
+
+        for cover_text in cover_texts {
+            validate_pivot_smaller_than_text(pivot, cover_text)?;
+        }
+        if !self.no_header && pivot > MAX_HEADER_PIVOT {
+            return Err(format!(
+                "Pivot cannot be greater than {} when the header is used. Consider using `--no-header`.",
+                MAX_HEADER_PIVOT
+            )
+            .into());
+        }
+        Ok(pivot)
+    }
+
+    fn get_line_selector(&self) -> Option<LineSelector> {
+        self.line_key.as_ref().map(LineSelector::new)
+    }
+
+    fn get_secrets(&self) -> DerivedSecrets {
+        DerivedSecrets {
+            line_selector: self.get_line_selector(),
+            variant_rotation: self.variant_key.as_ref().map(VariantRotation::new),
+            rng: self.key.as_ref().map(|key| keyed_rng(key, KEY_SALT)),
+        }
+    }
+
+    fn get_frame_options(&self) -> Result<FrameOptions, Box<dyn Error>> {
+        Ok(FrameOptions {
+            checksum: self.checksum,
+            passphrase: read_passphrase(&self.passphrase, &self.passphrase_file)?,
+            compression: self.compress,
+            error_correction: parse_ecc_level(self.ecc)?,
+            recipient: self.recipient.as_deref().map(str::parse).transpose()?,
+            identity: None,
+        })
+    }
+
+    /// With the rotation, the variant only decides in which order the submethods modify the line.
+    /// The first one is always used, so the stego text can be decoded without `--variant`.
+    fn get_variant(&self) -> u8 {
+        if self.variant_key.is_some() {
+            1
+        } else {
+            self.variant
+        }
+    }
+
+    pub(crate) fn get_header(&self, pivot: usize) -> StegoHeader {
+        if self.eluv {
+            StegoHeader::new(
+                MethodId::ELUV,
+                self.get_variant(),
+                Some(get_character_set_type(&self.set)),
+                pivot,
+            )
+        } else {
+            StegoHeader::new(MethodId::ExtendedLine, self.get_variant(), None, pivot)
+        }
     }
 
     pub(crate) fn get_method(
@@ -158,13 +687,13 @@ impl EncodeSubCommand {
             Box::new(
                 ELUVMethodBuilder::new()
                     .character_set(get_character_set_type(&self.set))
-                    .variant(self.variant.try_into()?)
+                    .variant(self.get_variant().try_into()?)
                     .build(),
             )
         } else {
             Box::new(
                 ExtendedLineMethodBuilder::new()
-                    .variant(self.variant.try_into()?)
+                    .variant(self.get_variant().try_into()?)
                     .build(),
             )
         })
@@ -185,6 +714,76 @@ pub fn get_character_set_type(set_option: &Option<ELUVCharacterSet>) -> Characte
     }
 }
 
+/// Saves the stego texts of the parts to the directory, creating it if needed.
+///
+/// # Returns
+/// Paths of the saved parts, one per line.
+fn save_parts(parts_dir: &str, stego_texts: &[String]) -> Result<Vec<u8>, Box<dyn Error>> {
+    fs::create_dir_all(parts_dir)?;
+    let total = stego_texts.len();
+    let mut paths = Vec::with_capacity(total);
+    for (index, stego_text) in stego_texts.iter().enumerate() {
+        let path = Path::new(parts_dir).join(format!("part_{}_of_{}.txt", index + 1, total));
+        fs::write(&path, stego_text)?;
+        Writer::info(&format!(
+            "Saved part {} of {} to '{}'",
+            index + 1,
+            total,
+            path.display()
+        ));
+        paths.push(path.display().to_string());
+    }
+    Ok(paths.join("\n").into_bytes())
+}
+
+/// Tells whether the error means the cover text has run out before all the data has been encoded.
+fn is_capacity_error(error: &(dyn Error + 'static)) -> bool {
+    if error.is::<EncodingError>() {
+        return true;
+    }
+    matches!(
+        error.downcast_ref::<ContextError>().map(ContextError::kind),
+        Some(ContextErrorKind::NoTextLeft)
+    )
+}
+
+pub(crate) fn read_passphrase(
+    passphrase: &Option<String>,
+    passphrase_file: &Option<String>,
+) -> Result<Option<Vec<u8>>, Box<dyn Error>> {
+    if let Some(passphrase) = passphrase {
+        return Ok(Some(passphrase.as_bytes().to_vec()));
+    }
+    if let Some(path) = passphrase_file {
+        let mut passphrase = vec![];
+        File::open(path)?.read_to_end(&mut passphrase)?;
+        if passphrase.ends_with(b"\n") {
+            passphrase.pop();
+            if passphrase.ends_with(b"\r") {
+                passphrase.pop();
+            }
+        }
+        if passphrase.is_empty() {
+            return Err("Passphrase file is empty.".into());
+        }
+        return Ok(Some(passphrase));
+    }
+    Ok(None)
+}
+
+pub(crate) fn parse_ecc_level(level: Option<u8>) -> Result<Option<EccLevel>, Box<dyn Error>> {
+    match level {
+        Some(value) => EccLevel::new(value).map(Some).ok_or_else(|| {
+            format!(
+                "Error correction level has to be between 1 and {}.",
+                MAX_ECC_LEVEL
+            )
+            .into()
+        }),
+        None => Ok(None),
+    }
+}
+
 pub(crate) fn determine_pivot_size<'a>(words: impl Iterator<Item = &'a str>) -> usize {
     words
         .into_iter()
@@ -218,6 +817,8 @@ mod test {
 
     use crate::method::trailing_unicode::character_sets::CharacterSetType;
 
+    use crate::context::{ContextError, ContextErrorKind};
+
     use super::{get_character_set_type, ELUVCharacterSet, EncodeSubCommand};
 
     #[test]
@@ -226,13 +827,28 @@ mod test {
         let data_input: Vec<u8> = vec![0b11111111];
 
         let command = EncodeSubCommand {
-            cover: "stub".into(),
+            cover: vec!["stub".into()],
             data: "stub".into(),
             pivot: Some(3),
             eluv: false,
             extended_line: true,
             set: None,
             variant: 1,
+            checksum: false,
+            passphrase: None,
+            passphrase_file: None,
+            recipient: None,
+            compress: false,
+            ecc: None,
+            no_header: false,
+            parts_dir: None,
+            line_key: None,
+            key: None,
+            spread: None,
+            variant_key: None,
+            deniable_key: None,
+            decoy_data: None,
+            decoy_key: None,
         };
 
         let result = command.do_encode(cover_input.as_bytes(), data_input.as_slice());
@@ -246,13 +862,28 @@ mod test {
         let data_input: Vec<u8> = vec![0b11111111];
 
         let command = EncodeSubCommand {
-            cover: "stub".into(),
+            cover: vec!["stub".into()],
             data: "stub".into(),
             pivot: Some(3),
             eluv: false,
             extended_line: true,
             set: None,
             variant: 1,
+            checksum: false,
+            passphrase: None,
+            passphrase_file: None,
+            recipient: None,
+            compress: false,
+            ecc: None,
+            no_header: false,
+            parts_dir: None,
+            line_key: None,
+            key: None,
+            spread: None,
+            variant_key: None,
+            deniable_key: None,
+            decoy_data: None,
+            decoy_key: None,
         };
 
         let result = command.do_encode(cover_input.as_bytes(), data_input.as_slice());
@@ -266,13 +897,28 @@ mod test {
         let data_input: Vec<u8> = vec![0b11111111];
 
         let command = EncodeSubCommand {
-            cover: "stub".into(),
+            cover: vec!["stub".into()],
             data: "stub".into(),
             pivot: Some(6),
             eluv: false,
             extended_line: true,
             set: None,
             variant: 1,
+            checksum: false,
+            passphrase: None,
+            passphrase_file: None,
+            recipient: None,
+            compress: false,
+            ecc: None,
+            no_header: false,
+            parts_dir: None,
+            line_key: None,
+            key: None,
+            spread: None,
+            variant_key: None,
+            deniable_key: None,
+            decoy_data: None,
+            decoy_key: None,
         };
 
         let result = command.do_encode(cover_input.as_bytes(), data_input.as_slice());
@@ -280,6 +926,49 @@ mod test {
         Ok(())
     }
 
+    #[test]
+    fn encoding_in_parts_fails_with_the_error_other_than_capacity() -> Result<(), Box<dyn Error>> {
+        // Pivot is picked by the characters, but the lines are constructed by the bytes
+        let cover_input = format!("{}ąąąą {}", "a b c ".repeat(4), "a b c ".repeat(20));
+        let data_input: Vec<u8> = vec![0b11111111; 4];
+
+        let command = EncodeSubCommand {
+            cover: vec!["stub".into(), "stub".into()],
+            data: "stub".into(),
+            pivot: Some(5),
+            eluv: false,
+            extended_line: true,
+            set: None,
+            variant: 1,
+            checksum: false,
+            passphrase: None,
+            passphrase_file: None,
+            recipient: None,
+            compress: false,
+            ecc: None,
+            no_header: true,
+            parts_dir: Some("stub".into()),
+            line_key: None,
+            key: None,
+            spread: None,
+            variant_key: None,
+            deniable_key: None,
+            decoy_data: None,
+            decoy_key: None,
+        };
+
+        let result = command.do_encode_in_parts(
+            vec![cover_input.as_bytes(), cover_input.as_bytes()],
+            data_input.as_slice(),
+        );
+        let error = result.expect_err("Encoding should stop at the word longer than pivot");
+        assert!(matches!(
+            error.downcast_ref::<ContextError>().map(ContextError::kind),
+            Some(ContextErrorKind::CannotConstructLine)
+        ));
+        Ok(())
+    }
+
     #[test]
     fn get_character_set_type_returns_default_when_none_is_provided() -> Result<(), Box<dyn Error>>
     {
@@ -0,0 +1,22 @@
+use std::error::Error;
+
+use clap::Clap;
+use ptero_common::public_key::Identity;
+
+use super::writer::Writer;
+
+/// Generate the key file for encrypting the secret data to the public key
+///
+/// The key file holds the secret identity passed to `decode --identity`, keep it private.
+/// Its first line is a comment with the public key, which can be shared with anyone encoding
+/// the data for you with `encode --recipient`.
+#[derive(Clap)]
+pub struct KeygenSubCommand {}
+
+impl KeygenSubCommand {
+    pub fn run(&self) -> Result<Vec<u8>, Box<dyn Error>> {
+        let identity = Identity::generate();
+        Writer::warn(&format!("Public key: {}", identity.recipient()));
+        Ok(identity.to_key_file().into_bytes())
+    }
+}
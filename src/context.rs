@@ -1,6 +1,8 @@
 use std::{error::Error, fmt};
 
 use log::error;
+use ptero_common::{rotation::VariantRotation, selection::LineSelector};
+use rand::{thread_rng, Rng, RngCore};
 
 use crate::text::{CoverTextLineIterator, CoverTextWordIterator};
 
@@ -23,6 +25,32 @@ pub trait Context {
     /// # Returns
     /// Result which is either `&String` or [ContextError]. Returned string is the newly loaded fragment.
     fn load_text(&mut self) -> Result<&str, ContextError>;
+
+    /// Tells whether currently loaded cover text fragment should carry the data.
+    /// Fragments that don't are left untouched as decoys.
+    ///
+    /// # Returns
+    /// Always `true`, unless the context selects the fragments with a key.
+    fn is_current_selected(&self) -> bool {
+        true
+    }
+
+    /// Tells in which order the submethods of complex method take the data bits
+    /// in currently loaded cover text fragment, as the indices of the submethods.
+    ///
+    /// # Returns
+    /// Always `None` - the order of the method is used, unless the context rotates it with a key.
+    fn rotated_order(&self) -> Option<[usize; 3]> {
+        None
+    }
+
+    /// Picks a random number lower than `upper_bound`, e.g. the position in the loaded fragment.
+    ///
+    /// # Returns
+    /// Number from the thread RNG, unless the context has been given its own.
+    fn gen_below(&mut self, upper_bound: usize) -> usize {
+        thread_rng().gen_range(0..upper_bound)
+    }
 }
 
 /// Context used by methods requiring pivot.
@@ -31,6 +59,10 @@ pub struct PivotByRawLineContext {
     pivot: usize,
     cover_text_iter: CoverTextLineIterator,
     current_text: Option<String>,
+    line_selector: Option<LineSelector>,
+    is_current_selected: bool,
+    variant_rotation: Option<VariantRotation>,
+    rotated_order: Option<[usize; 3]>,
 }
 /// Context used by methods requiring pivot.
 /// Loads cover text line by line, uses pivot and does not preserve original whitespace.
@@ -39,6 +71,11 @@ pub struct PivotByLineContext {
     pivot: usize,
     cover_text_iter: CoverTextWordIterator,
     current_text: Option<String>,
+    line_selector: Option<LineSelector>,
+    is_current_selected: bool,
+    variant_rotation: Option<VariantRotation>,
+    rotated_order: Option<[usize; 3]>,
+    rng: Option<Box<dyn RngCore>>,
 }
 
 impl PivotByRawLineContext {
@@ -47,12 +84,28 @@ impl PivotByRawLineContext {
             pivot,
             cover_text_iter: CoverTextLineIterator::new(cover_text),
             current_text: None,
+            line_selector: None,
+            is_current_selected: true,
+            variant_rotation: None,
+            rotated_order: None,
         }
     }
 
     pub fn get_pivot(&self) -> usize {
         self.pivot
     }
+
+    /// Selects the lines carrying the data with given selector, starting from the next loaded line.
+    /// Has to match the selection used when encoding.
+    pub fn select_lines_with(&mut self, line_selector: LineSelector) {
+        self.line_selector = Some(line_selector);
+    }
+
+    /// Rotates the order of the submethods with given rotation, starting from the next loaded line.
+    /// Has to match the rotation used when encoding.
+    pub fn rotate_variants_with(&mut self, variant_rotation: VariantRotation) {
+        self.variant_rotation = Some(variant_rotation);
+    }
 }
 
 impl PivotByLineContext {
@@ -63,6 +116,11 @@ impl PivotByLineContext {
             pivot,
             cover_text_iter: CoverTextWordIterator::new(cover_text),
             current_text: None,
+            line_selector: None,
+            is_current_selected: true,
+            variant_rotation: None,
+            rotated_order: None,
+            rng: None,
         }
     }
 
@@ -70,6 +128,24 @@ impl PivotByLineContext {
         self.pivot
     }
 
+    /// Selects the lines carrying the data with given selector, starting from the next loaded line.
+    /// Other lines are left untouched as decoys.
+    pub fn select_lines_with(&mut self, line_selector: LineSelector) {
+        self.line_selector = Some(line_selector);
+    }
+
+    /// Draws the order of the submethods for every line with given rotation,
+    /// starting from the next loaded line.
+    pub fn rotate_variants_with(&mut self, variant_rotation: VariantRotation) {
+        self.variant_rotation = Some(variant_rotation);
+    }
+
+    /// Makes the random choices of the methods with given RNG instead of the thread one,
+    /// e.g. the one derived from the shared key so the stego text can be reproduced.
+    pub fn randomize_with(&mut self, rng: impl RngCore + 'static) {
+        self.rng = Some(Box::new(rng));
+    }
+
     // Peeks the next word without forwarding the iterator.
     //
     // # Returns
@@ -141,10 +217,27 @@ impl Context for PivotByLineContext {
     // Result which is either the line or [ContextError] if anything fails. 
     fn load_text(&mut self) -> Result<&str, ContextError> {
         self.current_text = self.construct_line_by_pivot()?;
+        self.is_current_selected = select_next(&mut self.line_selector);
+        self.rotated_order = self.variant_rotation.as_mut().map(VariantRotation::next_order);
         self.current_text
             .as_deref()
             .ok_or_else(|| ContextError::new(ContextErrorKind::NoTextLeft))
     }
+
+    fn is_current_selected(&self) -> bool {
+        self.is_current_selected
+    }
+
+    fn rotated_order(&self) -> Option<[usize; 3]> {
+        self.rotated_order
+    }
+
+    fn gen_below(&mut self, upper_bound: usize) -> usize {
+        match self.rng.as_mut() {
+            Some(rng) => rng.gen_range(0..upper_bound),
+            None => thread_rng().gen_range(0..upper_bound),
+        }
+    }
 }
 
 impl Context for PivotByRawLineContext {
@@ -167,10 +260,27 @@ impl Context for PivotByRawLineContext {
     // Result which is either the line or [ContextError] if anything fails. 
     fn load_text(&mut self) -> Result<&str, ContextError> {
         self.current_text = self.cover_text_iter.next();
+        self.is_current_selected = select_next(&mut self.line_selector);
+        self.rotated_order = self.variant_rotation.as_mut().map(VariantRotation::next_order);
         self.current_text
             .as_deref()
             .ok_or_else(|| ContextError::new(ContextErrorKind::NoTextLeft))
     }
+
+    fn is_current_selected(&self) -> bool {
+        self.is_current_selected
+    }
+
+    fn rotated_order(&self) -> Option<[usize; 3]> {
+        self.rotated_order
+    }
+}
+
+fn select_next(line_selector: &mut Option<LineSelector>) -> bool {
+    match line_selector {
+        Some(line_selector) => line_selector.is_next_selected(),
+        None => true,
+    }
 }
 
 /// Enum determining the exact context error.
@@ -1,6 +1,7 @@
 use std::{convert::TryFrom, error::Error, sync::mpsc::Sender};
 
 use log::{debug};
+use ptero_common::frame::{FrameOptions, FrameReader};
 
 use crate::{binary::{Bit, BitVec}, cli::progress::ProgressStatus, context::{Context, ContextError}};
 
@@ -22,10 +23,48 @@ pub trait Decoder<D> where D: Context {
     /// [Bits]: crate::binary::Bit
     fn partial_decode(&self, context: &D) -> Result<Vec<Bit>, ContextError>;
 
+    /// Decodes the framed payload from the cover text (see [ptero_common::frame]).
+    /// Stops loading the text as soon as the whole frame has been read.
+    ///
+    /// # Returns
+    /// It returns `Result`, either exactly the hidden payload or error.
+    /// Fails when the cover text ends before the frame is complete.
     fn decode(&self, context: &mut D, progress_channel: Option<&Sender<ProgressStatus>>) -> Result<Vec<u8>, Box<dyn Error>> {
+        self.decode_with(context, &FrameOptions::default(), progress_channel)
+    }
+
+    /// Decodes the framed payload like [Decoder::decode], using the passphrase from
+    /// `frame_options` to decrypt it.
+    fn decode_with(&self, context: &mut D, frame_options: &FrameOptions, progress_channel: Option<&Sender<ProgressStatus>>) -> Result<Vec<u8>, Box<dyn Error>> {
+        let mut frame_reader = FrameReader::with_options(frame_options.clone());
+        debug!("Decoding framed secret from the text");
+        while !frame_reader.is_complete() && context.load_text().is_ok() {
+            if !context.is_current_selected() {
+                continue;
+            }
+            let data = self.partial_decode(context)?;
+            if let Some(tx) = progress_channel {
+                tx.send(ProgressStatus::Step(context.get_current_text()?.len() as u64)).ok();
+            }
+            for Bit(value) in data {
+                if frame_reader.is_complete() {
+                    break;
+                }
+                frame_reader.push_bit(value == 1)?;
+            }
+        }
+        Ok(frame_reader.into_payload()?)
+    }
+
+    /// Decodes all the bits from the cover text, without interpreting the frame.
+    /// The result is padded with zeroes to the byte-size boundary.
+    fn decode_raw(&self, context: &mut D, progress_channel: Option<&Sender<ProgressStatus>>) -> Result<Vec<u8>, Box<dyn Error>> {
         let mut secret = Vec::default();
         debug!("Decoding secret from the text");
         while context.load_text().is_ok() {
+            if !context.is_current_selected() {
+                continue;
+            }
             let mut data = self.partial_decode(&context)?;
             if let Some(tx) = progress_channel {
                 tx.send(ProgressStatus::Step(context.get_current_text()?.len() as u64)).ok();
@@ -0,0 +1,451 @@
+//! # Description
+//!
+//! Deniable encoding of up to two independent payloads in one stego text, e.g. an innocuous decoy and the real secret.
+//!
+//! Lines of the cover text are dealt into [POOL_COUNT] disjoint pools (see [LineSelector::pool]) and every payload
+//! takes one of them, picked at random. The payloads are sealed with their own keys together with their lengths
+//! (see [seal_delimited]) and the rest of every pool is filled with random bits. A pool without a payload carries
+//! only the filler. Without the key, each pool looks like the same random noise - revealing one payload doesn't tell
+//! whether the other one exists.
+//!
+//! The seed dealing the lines is random and concealed in the first lines of the stego text, in the allocation record.
+//! The record starts with a random nonce followed by one slot per pool - the seed masked by the key of the payload
+//! (see [keyed_rng]) or random bits for the pool with the filler only. Decoding unmasks every slot with the key,
+//! deals the lines and returns the payload it opens.
+//!
+//! Once the payloads are concealed, the filler continues for a random amount of lines and the rest of the cover
+//! is left untouched, the same way as by [Encoder::encode].
+//!
+//! Works with any complex method, e.g. [ExtendedLineMethod](crate::method::complex::extended_line::ExtendedLineMethod).
+//!
+//! # Examples
+//! ```
+//! use ptero::context::{PivotByLineContext, PivotByRawLineContext};
+//! use ptero::deniable::{decode_deniable, encode_deniable, KeyedPayload};
+//! use ptero::method::complex::extended_line::ExtendedLineMethod;
+//! use ptero_common::frame::{frame_payload, FrameOptions};
+//!
+//! let cover_text = "a b c ".repeat(500);
+//! let decoy = frame_payload(b"Hi", &FrameOptions::default());
+//! let secret = frame_payload(b"Go", &FrameOptions::default());
+//! let method = ExtendedLineMethod::default();
+//!
+//! let mut context = PivotByLineContext::new(&cover_text, 3);
+//! let stego_text = encode_deniable(
+//!     &method,
+//!     &mut context,
+//!     &[
+//!         KeyedPayload { data: &decoy, key: b"bamboo" },
+//!         KeyedPayload { data: &secret, key: b"eucalyptus" },
+//!     ],
+//!     &mut rand::thread_rng(),
+//!     None,
+//! )
+//! .unwrap();
+//!
+//! let new_context = |line_selector| {
+//!     let mut context = PivotByRawLineContext::new(&stego_text, 3);
+//!     if let Some(line_selector) = line_selector {
+//!         context.select_lines_with(line_selector);
+//!     }
+//!     context
+//! };
+//! let options = FrameOptions::default();
+//! assert_eq!(decode_deniable(&method, new_context, b"bamboo", &options).unwrap(), b"Hi");
+//! assert_eq!(decode_deniable(&method, new_context, b"eucalyptus", &options).unwrap(), b"Go");
+//! ```
+use std::{convert::TryFrom, error::Error, iter, sync::mpsc::Sender};
+
+use log::{debug, trace};
+use ptero_common::{
+    config::keyed_rng,
+    encryption::{open_delimited, seal_delimited},
+    frame::{unframe_payload, FrameOptions},
+    selection::{LineSelector, POOL_COUNT},
+};
+use rand::{seq::SliceRandom, Rng, RngCore, SeedableRng};
+use rand_chacha::ChaCha20Rng;
+
+use crate::{
+    binary::{Bit, BitIterator, BitVec},
+    cli::progress::ProgressStatus,
+    context::Context,
+    decoder::Decoder,
+    encoder::{Encoder, EncodingError},
+};
+
+/// Amount of bytes taken by the allocation record - the nonce and a slot per pool.
+pub const RECORD_LENGTH: usize = (1 + POOL_COUNT) * SLOT_LENGTH;
+
+const SLOT_LENGTH: usize = 8;
+// Part of the format, changing it makes older stego texts unreadable
+const SLOT_SALT: &[u8] = b"ptero deniable slot";
+
+/// Payload concealed in one of the pools, together with the key revealing it.
+#[derive(Debug, Clone, Copy)]
+pub struct KeyedPayload<'a> {
+    /// Framed payload, see [ptero_common::frame].
+    pub data: &'a [u8],
+    pub key: &'a [u8],
+}
+
+/// Bits of the pool - the sealed payload followed by the endless random filler.
+struct PoolData {
+    sealed: std::vec::IntoIter<Bit>,
+    filler: ChaCha20Rng,
+}
+
+impl Iterator for PoolData {
+    type Item = Bit;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.sealed
+            .next()
+            .or_else(|| Some(Bit(self.filler.gen_range(0..=1))))
+    }
+}
+
+/// Mask hiding the seed of the pools in the slot of the key.
+fn slot_mask(key: &[u8], nonce: u64) -> u64 {
+    keyed_rng(key, [SLOT_SALT, &nonce.to_be_bytes()].concat()).next_u64()
+}
+
+fn read_u64(bytes: &[u8]) -> u64 {
+    let mut buffer = [0; SLOT_LENGTH];
+    buffer.copy_from_slice(bytes);
+    u64::from_be_bytes(buffer)
+}
+
+/// Encodes every payload into its own pool of the lines loaded from the context, preceded by the allocation record.
+///
+/// The pools are dealt, assigned to the payloads and filled by `rng`. When there are fewer payloads than
+/// [POOL_COUNT], the pools left carry only the filler.
+///
+/// # Returns
+/// Result which is either the stego text or error if any of the payloads doesn't fit into its pool.
+pub fn encode_deniable<E>(
+    method: &dyn Encoder<E>,
+    context: &mut E,
+    payloads: &[KeyedPayload],
+    rng: &mut dyn RngCore,
+    progress_channel: Option<&Sender<ProgressStatus>>,
+) -> Result<String, Box<dyn Error>>
+where
+    E: Context,
+{
+    if payloads.is_empty() || payloads.len() > POOL_COUNT {
+        return Err(format!("Between 1 and {} payloads can be encoded.", POOL_COUNT).into());
+    }
+    let seed = rng.next_u64();
+    let nonce = rng.next_u64();
+    let mut pool_payloads = payloads
+        .iter()
+        .map(Some)
+        .chain(iter::repeat(None))
+        .take(POOL_COUNT)
+        .collect::<Vec<Option<&KeyedPayload>>>();
+    pool_payloads.shuffle(rng);
+
+    let mut record = nonce.to_be_bytes().to_vec();
+    let mut pools = Vec::with_capacity(POOL_COUNT);
+    for payload in pool_payloads {
+        let (slot, sealed) = match payload {
+            Some(payload) => (
+                seed ^ slot_mask(payload.key, nonce),
+                seal_delimited(payload.data, payload.key),
+            ),
+            None => (rng.next_u64(), vec![]),
+        };
+        record.extend_from_slice(&slot.to_be_bytes());
+        pools.push(PoolData {
+            sealed: BitIterator::new(&sealed).collect::<Vec<Bit>>().into_iter(),
+            filler: ChaCha20Rng::from_rng(&mut *rng)?,
+        });
+    }
+
+    let mut stego_text = String::new();
+    // The record fills up its last line with random bits, so the decoding knows where it ends
+    let mut record_bits = BitIterator::new(&record).collect::<Vec<Bit>>();
+    while record_bits.len() % method.bitrate() != 0 {
+        record_bits.push(Bit(rng.gen_range(0..=1)));
+    }
+    let mut record_bits = record_bits.into_iter();
+    let mut record_lines = 0;
+    while record_bits.len() > 0 {
+        context
+            .load_text()
+            .map_err(|_| EncodingError::capacity_error())?;
+        method.partial_encode(context, &mut record_bits)?;
+        stego_text.push_str(&format!("{}\n", context.get_current_text()?));
+        record_lines += 1;
+    }
+    debug!("Encoded the allocation record in {} lines", record_lines);
+
+    let mut pool_selectors = (0..POOL_COUNT)
+        .map(|pool| LineSelector::pool(seed, pool))
+        .collect::<Vec<LineSelector>>();
+    // Decoding loads the record lines with the pool already selected
+    for selector in pool_selectors.iter_mut() {
+        selector.by_ref().take(record_lines).for_each(drop);
+    }
+    let mut pool_lines = 0;
+    let mut filler_lines_left = None;
+    while filler_lines_left != Some(0) && context.load_text().is_ok() {
+        let pool = pool_selectors
+            .iter_mut()
+            .map(LineSelector::is_next_selected)
+            .collect::<Vec<bool>>()
+            .iter()
+            .position(|is_selected| *is_selected)
+            .unwrap_or_default();
+        trace!(
+            "Encoding line '{}' in the pool {}",
+            context.get_current_text()?,
+            pool
+        );
+        let data = &mut pools[pool];
+        let is_sealed_left = data.sealed.len() > 0;
+        match method.partial_encode(context, data) {
+            Ok(_) if is_sealed_left => {
+                if let Some(tx) = progress_channel {
+                    tx.send(ProgressStatus::Step(method.bitrate() as u64)).ok();
+                }
+            }
+            Ok(_) => {}
+            // Last lines may have no words left to extend them, only the filler is lost then
+            Err(error) if pools.iter().all(|data| data.sealed.len() == 0) => {
+                debug!("Skipping the filler of the last line: {}", error);
+            }
+            Err(error) => return Err(error),
+        }
+        stego_text.push_str(&format!("{}\n", context.get_current_text()?));
+        pool_lines += 1;
+
+        match filler_lines_left.as_mut() {
+            Some(lines) => *lines -= 1,
+            // Random amount of the filler hides where the payloads end
+            None if pools.iter().all(|data| data.sealed.len() == 0) => {
+                filler_lines_left = Some(rng.gen_range(0..=pool_lines));
+            }
+            None => {}
+        }
+    }
+    // Append the rest of possible missing cover text
+    let mut appended_line_count = 0;
+    while let Ok(line) = context.load_text() {
+        appended_line_count += 1;
+        stego_text.push_str(&format!("{}\n", &line));
+    }
+    debug!("Appended the {} of left lines", appended_line_count);
+
+    let remaining_bits = pools.iter().map(|data| data.sealed.len()).sum::<usize>();
+    if remaining_bits > 0 {
+        debug!("Capacity exceeded by {} bits", remaining_bits);
+        return Err(EncodingError::capacity_error().into());
+    }
+    Ok(stego_text)
+}
+
+/// Decodes the payload revealed by the key, trying every pool of the lines.
+///
+/// # Arguments
+///
+/// * `new_context` - creates the context of the whole stego text, which selects the lines with given selector if any
+/// * `key` - key of the payload to reveal
+/// * `frame_options` - options used to read the framed payload, the passphrase is not used
+///
+/// # Returns
+/// Result which is either the payload or error if the key doesn't open any of the pools.
+pub fn decode_deniable<D, F>(
+    method: &dyn Decoder<D>,
+    new_context: F,
+    key: &[u8],
+    frame_options: &FrameOptions,
+) -> Result<Vec<u8>, Box<dyn Error>>
+where
+    D: Context,
+    F: Fn(Option<LineSelector>) -> D,
+{
+    let mut context = new_context(None);
+    let mut record_bits = Vec::with_capacity(RECORD_LENGTH * 8);
+    let mut record_lines = 0;
+    while record_bits.len() < RECORD_LENGTH * 8 {
+        context.load_text()?;
+        record_bits.append(&mut method.partial_decode(&context)?);
+        record_lines += 1;
+    }
+    record_bits.truncate(RECORD_LENGTH * 8);
+    let record: Vec<u8> = TryFrom::try_from(BitVec::from(record_bits))?;
+    let (nonce, slots) = record.split_at(SLOT_LENGTH);
+    let mask = slot_mask(key, read_u64(nonce));
+
+    for (pool, slot) in slots.chunks(SLOT_LENGTH).enumerate() {
+        let mut context = new_context(Some(LineSelector::pool(read_u64(slot) ^ mask, pool)));
+        for _ in 0..record_lines {
+            context.load_text()?;
+        }
+        let pool_data = method.decode_raw(&mut context, None)?;
+        match open_delimited(&pool_data, key) {
+            Ok(framed) => {
+                debug!("Opened the payload of the pool {}", pool);
+                return Ok(unframe_payload(&framed, frame_options)?);
+            }
+            Err(error) => debug!("Couldn't open the pool {}: {}", pool, error),
+        }
+    }
+    Err("None of the payloads can be revealed with given key.".into())
+}
+
+#[allow(unused_imports)]
+mod test {
+    use std::error::Error;
+
+    use ptero_common::frame::{frame_payload, FrameOptions};
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha20Rng;
+
+    use crate::{
+        context::{PivotByLineContext, PivotByRawLineContext},
+        method::complex::extended_line::ExtendedLineMethod,
+    };
+
+    use super::{decode_deniable, encode_deniable, KeyedPayload};
+
+    #[test]
+    fn decodes_only_the_payload_of_given_key() -> Result<(), Box<dyn Error>> {
+        let cover_input = "a b c ".repeat(500);
+        let decoy = frame_payload(b"Hello", &FrameOptions::default());
+        let secret = frame_payload(b"Bye", &FrameOptions::default());
+        let method = ExtendedLineMethod::default();
+
+        let mut context = PivotByLineContext::new(&cover_input, 3);
+        let stego_text = encode_deniable(
+            &method,
+            &mut context,
+            &[
+                KeyedPayload {
+                    data: &decoy,
+                    key: b"bamboo",
+                },
+                KeyedPayload {
+                    data: &secret,
+                    key: b"eucalyptus",
+                },
+            ],
+            &mut ChaCha20Rng::seed_from_u64(7),
+            None,
+        )?;
+
+        let new_context = |line_selector| {
+            let mut context = PivotByRawLineContext::new(&stego_text, 3);
+            if let Some(line_selector) = line_selector {
+                context.select_lines_with(line_selector);
+            }
+            context
+        };
+        let options = FrameOptions::default();
+        assert_eq!(
+            decode_deniable(&method, new_context, b"bamboo", &options)?,
+            b"Hello"
+        );
+        assert_eq!(
+            decode_deniable(&method, new_context, b"eucalyptus", &options)?,
+            b"Bye"
+        );
+        assert!(decode_deniable(&method, new_context, b"panda", &options).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn decodes_single_payload_along_with_the_filler() -> Result<(), Box<dyn Error>> {
+        let cover_input = "a b c ".repeat(500);
+        let secret = frame_payload(b"Bye", &FrameOptions::default());
+        let method = ExtendedLineMethod::default();
+
+        let mut context = PivotByLineContext::new(&cover_input, 3);
+        let stego_text = encode_deniable(
+            &method,
+            &mut context,
+            &[KeyedPayload {
+                data: &secret,
+                key: b"eucalyptus",
+            }],
+            &mut ChaCha20Rng::seed_from_u64(7),
+            None,
+        )?;
+
+        let new_context = |line_selector| {
+            let mut context = PivotByRawLineContext::new(&stego_text, 3);
+            if let Some(line_selector) = line_selector {
+                context.select_lines_with(line_selector);
+            }
+            context
+        };
+        let options = FrameOptions::default();
+        assert_eq!(
+            decode_deniable(&method, new_context, b"eucalyptus", &options)?,
+            b"Bye"
+        );
+        assert!(decode_deniable(&method, new_context, b"bamboo", &options).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn leaves_the_tail_of_the_cover_untouched() -> Result<(), Box<dyn Error>> {
+        let cover_input = "a b c ".repeat(2000);
+        let secret = frame_payload(b"Bye", &FrameOptions::default());
+        let method = ExtendedLineMethod::default();
+
+        let mut context = PivotByLineContext::new(&cover_input, 3);
+        let stego_text = encode_deniable(
+            &method,
+            &mut context,
+            &[KeyedPayload {
+                data: &secret,
+                key: b"eucalyptus",
+            }],
+            &mut ChaCha20Rng::seed_from_u64(7),
+            None,
+        )?;
+
+        // Untouched lines keep the pivot and have no extra whitespace
+        let stego_lines = stego_text.lines().collect::<Vec<&str>>();
+        let untouched_lines = stego_lines
+            .iter()
+            .rev()
+            .skip(1)
+            .take_while(|line| line.len() <= 3 && line.trim() == **line)
+            .count();
+        assert!(untouched_lines > stego_lines.len() / 2);
+        assert!(untouched_lines < stego_lines.len() - 1);
+        Ok(())
+    }
+
+    #[test]
+    fn fails_when_payload_does_not_fit_into_its_pool() -> Result<(), Box<dyn Error>> {
+        let cover_input = "a b c ".repeat(100);
+        let decoy = frame_payload(b"Hello", &FrameOptions::default());
+        let method = ExtendedLineMethod::default();
+
+        let mut context = PivotByLineContext::new(&cover_input, 3);
+        let result = encode_deniable(
+            &method,
+            &mut context,
+            &[
+                KeyedPayload {
+                    data: &decoy,
+                    key: b"bamboo",
+                },
+                KeyedPayload {
+                    data: &[42; 64],
+                    key: b"eucalyptus",
+                },
+            ],
+            &mut ChaCha20Rng::seed_from_u64(7),
+            None,
+        );
+
+        assert!(result.is_err());
+        Ok(())
+    }
+}
@@ -58,6 +58,11 @@ where
         while !no_data_left {
             context.load_text()?;
             trace!("Current line '{}'", context.get_current_text()?);
+            if !context.is_current_selected() {
+                trace!("Leaving the line as a decoy");
+                stego_text.push_str(&format!("{}\n", context.get_current_text()?));
+                continue;
+            }
             match self.partial_encode(context, data)? {
                 EncoderResult::Success => {
                     if let Some(tx) = progress_channel {
@@ -90,7 +95,7 @@ where
 }
 
 /// Enum for data encoding errors types
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Copy, PartialEq)]
 pub enum EncodingErrorKind {
     CapacityTooLow,
     NoWordsLeft,
@@ -115,6 +120,10 @@ impl EncodingError {
             kind: EncodingErrorKind::NoWordsLeft,
         }
     }
+
+    pub fn kind(&self) -> EncodingErrorKind {
+        self.kind
+    }
 }
 
 #[cfg(not(tarpaulin_include))]
@@ -0,0 +1,434 @@
+//! # Description
+//!
+//! Self-describing header placed in the first [HEADER_LENGTH] lines of the stego text.
+//! It records everything the decoder needs to know - the method, its variant, the character set
+//! and the pivot - so the stego text can be decoded without any extra flags.
+//!
+//! The header is always encoded with the [TrailingWhitespaceMethod] (one bit per line), which doesn't
+//! depend on any of the recorded parameters. Bits are laid out as follows (most significant first):
+//!
+//! | version (2) | method (2) | variant (2) | character set (3) | pivot (8) | CRC-7 (7) |
+//!
+//! The checksum lets the decoder tell a real header apart from a cover text without one.
+//!
+//! # Examples
+//! ```
+//! use ptero::context::{PivotByLineContext, PivotByRawLineContext};
+//! use ptero::header::{MethodId, StegoHeader, HEADER_LENGTH};
+//! use ptero::method::trailing_unicode::character_sets::CharacterSetType;
+//!
+//! let cover_text = "a b\n".repeat(HEADER_LENGTH);
+//! let header = StegoHeader::new(MethodId::ELUV, 2, Some(CharacterSetType::TwitterUnicodeSet), 4);
+//! let mut context = PivotByLineContext::new(&cover_text, 4);
+//! let stego_text = header.write(&mut context).unwrap();
+//!
+//! let mut context = PivotByRawLineContext::new(&stego_text, 0);
+//! assert_eq!(StegoHeader::read(&mut context).unwrap(), header);
+//! ```
+
+use std::{error::Error, fmt};
+
+use log::{debug, trace};
+
+use crate::{
+    binary::Bit,
+    context::Context,
+    decoder::Decoder,
+    encoder::Encoder,
+    method::{
+        trailing_unicode::character_sets::CharacterSetType,
+        trailing_whitespace::TrailingWhitespaceMethod,
+    },
+};
+
+/// Version of the header layout written by this crate.
+pub const PROTOCOL_VERSION: u8 = 1;
+/// Amount of lines (and bits) taken by the header.
+pub const HEADER_LENGTH: usize = 24;
+/// The largest pivot that fits into the header.
+pub const MAX_HEADER_PIVOT: usize = u8::MAX as usize;
+
+const VERSION_BITS: usize = 2;
+const METHOD_BITS: usize = 2;
+const VARIANT_BITS: usize = 2;
+const CHARACTER_SET_BITS: usize = 3;
+const PIVOT_BITS: usize = 8;
+const CHECKSUM_BITS: usize = 7;
+// CRC-7 polynomial x^7 + x^3 + 1, non-zero initial value so lines without any trailing
+// whitespace are not mistaken for a header
+const CHECKSUM_POLYNOMIAL: u8 = 0x09;
+const CHECKSUM_INIT: u8 = 0x7f;
+
+/// Identifies the complex method used to encode the data.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum MethodId {
+    ExtendedLine,
+    ELUV,
+}
+
+/// Parameters of the encoding stored at the beginning of the stego text.
+#[derive(Debug, Clone, PartialEq)]
+pub struct StegoHeader {
+    pub version: u8,
+    pub method: MethodId,
+    /// Variant of the method, from 1 to 3.
+    pub variant: u8,
+    /// Character set used by the ELUV method, `None` for methods not using one.
+    pub character_set: Option<CharacterSetType>,
+    pub pivot: usize,
+}
+
+impl StegoHeader {
+    /// Creates the header in the current [PROTOCOL_VERSION].
+    pub fn new(
+        method: MethodId,
+        variant: u8,
+        character_set: Option<CharacterSetType>,
+        pivot: usize,
+    ) -> Self {
+        StegoHeader {
+            version: PROTOCOL_VERSION,
+            method,
+            variant,
+            character_set,
+            pivot,
+        }
+    }
+
+    /// Converts the header to bits, see the module docs for the layout.
+    ///
+    /// # Returns
+    /// Result which is either the [HEADER_LENGTH] bits or [HeaderError] if any value doesn't fit.
+    pub fn to_bits(&self) -> Result<Vec<Bit>, HeaderError> {
+        if self.pivot > MAX_HEADER_PIVOT {
+            return Err(HeaderError::new(HeaderErrorKind::PivotTooLarge(self.pivot)));
+        }
+        if !(1..=3).contains(&self.variant) {
+            return Err(HeaderError::new(HeaderErrorKind::InvalidVariant(
+                self.variant,
+            )));
+        }
+        let method = match self.method {
+            MethodId::ExtendedLine => 0,
+            MethodId::ELUV => 1,
+        };
+        let character_set = match self.character_set {
+            None | Some(CharacterSetType::FullUnicodeSet) => 0,
+            Some(CharacterSetType::FourBitUnicodeSet) => 1,
+            Some(CharacterSetType::ThreeBitUnicodeSet) => 2,
+            Some(CharacterSetType::TwoBitUnicodeSet) => 3,
+            Some(CharacterSetType::TwitterUnicodeSet) => 4,
+        };
+
+        let mut bits = Vec::with_capacity(HEADER_LENGTH);
+        push_number(&mut bits, self.version as usize, VERSION_BITS);
+        push_number(&mut bits, method, METHOD_BITS);
+        push_number(&mut bits, self.variant as usize - 1, VARIANT_BITS);
+        push_number(&mut bits, character_set, CHARACTER_SET_BITS);
+        push_number(&mut bits, self.pivot, PIVOT_BITS);
+        let checksum = crc7(&bits);
+        push_number(&mut bits, checksum as usize, CHECKSUM_BITS);
+        Ok(bits)
+    }
+
+    /// Restores the header from bits produced by [StegoHeader::to_bits].
+    ///
+    /// # Returns
+    /// Result which is either the header or [HeaderError] if the bits don't form a valid header.
+    pub fn from_bits(bits: &[Bit]) -> Result<Self, HeaderError> {
+        if bits.len() < HEADER_LENGTH {
+            return Err(HeaderError::new(HeaderErrorKind::TooShort));
+        }
+        let (fields, checksum) = bits[..HEADER_LENGTH].split_at(HEADER_LENGTH - CHECKSUM_BITS);
+        if crc7(fields) as usize != read_number(checksum) {
+            return Err(HeaderError::new(HeaderErrorKind::ChecksumMismatch));
+        }
+
+        let mut offset = 0;
+        let mut next_number = |length: usize| {
+            offset += length;
+            read_number(&fields[offset - length..offset])
+        };
+        let version = next_number(VERSION_BITS) as u8;
+        if version != PROTOCOL_VERSION {
+            return Err(HeaderError::new(HeaderErrorKind::UnsupportedVersion(
+                version,
+            )));
+        }
+        let method = match next_number(METHOD_BITS) {
+            0 => MethodId::ExtendedLine,
+            1 => MethodId::ELUV,
+            value => return Err(HeaderError::new(HeaderErrorKind::UnknownMethod(value as u8))),
+        };
+        let variant = next_number(VARIANT_BITS) as u8 + 1;
+        if variant > 3 {
+            return Err(HeaderError::new(HeaderErrorKind::InvalidVariant(variant)));
+        }
+        let character_set = match (method, next_number(CHARACTER_SET_BITS)) {
+            (MethodId::ExtendedLine, 0) => None,
+            (MethodId::ELUV, 0) => Some(CharacterSetType::FullUnicodeSet),
+            (MethodId::ELUV, 1) => Some(CharacterSetType::FourBitUnicodeSet),
+            (MethodId::ELUV, 2) => Some(CharacterSetType::ThreeBitUnicodeSet),
+            (MethodId::ELUV, 3) => Some(CharacterSetType::TwoBitUnicodeSet),
+            (MethodId::ELUV, 4) => Some(CharacterSetType::TwitterUnicodeSet),
+            (_, value) => {
+                return Err(HeaderError::new(HeaderErrorKind::UnknownCharacterSet(
+                    value as u8,
+                )))
+            }
+        };
+        let pivot = next_number(PIVOT_BITS);
+
+        Ok(StegoHeader {
+            version,
+            method,
+            variant,
+            character_set,
+            pivot,
+        })
+    }
+
+    /// Encodes the header into the next [HEADER_LENGTH] lines loaded from the context.
+    ///
+    /// # Returns
+    /// Result which is either the stego text of the header or error if the cover text is too short.
+    pub fn write<E>(&self, context: &mut E) -> Result<String, Box<dyn Error>>
+    where
+        E: Context,
+    {
+        debug!("Encoding the stego header {:?}", self);
+        write_bits(context, &self.to_bits()?)
+    }
+
+    /// Decodes the header from the next [HEADER_LENGTH] lines loaded from the context.
+    ///
+    /// # Returns
+    /// Result which is either the header or [HeaderError] if the text doesn't start with a valid one.
+    pub fn read<D>(context: &mut D) -> Result<Self, HeaderError>
+    where
+        D: Context,
+    {
+        let bits = read_bits(context, HEADER_LENGTH)
+            .ok_or_else(|| HeaderError::new(HeaderErrorKind::TooShort))?;
+        let header = StegoHeader::from_bits(&bits)?;
+        debug!("Decoded the stego header {:?}", header);
+        Ok(header)
+    }
+}
+
+/// Encodes the bits one per line with the [TrailingWhitespaceMethod], which doesn't depend
+/// on any parameters of the actual method.
+///
+/// # Returns
+/// Result which is either the stego text of the lines or error if the cover text is too short.
+pub(crate) fn write_bits<E>(context: &mut E, bits: &[Bit]) -> Result<String, Box<dyn Error>>
+where
+    E: Context,
+{
+    let method = TrailingWhitespaceMethod::new();
+    let line_count = bits.len();
+    let mut bits = bits.iter().cloned();
+    let mut stego_text = String::new();
+    for _ in 0..line_count {
+        context.load_text()?;
+        method.partial_encode(context, &mut bits)?;
+        let line = context.get_current_text()?;
+        trace!("Descriptive line '{}'", line);
+        stego_text.push_str(&format!("{}\n", line));
+    }
+    Ok(stego_text)
+}
+
+/// Decodes `count` bits written by [write_bits].
+///
+/// # Returns
+/// The bits or `None` if the stego text is too short.
+pub(crate) fn read_bits<D>(context: &mut D, count: usize) -> Option<Vec<Bit>>
+where
+    D: Context,
+{
+    let method = TrailingWhitespaceMethod::new();
+    let mut bits = Vec::with_capacity(count);
+    for _ in 0..count {
+        context.load_text().ok()?;
+        bits.append(&mut method.partial_decode(context).ok()?);
+    }
+    Some(bits)
+}
+
+pub(crate) fn push_number(bits: &mut Vec<Bit>, value: usize, length: usize) {
+    for position in (0..length).rev() {
+        bits.push(Bit(((value >> position) & 1) as u8));
+    }
+}
+
+pub(crate) fn read_number(bits: &[Bit]) -> usize {
+    bits.iter()
+        .fold(0, |value, Bit(bit)| (value << 1) | *bit as usize)
+}
+
+fn crc7(bits: &[Bit]) -> u8 {
+    bits.iter().fold(CHECKSUM_INIT, |crc, Bit(bit)| {
+        let feedback = ((crc >> (CHECKSUM_BITS - 1)) & 1) ^ bit;
+        let shifted = (crc << 1) & 0x7f;
+        if feedback == 1 {
+            shifted ^ CHECKSUM_POLYNOMIAL
+        } else {
+            shifted
+        }
+    })
+}
+
+/// Enum determining the exact header error.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum HeaderErrorKind {
+    TooShort,
+    ChecksumMismatch,
+    UnsupportedVersion(u8),
+    UnknownMethod(u8),
+    UnknownCharacterSet(u8),
+    InvalidVariant(u8),
+    PivotTooLarge(usize),
+}
+
+/// Error implementation for [StegoHeader]. Exact error message is determined by [HeaderErrorKind].
+#[derive(Debug)]
+pub struct HeaderError {
+    kind: HeaderErrorKind,
+}
+
+impl HeaderError {
+    fn new(kind: HeaderErrorKind) -> Self {
+        HeaderError { kind }
+    }
+
+    pub fn kind(&self) -> HeaderErrorKind {
+        self.kind
+    }
+}
+
+#[cfg(not(tarpaulin_include))]
+impl fmt::Display for HeaderError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.kind {
+            HeaderErrorKind::TooShort => write!(f, "Stego text is too short to contain the header."),
+            HeaderErrorKind::ChecksumMismatch => {
+                write!(f, "Stego text doesn't start with a valid header.")
+            }
+            HeaderErrorKind::UnsupportedVersion(version) => {
+                write!(f, "Header version {} is not supported.", version)
+            }
+            HeaderErrorKind::UnknownMethod(method) => {
+                write!(f, "Header contains unknown method {}.", method)
+            }
+            HeaderErrorKind::UnknownCharacterSet(set) => {
+                write!(f, "Header contains unknown character set {}.", set)
+            }
+            HeaderErrorKind::InvalidVariant(variant) => {
+                write!(f, "Header contains invalid variant {}.", variant)
+            }
+            HeaderErrorKind::PivotTooLarge(pivot) => write!(
+                f,
+                "Pivot {} is too large to be stored in the header, the maximum is {}.",
+                pivot, MAX_HEADER_PIVOT
+            ),
+        }
+    }
+}
+
+impl Error for HeaderError {}
+
+#[allow(unused_imports)]
+mod test {
+    use std::error::Error;
+
+    use crate::{
+        binary::Bit,
+        context::{PivotByLineContext, PivotByRawLineContext},
+        method::trailing_unicode::character_sets::CharacterSetType,
+    };
+
+    use super::{HeaderErrorKind, MethodId, StegoHeader, HEADER_LENGTH};
+
+    #[test]
+    fn converts_header_to_bits_and_back() -> Result<(), Box<dyn Error>> {
+        let header = StegoHeader::new(
+            MethodId::ELUV,
+            3,
+            Some(CharacterSetType::TwoBitUnicodeSet),
+            255,
+        );
+
+        let bits = header.to_bits()?;
+
+        assert_eq!(bits.len(), HEADER_LENGTH);
+        assert_eq!(&bits[..6], &[Bit(0), Bit(1), Bit(0), Bit(1), Bit(1), Bit(0)]);
+        assert_eq!(StegoHeader::from_bits(&bits)?, header);
+        Ok(())
+    }
+
+    #[test]
+    fn detects_corrupted_header() -> Result<(), Box<dyn Error>> {
+        let header = StegoHeader::new(MethodId::ExtendedLine, 1, None, 12);
+        let mut bits = header.to_bits()?;
+        bits[10] = Bit(1 - bits[10].0);
+
+        let result = StegoHeader::from_bits(&bits);
+
+        assert_eq!(
+            result.map_err(|error| error.kind()).err(),
+            Some(HeaderErrorKind::ChecksumMismatch)
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn fails_when_pivot_does_not_fit() -> Result<(), Box<dyn Error>> {
+        let header = StegoHeader::new(MethodId::ExtendedLine, 1, None, 256);
+
+        assert_eq!(
+            header.to_bits().map_err(|error| error.kind()).err(),
+            Some(HeaderErrorKind::PivotTooLarge(256))
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn reads_header_written_to_the_cover_text() -> Result<(), Box<dyn Error>> {
+        let cover_text = "a b c\n".repeat(HEADER_LENGTH + 1);
+        let header = StegoHeader::new(MethodId::ExtendedLine, 2, None, 6);
+
+        let stego_text = header.write(&mut PivotByLineContext::new(&cover_text, 6))?;
+        let result = StegoHeader::read(&mut PivotByRawLineContext::new(&stego_text, 0))?;
+
+        assert_eq!(stego_text.lines().count(), HEADER_LENGTH);
+        assert_eq!(result, header);
+        Ok(())
+    }
+
+    #[test]
+    fn fails_when_text_has_no_header() -> Result<(), Box<dyn Error>> {
+        let stego_text = "a b c\n".repeat(HEADER_LENGTH);
+
+        let result = StegoHeader::read(&mut PivotByRawLineContext::new(&stego_text, 0));
+
+        assert_eq!(
+            result.map_err(|error| error.kind()).err(),
+            Some(HeaderErrorKind::ChecksumMismatch)
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn fails_when_text_is_too_short() -> Result<(), Box<dyn Error>> {
+        let stego_text = "a b c\n".repeat(HEADER_LENGTH - 1);
+
+        let result = StegoHeader::read(&mut PivotByRawLineContext::new(&stego_text, 0));
+
+        assert_eq!(
+            result.map_err(|error| error.kind()).err(),
+            Some(HeaderErrorKind::TooShort)
+        );
+        Ok(())
+    }
+}
@@ -17,6 +17,15 @@ pub mod decoder;
 /// Context containing all needed data (e.g. access to cover text) for the steganography methods.
 pub mod context;
 
+/// Self-describing header of the stego text, allows decoding without passing the method parameters.
+pub mod header;
+
+/// Spacing of the lines carrying the data through the whole stego text.
+pub mod spread;
+
+/// Deniable encoding of two payloads, each revealed by its own key.
+pub mod deniable;
+
 /// Module containing all the available methods for text steganography. 
 pub mod method;
 
@@ -26,7 +35,9 @@ pub mod log;
 pub mod cli {
     pub mod capacity;
     pub mod decoder;
+    pub mod discovery;
     pub mod encoder;
+    pub mod keygen;
     pub mod writer;
     pub mod progress;
 }
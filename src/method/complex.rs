@@ -1,10 +1,72 @@
+use std::error::Error;
+
+use crate::{
+    binary::Bit,
+    context::{Context, ContextError},
+    encoder::EncoderResult,
+};
+
+use super::Method;
+
 pub mod eluv;
 pub mod extended_line;
 
+/// Encodes the data with the submethods taking the bits in given order, see
+/// [Context::rotated_order](crate::context::Context::rotated_order).
+///
+/// Every submethod gets its bits up front, but they still modify the fragment in the order
+/// of the method. This way every order can be decoded, e.g. the trailing character is never
+/// followed by the word extending the line.
+pub fn partial_encode_in_order<E, D>(
+    methods: &[Box<dyn Method<E, D>>],
+    order: [usize; 3],
+    context: &mut E,
+    data: &mut dyn Iterator<Item = Bit>,
+) -> Result<EncoderResult, Box<dyn Error>>
+where
+    E: Context,
+    D: Context,
+{
+    let mut method_bits = vec![Vec::new(); methods.len()];
+    for index in order.iter() {
+        method_bits[*index] = data.take(methods[*index].bitrate()).collect::<Vec<Bit>>();
+    }
+
+    let mut is_data_still_available = EncoderResult::Success;
+    for (encoder, bits) in methods.iter().zip(method_bits) {
+        if bits.len() < encoder.bitrate() {
+            is_data_still_available = EncoderResult::NoDataLeft;
+        }
+        encoder.partial_encode(context, &mut bits.into_iter())?;
+    }
+    Ok(is_data_still_available)
+}
+
+/// Decodes the data encoded with [partial_encode_in_order].
+pub fn partial_decode_in_order<E, D>(
+    methods: &[Box<dyn Method<E, D>>],
+    order: [usize; 3],
+    context: &D,
+) -> Result<Vec<Bit>, ContextError>
+where
+    E: Context,
+    D: Context,
+{
+    let mut method_bits = methods
+        .iter()
+        .map(|decoder| decoder.partial_decode(context))
+        .collect::<Result<Vec<Vec<Bit>>, ContextError>>()?;
+    Ok(order
+        .iter()
+        .flat_map(|index| std::mem::take(&mut method_bits[*index]))
+        .collect())
+}
+
 /// Macro for implementing the [Encoder](crate::encoder::Encoder) trait for
 /// complex methods. It assumes that method is only composed by other methods.
 ///
 /// Requires the method to have `methods` field being vector containing type [Encoder](crate::encoder::Encoder).
+/// The submethods take the data in the order rotated by the context, if there is one.
 #[macro_export]
 macro_rules! impl_complex_encoder {
     ($t:ident, $c:ident) => {
@@ -14,6 +76,14 @@ macro_rules! impl_complex_encoder {
                 context: &mut $c,
                 data: &mut dyn Iterator<Item = crate::binary::Bit>,
             ) -> Result<crate::encoder::EncoderResult, Box<dyn std::error::Error>> {
+                if let Some(order) = crate::context::Context::rotated_order(context) {
+                    return crate::method::complex::partial_encode_in_order(
+                        &self.methods,
+                        order,
+                        context,
+                        data,
+                    );
+                }
                 let mut is_data_still_available = crate::encoder::EncoderResult::Success;
                 for encoder in &self.methods {
                     if let crate::encoder::EncoderResult::NoDataLeft =
@@ -41,6 +111,7 @@ macro_rules! impl_complex_encoder {
 /// complex methods. It assumes that method is only composed by other methods.
 ///
 /// Requires the method to have `methods` field being vector containing type [Decoder](crate::decoder::Decoder).
+/// The data is put together in the order rotated by the context, if there is one.
 #[macro_export]
 macro_rules! impl_complex_decoder {
     ($t:ident, $c:ident) => {
@@ -49,6 +120,13 @@ macro_rules! impl_complex_decoder {
                 &self,
                 context: &$c,
             ) -> Result<Vec<crate::binary::Bit>, crate::context::ContextError> {
+                if let Some(order) = crate::context::Context::rotated_order(context) {
+                    return crate::method::complex::partial_decode_in_order(
+                        &self.methods,
+                        order,
+                        context,
+                    );
+                }
                 let mut secret_data = Vec::default();
                 for decoder in &self.methods {
                     let mut result = decoder.partial_decode(context)?;
@@ -106,6 +106,11 @@ impl Method<PivotByLineContext, PivotByRawLineContext> for ELUVMethod {
 mod test {
     use std::error::Error;
 
+    use ptero_common::{
+        frame::{frame_payload, FrameOptions},
+        rotation::VariantRotation,
+    };
+
     use crate::{
         binary::BitIterator,
         cli::encoder::EncodeSubCommand,
@@ -160,12 +165,36 @@ mod test {
 
         let method = ELUVMethod::default();
         let mut context = PivotByRawLineContext::new(&stego_text, pivot);
-        let secret_data = method.decode(&mut context, None)?;
+        let secret_data = method.decode_raw(&mut context, None)?;
 
         assert_eq!(&secret_data, &[0b10_00000_0, 0b1_00001_11, 0b10101_000]);
         Ok(())
     }
 
+    #[test]
+    fn decodes_data_encoded_in_rotated_order() -> Result<(), Box<dyn Error>> {
+        let cover_input = "a b c ".repeat(24);
+        let data_input = frame_payload(b"abc", &FrameOptions::default());
+        let pivot: usize = 3;
+        let variant_rotation = VariantRotation::new(b"bamboo");
+
+        let mut data_iterator = BitIterator::new(&data_input);
+        let method = ELUVMethod::default();
+        let mut context = PivotByLineContext::new(&cover_input, pivot);
+        context.rotate_variants_with(variant_rotation.clone());
+        let stego_text = method.encode(&mut context, &mut data_iterator, None)?;
+
+        let mut context = PivotByRawLineContext::new(&stego_text, pivot);
+        context.rotate_variants_with(variant_rotation);
+        let secret_data = method.decode(&mut context, None)?;
+        let mut context = PivotByRawLineContext::new(&stego_text, pivot);
+        let unrotated_data = method.decode(&mut context, None).ok();
+
+        assert_eq!(&secret_data, b"abc");
+        assert_ne!(unrotated_data, Some(secret_data));
+        Ok(())
+    }
+
     #[test]
     fn decodes_zeroes_if_no_data_encoded() -> Result<(), Box<dyn Error>> {
         let stego_text = "a\n".repeat(5);
@@ -173,7 +202,7 @@ mod test {
 
         let method = ELUVMethod::default();
         let mut context = PivotByRawLineContext::new(&stego_text, pivot);
-        let secret_data = method.decode(&mut context, None)?;
+        let secret_data = method.decode_raw(&mut context, None)?;
 
         assert_eq!(&secret_data, &[0, 0, 0, 0, 0]);
         Ok(())
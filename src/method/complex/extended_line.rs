@@ -96,6 +96,13 @@ impl Method<PivotByLineContext, PivotByRawLineContext> for ExtendedLineMethod {
 mod test {
     use std::error::Error;
 
+    use ptero_common::{
+        config::keyed_rng,
+        frame::{frame_payload, FrameOptions},
+        rotation::VariantRotation,
+        selection::{LineSelector, Spread, SpreadMode},
+    };
+
     use crate::{
         binary::BitIterator,
         context::{PivotByLineContext, PivotByRawLineContext},
@@ -136,6 +143,24 @@ mod test {
         Ok(())
     }
 
+    #[test]
+    fn encodes_the_same_text_with_keyed_rng() -> Result<(), Box<dyn Error>> {
+        let cover_input = "a bb ccc dddd eeeee ".repeat(10);
+        let data_input: Vec<u8> = vec![0b11111111, 0b10101010];
+        let pivot: usize = 16;
+        let method = ExtendedLineMethod::default();
+
+        let encode_with_key = |key: &str| -> Result<String, Box<dyn Error>> {
+            let mut data_iterator = BitIterator::new(&data_input);
+            let mut context = PivotByLineContext::new(&cover_input, pivot);
+            context.randomize_with(keyed_rng(key, ""));
+            method.encode(&mut context, &mut data_iterator, None)
+        };
+
+        assert_eq!(encode_with_key("bamboo")?, encode_with_key("bamboo")?);
+        Ok(())
+    }
+
     #[test]
     fn decodes_binary_data() -> Result<(), Box<dyn Error>> {
         let stego_text = "a  bc\na bcd\na  b d \n";
@@ -143,12 +168,116 @@ mod test {
 
         let method = ExtendedLineMethod::default();
         let mut context = PivotByRawLineContext::new(&stego_text, pivot);
-        let secret_data = method.decode(&mut context, None)?;
+        let secret_data = method.decode_raw(&mut context, None)?;
 
         assert_eq!(&secret_data, &[0b100_010_11, 0b100_000_00]);
         Ok(())
     }
 
+    #[test]
+    fn decodes_exactly_the_framed_data() -> Result<(), Box<dyn Error>> {
+        let cover_input = "a b c ".repeat(12);
+        let data_input = frame_payload(b"ab", &FrameOptions::default());
+        let pivot: usize = 3;
+
+        let mut data_iterator = BitIterator::new(&data_input);
+        let method = ExtendedLineMethod::default();
+        let mut context = PivotByLineContext::new(&cover_input, pivot);
+        let stego_text = method.encode(&mut context, &mut data_iterator, None)?;
+
+        let mut context = PivotByRawLineContext::new(&stego_text, pivot);
+        let secret_data = method.decode(&mut context, None)?;
+
+        assert_eq!(&secret_data, b"ab");
+        Ok(())
+    }
+
+    #[test]
+    fn decodes_data_encoded_in_rotated_order() -> Result<(), Box<dyn Error>> {
+        let cover_input = "a b c ".repeat(24);
+        let data_input = frame_payload(b"ab", &FrameOptions::default());
+        let pivot: usize = 3;
+        let variant_rotation = VariantRotation::new(b"bamboo");
+
+        let mut data_iterator = BitIterator::new(&data_input);
+        let method = ExtendedLineMethod::default();
+        let mut context = PivotByLineContext::new(&cover_input, pivot);
+        context.rotate_variants_with(variant_rotation.clone());
+        let stego_text = method.encode(&mut context, &mut data_iterator, None)?;
+
+        let mut context = PivotByRawLineContext::new(&stego_text, pivot);
+        context.rotate_variants_with(variant_rotation);
+        let secret_data = method.decode(&mut context, None)?;
+
+        assert_eq!(&secret_data, b"ab");
+        Ok(())
+    }
+
+    #[test]
+    fn decodes_data_encoded_only_in_selected_lines() -> Result<(), Box<dyn Error>> {
+        let cover_input = "a b c ".repeat(24);
+        let data_input = frame_payload(b"ab", &FrameOptions::default());
+        let pivot: usize = 3;
+        let line_selector = LineSelector::new(b"bamboo");
+
+        let mut data_iterator = BitIterator::new(&data_input);
+        let method = ExtendedLineMethod::default();
+        let mut context = PivotByLineContext::new(&cover_input, pivot);
+        context.select_lines_with(line_selector.clone());
+        let stego_text = method.encode(&mut context, &mut data_iterator, None)?;
+
+        // Last line of the cover keeps the trailing delimiter, it's skipped
+        let decoy_lines = stego_text
+            .lines()
+            .take(24)
+            .zip(line_selector.clone())
+            .filter(|(_, is_selected)| !is_selected)
+            .map(|(line, _)| line)
+            .collect::<Vec<&str>>();
+        assert!(!decoy_lines.is_empty());
+        assert!(decoy_lines
+            .iter()
+            .all(|line| ["a b", "c a", "b c"].contains(line)));
+
+        let mut context = PivotByRawLineContext::new(&stego_text, pivot);
+        context.select_lines_with(line_selector);
+        assert_eq!(&method.decode(&mut context, None)?, b"ab");
+
+        let mut context = PivotByRawLineContext::new(&stego_text, pivot);
+        assert_ne!(method.decode(&mut context, None).ok(), Some(b"ab".to_vec()));
+        Ok(())
+    }
+
+    #[test]
+    fn decodes_data_spread_through_the_cover() -> Result<(), Box<dyn Error>> {
+        let cover_input = "a b c ".repeat(24);
+        let data_input = frame_payload(b"a", &FrameOptions::default());
+        let pivot: usize = 3;
+        let spread = Spread {
+            mode: SpreadMode::Uniform,
+            stride: 3,
+        };
+
+        let mut data_iterator = BitIterator::new(&data_input);
+        let method = ExtendedLineMethod::default();
+        let mut context = PivotByLineContext::new(&cover_input, pivot);
+        context.select_lines_with(LineSelector::spread(spread));
+        let stego_text = method.encode(&mut context, &mut data_iterator, None)?;
+
+        // Only every third line can carry the data
+        assert!(stego_text
+            .lines()
+            .take(24)
+            .enumerate()
+            .filter(|(index, _)| index % 3 != 0)
+            .all(|(_, line)| ["a b", "c a", "b c"].contains(&line)));
+
+        let mut context = PivotByRawLineContext::new(&stego_text, pivot);
+        context.select_lines_with(LineSelector::spread(spread));
+        assert_eq!(&method.decode(&mut context, None)?, b"a");
+        Ok(())
+    }
+
     #[test]
     fn decodes_zeroes_if_no_data_encoded() -> Result<(), Box<dyn Error>> {
         let stego_text = "a\n".repeat(5);
@@ -156,7 +285,7 @@ mod test {
 
         let method = ExtendedLineMethod::default();
         let mut context = PivotByRawLineContext::new(&stego_text, pivot);
-        let secret_data = method.decode(&mut context, None)?;
+        let secret_data = method.decode_raw(&mut context, None)?;
 
         assert_eq!(&secret_data, &[0, 0]);
         Ok(())
@@ -7,7 +7,6 @@ use std::error::Error;
 use crate::{binary::Bit, context::{Context, ContextError}, decoder::Decoder, encoder::{Capacity, Encoder, EncoderResult}};
 
 use log::{trace};
-use rand::{thread_rng, Rng};
 
 /// Character used as the random whitespace in the method.
 pub const ASCII_WHITESPACE: char = ' ';
@@ -50,9 +49,9 @@ where
     ) -> Result<EncoderResult, Box<dyn Error>> {
         Ok(match data.next() {
             Some(Bit(1)) => {
-                let mut rng = thread_rng();
+                let text_length = context.get_current_text()?.len();
+                let position_determinant = context.gen_below(text_length);
                 let text = context.get_current_text_mut()?;
-                let position_determinant = rng.gen_range(0, &text.len());
                 let mut position = text.find(' ').unwrap_or_else(|| text.len());
                 for (index, character) in text.char_indices() {
                     if index > position_determinant {
@@ -34,8 +34,8 @@ use super::Method;
 ///
 /// Accepts any [Context](crate::context::Context).
 #[derive(Debug, PartialEq)]
-pub struct TrailingUnicodeMethod {
-    character_set: CharacterSetType,
+pub struct TrailingUnicodeMethod<T = CharacterSetType> {
+    character_set: T,
 }
 
 impl Default for TrailingUnicodeMethod {
@@ -44,24 +44,33 @@ impl Default for TrailingUnicodeMethod {
     }
 }
 
-impl TrailingUnicodeMethod {
-    pub fn new(unicode_set: CharacterSetType) -> Self {
+impl<T> TrailingUnicodeMethod<T>
+where
+    T: GetCharacterSet,
+{
+    /// Creates the method using given character set, e.g. one of [CharacterSetType]
+    /// or a [KeyedCharacterSet](self::character_sets::KeyedCharacterSet) shuffling it.
+    pub fn new(unicode_set: T) -> Self {
         TrailingUnicodeMethod {
             character_set: unicode_set,
         }
     }
 }
 
-impl Capacity for TrailingUnicodeMethod {
+impl<T> Capacity for TrailingUnicodeMethod<T>
+where
+    T: GetCharacterSet,
+{
     fn bitrate(&self) -> usize {
         let amount_of_bits = std::mem::size_of::<usize>() * 8;
         amount_of_bits - self.character_set.size().leading_zeros() as usize
     }
 }
 
-impl<E> Encoder<E> for TrailingUnicodeMethod
+impl<E, T> Encoder<E> for TrailingUnicodeMethod<T>
 where
     E: Context,
+    T: GetCharacterSet,
 {
     fn partial_encode(
         &self,
@@ -92,9 +101,10 @@ where
     }
 }
 
-impl<D> Decoder<D> for TrailingUnicodeMethod
+impl<D, T> Decoder<D> for TrailingUnicodeMethod<T>
 where
     D: Context,
+    T: GetCharacterSet,
 {
     fn partial_decode(&self, context: &D) -> Result<Vec<Bit>, ContextError> {
         if let Some(character) = context.get_current_text()?.chars().last() {
@@ -116,12 +126,68 @@ where
     }
 }
 
-impl<E, D> Method<E, D> for TrailingUnicodeMethod
+impl<E, D, T> Method<E, D> for TrailingUnicodeMethod<T>
 where
     E: Context,
     D: Context,
+    T: GetCharacterSet,
 {
     fn method_name(&self) -> String {
         "TrailingUnicodeMethod".to_string()
     }
 }
+
+#[allow(unused_imports)]
+mod test {
+    use std::error::Error;
+
+    use crate::{
+        binary::Bit,
+        context::{Context, PivotByRawLineContext},
+        decoder::Decoder,
+        encoder::Encoder,
+    };
+
+    use super::{
+        character_sets::{CharacterSetType, GetCharacterSet, KeyedCharacterSet},
+        TrailingUnicodeMethod,
+    };
+
+    #[test]
+    fn compares_methods_by_character_set() {
+        assert_eq!(
+            TrailingUnicodeMethod::default(),
+            TrailingUnicodeMethod::new(CharacterSetType::FullUnicodeSet)
+        );
+        assert_ne!(
+            TrailingUnicodeMethod::default(),
+            TrailingUnicodeMethod::new(CharacterSetType::TwoBitUnicodeSet)
+        );
+    }
+
+    #[test]
+    fn encodes_and_decodes_with_keyed_character_set() -> Result<(), Box<dyn Error>> {
+        let character_set = KeyedCharacterSet::new(
+            CharacterSetType::FourBitUnicodeSet.get_set(),
+            "bamboo",
+            "forest",
+        );
+        let method = TrailingUnicodeMethod::new(character_set.clone());
+        let data = vec![Bit(1), Bit(0), Bit(1), Bit(1)];
+
+        let mut context = PivotByRawLineContext::new("a b c", 5);
+        context.load_text()?;
+        method.partial_encode(&mut context, &mut data.clone().into_iter())?;
+
+        assert_eq!(
+            context.get_current_text()?.chars().last().as_ref(),
+            character_set.get_character(0b1011)
+        );
+        assert_ne!(
+            character_set.get_set(),
+            CharacterSetType::FourBitUnicodeSet.get_set()
+        );
+        assert_eq!(method.partial_decode(&context)?, data);
+        Ok(())
+    }
+}
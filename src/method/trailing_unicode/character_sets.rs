@@ -1,3 +1,5 @@
+pub use ptero_common::config::KeyedCharacterSet;
+
 /// This trait is used for reading unicode set data.
 ///
 /// New sets should implement `get_set` which provides the array with
@@ -161,4 +163,23 @@ impl GetCharacterSet for CharacterSetType {
             CharacterSetType::TwitterUnicodeSet => &TWITTER_UNICODE_CHARACTER_SET,
         }
     }
-}
\ No newline at end of file
+}
+
+/// Shuffles the characters of a set with the shared key.
+///
+/// # Examples
+/// ```
+/// use ptero::method::trailing_unicode::character_sets::{
+///     CharacterSetType, GetCharacterSet, KeyedCharacterSet,
+/// };
+///
+/// let set = KeyedCharacterSet::new(CharacterSetType::FullUnicodeSet.get_set(), "bamboo", "forest");
+///
+/// assert_eq!(set.size(), CharacterSetType::FullUnicodeSet.size());
+/// assert_eq!(set.character_to_bits(set.get_character(5).unwrap()), 5);
+/// ```
+impl GetCharacterSet for KeyedCharacterSet {
+    fn get_set(&self) -> &[char] {
+        self.characters()
+    }
+}
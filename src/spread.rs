@@ -0,0 +1,63 @@
+//! # Description
+//!
+//! Spacing of the lines carrying the data through the whole stego text.
+//! The [Spread] is recorded in the [SPREAD_DESCRIPTOR_LENGTH] lines following the header,
+//! one bit per line like the header itself, so the decoder can skip the untouched lines.
+//!
+//! # Examples
+//! ```
+//! use ptero::context::{PivotByLineContext, PivotByRawLineContext};
+//! use ptero::spread::{read_spread, write_spread};
+//! use ptero_common::selection::{Spread, SpreadMode, SPREAD_DESCRIPTOR_LENGTH};
+//!
+//! let cover_text = "a b\n".repeat(SPREAD_DESCRIPTOR_LENGTH);
+//! let spread = Spread { mode: SpreadMode::Random, stride: 5 };
+//! let mut context = PivotByLineContext::new(&cover_text, 4);
+//! let stego_text = write_spread(&spread, &mut context).unwrap();
+//!
+//! let mut context = PivotByRawLineContext::new(&stego_text, 0);
+//! assert_eq!(read_spread(&mut context).unwrap(), spread);
+//! ```
+
+use std::error::Error;
+
+use log::debug;
+use ptero_common::selection::{Spread, SPREAD_DESCRIPTOR_LENGTH};
+
+use crate::{
+    context::Context,
+    header::{push_number, read_bits, read_number, write_bits},
+};
+
+/// Encodes the spread descriptor into the next [SPREAD_DESCRIPTOR_LENGTH] lines loaded from the context.
+///
+/// # Returns
+/// Result which is either the stego text of the descriptor or error if the cover text is too short.
+pub fn write_spread<E>(spread: &Spread, context: &mut E) -> Result<String, Box<dyn Error>>
+where
+    E: Context,
+{
+    debug!("Encoding the spread {:?}", spread);
+    let mut bits = Vec::with_capacity(SPREAD_DESCRIPTOR_LENGTH);
+    push_number(
+        &mut bits,
+        spread.to_byte() as usize,
+        SPREAD_DESCRIPTOR_LENGTH,
+    );
+    write_bits(context, &bits)
+}
+
+/// Decodes the spread descriptor from the next [SPREAD_DESCRIPTOR_LENGTH] lines loaded from the context.
+///
+/// # Returns
+/// Result which is either the spread or error if the text doesn't contain a valid descriptor.
+pub fn read_spread<D>(context: &mut D) -> Result<Spread, Box<dyn Error>>
+where
+    D: Context,
+{
+    let bits = read_bits(context, SPREAD_DESCRIPTOR_LENGTH)
+        .ok_or("Stego text is too short to contain the spread of the lines.")?;
+    let spread = Spread::from_byte(read_number(&bits) as u8)?;
+    debug!("Decoded the spread {:?}", spread);
+    Ok(spread)
+}
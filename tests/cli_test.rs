@@ -1,9 +1,12 @@
 use assert_cmd::Command;
 use log::{debug, info};
 use std::{error::Error, fs, panic, path::PathBuf};
-use utils::{global_setup, run_decode_command, run_encode_command};
+use utils::{
+    decode_command, encode_command, global_setup, header_decode_command, run_decode_command,
+    run_encode_command, run_header_decode_command,
+};
 
-use crate::utils::TemporaryFile;
+use crate::utils::{TemporaryDirectory, TemporaryFile};
 
 mod utils;
 
@@ -15,7 +18,7 @@ fn check_if_does_not_fail_when_encoding_over_ascii_cover(
     debug!("Checking for method: {}", method);
 
     debug!("Encoding to JSON format");
-    let json_struct = run_encode_command(cover, data, 50, None, method)?;
+    let json_struct = run_encode_command(cover, data, 50, None, method, &[])?;
     assert_eq!(json_struct["type"].as_str(), Some("success"));
     Ok(())
 }
@@ -24,11 +27,12 @@ fn check_if_correctly_decodes_data_from_utf8_stego_text(
     data: &PathBuf,
     stego_text: &PathBuf,
     method: &str,
+    extra_args: &[&str],
 ) -> Result<(), Box<dyn Error>> {
     debug!("Checking for method: {}", method);
 
     debug!("Decoding to JSON format");
-    let json_struct = run_decode_command(&stego_text, 50, None, method)?;
+    let json_struct = run_decode_command(&stego_text, 50, None, method, extra_args)?;
     assert_eq!(json_struct["type"].as_str(), Some("success"));
 
     debug!("Reading the secret data file");
@@ -54,10 +58,10 @@ fn check_if_encodes_and_decodes_the_same_data(
     let encoding_output_path = temp_file.path();
 
     info!("Encoding and saving to file");
-    run_encode_command(&cover, &data, 50, Some(&encoding_output_path), method)?;
+    run_encode_command(&cover, &data, 50, Some(&encoding_output_path), method, &[])?;
 
     debug!("Decoding from file {:?} to JSON", &encoding_output_path);
-    let json_struct = run_decode_command(&encoding_output_path, 50, None, method)?;
+    let json_struct = run_decode_command(&encoding_output_path, 50, None, method, &[])?;
     assert_eq!(json_struct["type"].as_str(), Some("success"));
     debug!("Decoded data: {:?}", &json_struct["result"]);
 
@@ -109,8 +113,38 @@ fn correctly_decodes_data_from_utf8_stego_text_when_eline_method_is_used(
     let stego_path_eline = res_dir.join("stego/stego_utf8_eline.txt");
     let stego_path_eluv = res_dir.join("stego/stego_utf8_eluv.txt");
 
-    check_if_correctly_decodes_data_from_utf8_stego_text(&data_path, &stego_path_eline, "eline")?;
-    check_if_correctly_decodes_data_from_utf8_stego_text(&data_path, &stego_path_eluv, "eluv")
+    // Encoded before the framing, only the method and the pivot are known
+    for extra_args in &[&["--no-framing"][..], &["--no-framing", "--no-header"][..]] {
+        check_if_correctly_decodes_data_from_utf8_stego_text(
+            &data_path,
+            &stego_path_eline,
+            "eline",
+            extra_args,
+        )?;
+        check_if_correctly_decodes_data_from_utf8_stego_text(
+            &data_path,
+            &stego_path_eluv,
+            "eluv",
+            extra_args,
+        )?;
+    }
+    Ok(())
+}
+
+#[test]
+fn decodes_exactly_the_framed_data_from_utf8_stego_text() -> Result<(), Box<dyn Error>> {
+    global_setup();
+    let mut res_dir = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    res_dir.push("resources");
+
+    let data = fs::read_to_string(res_dir.join("data.txt"))?;
+    for method in &["eline", "eluv"] {
+        debug!("Checking for method: {}", method);
+        let stego_path = res_dir.join(format!("stego/stego_utf8_{}_framed.txt", method));
+        let json_struct = run_decode_command(&stego_path, 50, None, method, &[])?;
+        assert_eq!(json_struct["result"].as_str(), Some(data.as_str()));
+    }
+    Ok(())
 }
 
 #[test]
@@ -201,3 +235,697 @@ fn eluv_encode_command_fail_when_wrong_set_param_provided() -> Result<(), Box<dy
         .failure();
     Ok(())
 }
+
+#[test]
+fn decode_fails_when_stego_text_with_checksum_is_corrupted() -> Result<(), Box<dyn Error>> {
+    global_setup();
+    let mut res_dir = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    res_dir.push("resources");
+
+    let data_path = res_dir.join("data.txt");
+    let cover_path = res_dir.join("cover/cover_ascii.txt");
+    let temp_file = TemporaryFile("checksum_encode_out");
+    let encoding_output_path = temp_file.path();
+
+    run_encode_command(
+        &cover_path,
+        &data_path,
+        50,
+        Some(&encoding_output_path),
+        "eline",
+        &["--checksum"],
+    )?;
+
+    info!("Flipping the trailing whitespace bit inside the payload");
+    let stego_text = fs::read_to_string(&encoding_output_path)?;
+    let corrupted_text = stego_text
+        .lines()
+        .enumerate()
+        .map(|(index, line)| match (index, line.strip_suffix(' ')) {
+            (9, Some(stripped)) => stripped.to_string(),
+            (9, None) => format!("{} ", line),
+            _ => line.to_string(),
+        })
+        .collect::<Vec<String>>()
+        .join("\n");
+    fs::write(&encoding_output_path, corrupted_text)?;
+
+    decode_command(&encoding_output_path, 50, None, "eline", &[])
+        .assert()
+        .failure();
+    Ok(())
+}
+
+#[test]
+fn decodes_encrypted_data_only_with_the_same_passphrase() -> Result<(), Box<dyn Error>> {
+    global_setup();
+    let mut res_dir = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    res_dir.push("resources");
+
+    let data_path = res_dir.join("data.txt");
+    let cover_path = res_dir.join("cover/cover_ascii.txt");
+    let temp_file = TemporaryFile("passphrase_encode_out");
+    let encoding_output_path = temp_file.path();
+    let passphrase_file = TemporaryFile("passphrase_file");
+    fs::write(passphrase_file.path(), "bamboo\n")?;
+
+    run_encode_command(
+        &cover_path,
+        &data_path,
+        25,
+        Some(&encoding_output_path),
+        "eluv",
+        &["--passphrase-file", passphrase_file.0],
+    )?;
+
+    info!("Decoding with the passphrase given directly");
+    let json_struct = run_decode_command(
+        &encoding_output_path,
+        25,
+        None,
+        "eluv",
+        &["--passphrase", "bamboo"],
+    )?;
+    let data = fs::read_to_string(&data_path)?;
+    assert_eq!(json_struct["result"].as_str(), Some(data.as_str()));
+
+    info!("Decoding with the wrong passphrase and without any");
+    for passphrase_args in [vec!["--passphrase", "eucalyptus"], vec![]].iter() {
+        decode_command(&encoding_output_path, 25, None, "eluv", passphrase_args)
+            .assert()
+            .failure();
+    }
+    Ok(())
+}
+
+#[test]
+fn encodes_and_decodes_compressed_data() -> Result<(), Box<dyn Error>> {
+    global_setup();
+    let mut res_dir = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    res_dir.push("resources");
+
+    let cover_path = res_dir.join("cover/cover_ascii.txt");
+    let data_file = TemporaryFile("compressed_data");
+    let data = "Meet me at the old bridge at noon";
+    fs::write(data_file.path(), data)?;
+    let temp_file = TemporaryFile("compressed_encode_out");
+    let encoding_output_path = temp_file.path();
+
+    run_encode_command(
+        &cover_path,
+        &data_file.path(),
+        50,
+        Some(&encoding_output_path),
+        "eluv",
+        &["--compress"],
+    )?;
+
+    let json_struct = run_decode_command(&encoding_output_path, 50, None, "eluv", &[])?;
+    assert_eq!(json_struct["result"].as_str(), Some(data));
+    Ok(())
+}
+
+#[test]
+fn decodes_data_from_stego_text_with_mangled_line_using_ecc() -> Result<(), Box<dyn Error>> {
+    global_setup();
+    let mut res_dir = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    res_dir.push("resources");
+
+    let data_path = res_dir.join("data.txt");
+    let cover_path = res_dir.join("cover/cover_ascii.txt");
+    let temp_file = TemporaryFile("ecc_encode_out");
+    let encoding_output_path = temp_file.path();
+
+    run_encode_command(
+        &cover_path,
+        &data_path,
+        50,
+        Some(&encoding_output_path),
+        "eluv",
+        &["--ecc", "2"],
+    )?;
+
+    info!("Trimming the line like a social media would do");
+    let stego_text = fs::read_to_string(&encoding_output_path)?;
+    let mangled_text = stego_text
+        .lines()
+        .enumerate()
+        .map(|(index, line)| match index {
+            // The 4th line after the 24 lines of the header
+            27 => line.split_whitespace().collect::<Vec<&str>>().join(" "),
+            _ => line.to_string(),
+        })
+        .collect::<Vec<String>>()
+        .join("\n");
+    assert_ne!(mangled_text, stego_text);
+    fs::write(&encoding_output_path, mangled_text)?;
+
+    let json_struct = run_decode_command(&encoding_output_path, 50, None, "eluv", &["--ecc", "2"])?;
+    let data = fs::read_to_string(&data_path)?;
+    assert_eq!(json_struct["result"].as_str(), Some(data.as_str()));
+    Ok(())
+}
+
+#[test]
+fn encode_fails_when_ecc_level_is_not_supported() -> Result<(), Box<dyn Error>> {
+    global_setup();
+    let mut res_dir = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    res_dir.push("resources");
+
+    encode_command(
+        &res_dir.join("cover/cover_ascii.txt"),
+        &res_dir.join("data.txt"),
+        50,
+        None,
+        "eline",
+        &["--ecc", "9"],
+    )
+    .assert()
+    .failure();
+    Ok(())
+}
+
+#[test]
+fn decodes_data_using_only_the_stego_text_header() -> Result<(), Box<dyn Error>> {
+    global_setup();
+    let mut res_dir = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    res_dir.push("resources");
+
+    let data_path = res_dir.join("data.txt");
+    let data = fs::read_to_string(&data_path)?;
+
+    for method in &["eline", "eluv"] {
+        debug!("Checking for method: {}", method);
+        let stego_path = res_dir.join(format!("stego/stego_utf8_{}_framed.txt", method));
+        let json_struct = run_header_decode_command(&stego_path, None, &[])?;
+        assert_eq!(json_struct["result"].as_str(), Some(data.as_str()));
+    }
+    Ok(())
+}
+
+#[test]
+fn decode_fails_when_parameters_conflict_with_the_header() -> Result<(), Box<dyn Error>> {
+    global_setup();
+    let mut res_dir = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    res_dir.push("resources");
+
+    let stego_path = res_dir.join("stego/stego_utf8_eluv_framed.txt");
+
+    header_decode_command(&stego_path, None, &["--eline"])
+        .assert()
+        .failure();
+    Ok(())
+}
+
+#[test]
+fn decodes_data_encoded_without_header_only_with_method_and_pivot() -> Result<(), Box<dyn Error>> {
+    global_setup();
+    let mut res_dir = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    res_dir.push("resources");
+
+    let data_path = res_dir.join("data.txt");
+    let cover_path = res_dir.join("cover/cover_ascii.txt");
+    let temp_file = TemporaryFile("no_header_encode_out");
+    let encoding_output_path = temp_file.path();
+
+    run_encode_command(
+        &cover_path,
+        &data_path,
+        50,
+        Some(&encoding_output_path),
+        "eline",
+        &["--no-header"],
+    )?;
+
+    info!("Decoding without any parameters");
+    header_decode_command(&encoding_output_path, None, &[])
+        .assert()
+        .failure();
+
+    let json_struct = run_decode_command(&encoding_output_path, 50, None, "eline", &[])?;
+    let data = fs::read_to_string(&data_path)?;
+    assert_eq!(json_struct["result"].as_str(), Some(data.as_str()));
+    Ok(())
+}
+
+#[test]
+fn auto_decode_ranks_the_encoding_parameters_first() -> Result<(), Box<dyn Error>> {
+    global_setup();
+    let mut res_dir = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    res_dir.push("resources");
+
+    let data_path = res_dir.join("data.txt");
+    let cover_path = res_dir.join("cover/cover_ascii.txt");
+    let temp_file = TemporaryFile("auto_encode_out");
+    let encoding_output_path = temp_file.path();
+
+    run_encode_command(
+        &cover_path,
+        &data_path,
+        40,
+        Some(&encoding_output_path),
+        "eluv",
+        &[
+            "--set",
+            "two-bit",
+            "--variant",
+            "2",
+            "--checksum",
+            "--no-header",
+        ],
+    )?;
+
+    let json_struct = run_header_decode_command(&encoding_output_path, None, &["--auto"])?;
+    let report = json_struct["result"].as_str().unwrap_or_default();
+    debug!("Auto decode report:\n{}", report);
+
+    let best_match = report.lines().next().unwrap_or_default();
+    assert!(best_match.contains("--eluv --set two-bit --variant 2 --pivot 40"));
+    assert!(best_match.contains("valid frame with checksum"));
+    assert!(best_match.contains(&format!("{:?}", fs::read_to_string(&data_path)?)));
+    Ok(())
+}
+
+#[test]
+fn auto_decode_suggests_parameters_decoding_stego_text_without_frame() -> Result<(), Box<dyn Error>>
+{
+    global_setup();
+    let mut res_dir = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    res_dir.push("resources");
+
+    let json_struct = run_header_decode_command(
+        &res_dir.join("stego/stego_utf8_eline.txt"),
+        None,
+        &["--auto"],
+    )?;
+    let report = json_struct["result"].as_str().unwrap_or_default();
+    debug!("Auto decode report:\n{}", report);
+
+    let best_match = report.lines().next().unwrap_or_default();
+    assert!(best_match.contains("--eline --variant 1 --pivot 50 --no-framing"));
+    assert!(best_match.contains(&format!(
+        "{:?}",
+        fs::read_to_string(res_dir.join("data.txt"))?
+    )));
+    Ok(())
+}
+
+#[test]
+fn auto_decode_cannot_be_used_with_method_parameters() -> Result<(), Box<dyn Error>> {
+    global_setup();
+    let mut res_dir = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    res_dir.push("resources");
+
+    header_decode_command(
+        &res_dir.join("stego/stego_utf8_eline.txt"),
+        None,
+        &["--auto", "--pivot", "50"],
+    )
+    .assert()
+    .failure();
+    Ok(())
+}
+
+#[test]
+fn decodes_data_split_across_multiple_cover_texts_given_in_any_order() -> Result<(), Box<dyn Error>>
+{
+    global_setup();
+    let mut res_dir = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    res_dir.push("resources");
+
+    let data_file = TemporaryFile("multipart_data");
+    let data_path = data_file.path();
+    fs::write(&data_path, "Meet me at the old bridge")?;
+    let parts_dir = TemporaryDirectory("multipart_parts");
+    let parts_path = parts_dir.path();
+
+    let cover_path = res_dir.join("cover/cover_ascii.txt");
+    let second_cover_path = res_dir.join("cover/cover_utf8.txt");
+
+    info!("Encoding in a single cover text");
+    encode_command(&cover_path, &data_path, 40, None, "eline", &[])
+        .assert()
+        .failure();
+
+    info!("Encoding in parts");
+    run_encode_command(
+        &cover_path,
+        &data_path,
+        40,
+        None,
+        "eline",
+        &[
+            "-c",
+            second_cover_path.to_str().unwrap(),
+            "--parts-dir",
+            parts_dir.0,
+        ],
+    )?;
+
+    let first_part_path = parts_path.join("part_1_of_2.txt");
+    let json_struct = run_header_decode_command(
+        &parts_path.join("part_2_of_2.txt"),
+        None,
+        &["-t", first_part_path.to_str().unwrap()],
+    )?;
+    assert_eq!(
+        json_struct["result"].as_str(),
+        Some("Meet me at the old bridge")
+    );
+
+    info!("Decoding without the first part");
+    header_decode_command(&parts_path.join("part_2_of_2.txt"), None, &["--parts"])
+        .assert()
+        .failure();
+    Ok(())
+}
+
+#[test]
+fn encode_fails_when_multiple_cover_texts_are_used_without_parts_dir() -> Result<(), Box<dyn Error>>
+{
+    global_setup();
+    let mut res_dir = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    res_dir.push("resources");
+
+    let second_cover_path = res_dir.join("cover/cover_utf8.txt");
+    encode_command(
+        &res_dir.join("cover/cover_ascii.txt"),
+        &res_dir.join("data.txt"),
+        50,
+        None,
+        "eline",
+        &["-c", second_cover_path.to_str().unwrap()],
+    )
+    .assert()
+    .failure();
+    Ok(())
+}
+
+#[test]
+fn decodes_data_encoded_in_lines_selected_with_key() -> Result<(), Box<dyn Error>> {
+    global_setup();
+    let mut res_dir = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    res_dir.push("resources");
+
+    let data_path = res_dir.join("data.txt");
+    let temp_file = TemporaryFile("line_key_encode_out");
+    let encoding_output_path = temp_file.path();
+
+    run_encode_command(
+        &res_dir.join("cover/cover_ascii.txt"),
+        &data_path,
+        40,
+        Some(&encoding_output_path),
+        "eluv",
+        &["--checksum", "--line-key", "bamboo"],
+    )?;
+
+    let json_struct =
+        run_header_decode_command(&encoding_output_path, None, &["--line-key", "bamboo"])?;
+    let data = fs::read_to_string(&data_path)?;
+    assert_eq!(json_struct["result"].as_str(), Some(data.as_str()));
+
+    info!("Decoding with a different key");
+    let output = header_decode_command(&encoding_output_path, None, &["--line-key", "eucalyptus"])
+        .output()?;
+    let json_struct: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap_or_default();
+    assert_ne!(json_struct["result"].as_str(), Some(data.as_str()));
+    Ok(())
+}
+
+#[test]
+fn decodes_data_encoded_with_rotated_variants() -> Result<(), Box<dyn Error>> {
+    global_setup();
+    let mut res_dir = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    res_dir.push("resources");
+
+    let data_path = res_dir.join("data.txt");
+    let temp_file = TemporaryFile("variant_key_encode_out");
+    let encoding_output_path = temp_file.path();
+
+    run_encode_command(
+        &res_dir.join("cover/cover_ascii.txt"),
+        &data_path,
+        40,
+        Some(&encoding_output_path),
+        "eluv",
+        &[
+            "--checksum",
+            "--no-header",
+            "--variant",
+            "3",
+            "--variant-key",
+            "bamboo",
+        ],
+    )?;
+
+    let json_struct = run_decode_command(
+        &encoding_output_path,
+        40,
+        None,
+        "eluv",
+        &["--no-header", "--variant-key", "bamboo"],
+    )?;
+    let data = fs::read_to_string(&data_path)?;
+    assert_eq!(json_struct["result"].as_str(), Some(data.as_str()));
+
+    info!("Decoding with a different key");
+    let output = decode_command(
+        &encoding_output_path,
+        40,
+        None,
+        "eluv",
+        &["--no-header", "--variant-key", "eucalyptus"],
+    )
+    .output()?;
+    let json_struct: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap_or_default();
+    assert_ne!(json_struct["result"].as_str(), Some(data.as_str()));
+    Ok(())
+}
+
+#[test]
+fn decodes_data_spread_through_the_cover() -> Result<(), Box<dyn Error>> {
+    global_setup();
+    let mut res_dir = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    res_dir.push("resources");
+
+    let data_path = res_dir.join("data.txt");
+    let data = fs::read_to_string(&data_path)?;
+
+    for mode in ["uniform", "random"].iter() {
+        let file_name = format!("spread_{}_encode_out", mode);
+        let temp_file = TemporaryFile(&file_name);
+        let encoding_output_path = temp_file.path();
+
+        run_encode_command(
+            &res_dir.join("cover/cover_ascii.txt"),
+            &data_path,
+            40,
+            Some(&encoding_output_path),
+            "eline",
+            &["--checksum", "--spread", mode],
+        )?;
+
+        let json_struct = run_header_decode_command(&encoding_output_path, None, &["--spread"])?;
+        assert_eq!(json_struct["result"].as_str(), Some(data.as_str()));
+    }
+    Ok(())
+}
+
+#[test]
+fn encodes_the_same_stego_text_with_the_same_key() -> Result<(), Box<dyn Error>> {
+    global_setup();
+    let mut res_dir = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    res_dir.push("resources");
+
+    let data_path = res_dir.join("data.txt");
+    let encode_with_key = |file_name: &str, key: &str| -> Result<String, Box<dyn Error>> {
+        let temp_file = TemporaryFile(file_name);
+        let encoding_output_path = temp_file.path();
+
+        run_encode_command(
+            &res_dir.join("cover/cover_ascii.txt"),
+            &data_path,
+            40,
+            Some(&encoding_output_path),
+            "eluv",
+            &["--key", key],
+        )?;
+        Ok(fs::read_to_string(&encoding_output_path)?)
+    };
+
+    let stego_text = encode_with_key("key_encode_out", "bamboo")?;
+    assert_eq!(
+        encode_with_key("same_key_encode_out", "bamboo")?,
+        stego_text
+    );
+
+    info!("Decoding without the key");
+    let temp_file = TemporaryFile("key_decode_in");
+    fs::write(temp_file.path(), &stego_text)?;
+    let json_struct = run_header_decode_command(&temp_file.path(), None, &[])?;
+    let data = fs::read_to_string(&data_path)?;
+    assert_eq!(json_struct["result"].as_str(), Some(data.as_str()));
+    Ok(())
+}
+
+#[test]
+fn decodes_only_the_data_matching_the_key() -> Result<(), Box<dyn Error>> {
+    global_setup();
+    let mut res_dir = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    res_dir.push("resources");
+
+    let data_path = res_dir.join("data.txt");
+    // Both data take a half of the cover text and need room for the encryption
+    let cover_file = TemporaryFile("deniable_cover");
+    fs::write(
+        cover_file.path(),
+        fs::read_to_string(res_dir.join("cover/cover_ascii.txt"))?.repeat(4),
+    )?;
+    let decoy_file = TemporaryFile("deniable_decoy_data");
+    fs::write(decoy_file.path(), "Bamboo")?;
+    let temp_file = TemporaryFile("deniable_encode_out");
+    let encoding_output_path = temp_file.path();
+
+    run_encode_command(
+        &cover_file.path(),
+        &data_path,
+        40,
+        Some(&encoding_output_path),
+        "eluv",
+        &[
+            "--deniable-key",
+            "eucalyptus",
+            "--decoy-data",
+            decoy_file.0,
+            "--decoy-key",
+            "bamboo",
+        ],
+    )?;
+
+    let decode_with_key =
+        |key: &str| run_header_decode_command(&encoding_output_path, None, &["--key", key]);
+    let data = fs::read_to_string(&data_path)?;
+    assert_eq!(
+        decode_with_key("eucalyptus")?["result"].as_str(),
+        Some(data.as_str())
+    );
+    assert_eq!(
+        decode_with_key("bamboo")?["result"].as_str(),
+        Some("Bamboo")
+    );
+
+    header_decode_command(&encoding_output_path, None, &["--key", "panda"])
+        .assert()
+        .failure();
+    Ok(())
+}
+
+#[test]
+fn decodes_data_encoded_deniably_without_decoy() -> Result<(), Box<dyn Error>> {
+    global_setup();
+    let mut res_dir = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    res_dir.push("resources");
+
+    let data_path = res_dir.join("data.txt");
+    let cover_file = TemporaryFile("deniable_single_cover");
+    fs::write(
+        cover_file.path(),
+        fs::read_to_string(res_dir.join("cover/cover_ascii.txt"))?.repeat(4),
+    )?;
+    let temp_file = TemporaryFile("deniable_single_encode_out");
+    let encoding_output_path = temp_file.path();
+
+    run_encode_command(
+        &cover_file.path(),
+        &data_path,
+        40,
+        Some(&encoding_output_path),
+        "eluv",
+        &["--deniable-key", "eucalyptus"],
+    )?;
+
+    let json_struct =
+        run_header_decode_command(&encoding_output_path, None, &["--key", "eucalyptus"])?;
+    let data = fs::read_to_string(&data_path)?;
+    assert_eq!(json_struct["result"].as_str(), Some(data.as_str()));
+    Ok(())
+}
+
+#[test]
+fn decodes_data_encrypted_to_recipient_only_with_its_identity() -> Result<(), Box<dyn Error>> {
+    global_setup();
+    let mut res_dir = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    res_dir.push("resources");
+
+    let key_file = TemporaryFile("recipient_key_file");
+    Command::cargo_bin("ptero_cli")
+        .unwrap()
+        .arg("-o")
+        .arg(key_file.path())
+        .arg("keygen")
+        .assert()
+        .success();
+    let key_file_content = fs::read_to_string(key_file.path())?;
+    let recipient = key_file_content
+        .lines()
+        .find_map(|line| line.strip_prefix("# Public key: "))
+        .expect("Key file should contain the public key");
+
+    let data_path = res_dir.join("data.txt");
+    // Ephemeral public key and authentication tag need room in the cover text
+    let cover_file = TemporaryFile("recipient_cover");
+    fs::write(
+        cover_file.path(),
+        fs::read_to_string(res_dir.join("cover/cover_ascii.txt"))?.repeat(3),
+    )?;
+    let temp_file = TemporaryFile("recipient_encode_out");
+    let encoding_output_path = temp_file.path();
+
+    run_encode_command(
+        &cover_file.path(),
+        &data_path,
+        40,
+        Some(&encoding_output_path),
+        "eline",
+        &["--recipient", recipient],
+    )?;
+
+    let json_struct = run_decode_command(
+        &encoding_output_path,
+        40,
+        None,
+        "eline",
+        &["--identity", key_file.0],
+    )?;
+    let data = fs::read_to_string(&data_path)?;
+    assert_eq!(json_struct["result"].as_str(), Some(data.as_str()));
+
+    decode_command(&encoding_output_path, 40, None, "eline", &[])
+        .assert()
+        .failure();
+    Ok(())
+}
+
+#[cfg(unix)]
+#[test]
+fn creates_key_file_readable_only_by_owner() -> Result<(), Box<dyn Error>> {
+    use std::os::unix::fs::PermissionsExt;
+
+    global_setup();
+    let key_file = TemporaryFile("owner_only_key_file");
+    // Existing file doesn't keep its permissions either
+    fs::write(key_file.path(), "")?;
+    fs::set_permissions(key_file.path(), fs::Permissions::from_mode(0o644))?;
+    Command::cargo_bin("ptero_cli")
+        .unwrap()
+        .arg("-o")
+        .arg(key_file.path())
+        .arg("keygen")
+        .assert()
+        .success();
+
+    let mode = fs::metadata(key_file.path())?.permissions().mode();
+    assert_eq!(mode & 0o777, 0o600);
+    Ok(())
+}
@@ -26,6 +26,26 @@ impl<'a> TemporaryFile<'a> {
     }
 }
 
+pub struct TemporaryDirectory<'a>(pub &'a str);
+
+impl<'a> Drop for TemporaryDirectory<'a> {
+    fn drop(&mut self) {
+        let dir_path = PathBuf::from(self.0);
+
+        if dir_path.exists() {
+            fs::remove_dir_all(dir_path)
+                .map_err(|e| error!("Failed during teardown: {:?}", e))
+                .ok();
+        }
+    }
+}
+
+impl<'a> TemporaryDirectory<'a> {
+    pub fn path(&self) -> PathBuf {
+        PathBuf::from(self.0)
+    }
+}
+
 pub fn global_setup() {
     INIT.call_once(|| {
         fern::Dispatch::new()
@@ -45,21 +65,22 @@ pub fn global_setup() {
     });
 }
 
-pub fn run_encode_command(
+/// Prepares the encode command, `extra_args` are passed after the cover, data and pivot.
+pub fn encode_command(
     cover_path: &PathBuf,
     data_path: &PathBuf,
     pivot: usize,
     output_path: Option<&PathBuf>,
     method: &str,
-) -> Result<Value, Box<dyn Error>> {
+    extra_args: &[&str],
+) -> Command {
     let mut cmd = Command::cargo_bin("ptero_cli").unwrap();
     if let Some(path) = output_path {
         cmd.arg("-o").arg(path);
     } else {
         cmd.arg("--json");
     }
-    let assert = cmd
-        .arg("encode")
+    cmd.arg("encode")
         .arg(format!("--{}", method))
         .arg("-c")
         .arg(&cover_path)
@@ -67,8 +88,28 @@ pub fn run_encode_command(
         .arg(&data_path)
         .arg("--pivot")
         .arg(format!("{}", pivot))
-        .assert()
-        .success();
+        .args(extra_args);
+    cmd
+}
+
+pub fn run_encode_command(
+    cover_path: &PathBuf,
+    data_path: &PathBuf,
+    pivot: usize,
+    output_path: Option<&PathBuf>,
+    method: &str,
+    extra_args: &[&str],
+) -> Result<Value, Box<dyn Error>> {
+    let assert = encode_command(
+        cover_path,
+        data_path,
+        pivot,
+        output_path,
+        method,
+        extra_args,
+    )
+    .assert()
+    .success();
 
     let json_out = String::from_utf8_lossy(&assert.get_output().stdout);
     let json_struct: Value = if output_path.is_some() {
@@ -80,25 +121,75 @@ pub fn run_encode_command(
     Ok(json_struct)
 }
 
-pub fn run_decode_command(
+/// Prepares the decode command, `extra_args` are passed after the stego text and pivot.
+pub fn decode_command(
     stego_text: &PathBuf,
     pivot: usize,
     output_path: Option<&PathBuf>,
     method: &str,
-) -> Result<Value, Box<dyn Error>> {
+    extra_args: &[&str],
+) -> Command {
     let mut cmd = Command::cargo_bin("ptero_cli").unwrap();
     if let Some(path) = output_path {
         cmd.arg("-o").arg(path);
     } else {
         cmd.arg("--json");
     }
-    let assert = cmd
-        .arg("decode")
+    cmd.arg("decode")
         .arg(format!("--{}", method))
         .arg("-t")
         .arg(&stego_text)
         .arg("--pivot")
         .arg(format!("{}", pivot))
+        .args(extra_args);
+    cmd
+}
+
+pub fn run_decode_command(
+    stego_text: &PathBuf,
+    pivot: usize,
+    output_path: Option<&PathBuf>,
+    method: &str,
+    extra_args: &[&str],
+) -> Result<Value, Box<dyn Error>> {
+    let assert = decode_command(stego_text, pivot, output_path, method, extra_args)
+        .assert()
+        .success();
+
+    let json_out = String::from_utf8_lossy(&assert.get_output().stdout);
+    let json_struct: Value = if output_path.is_some() {
+        serde_json::from_str("{}")?
+    } else {
+        serde_json::from_str(&json_out)?
+    };
+
+    Ok(json_struct)
+}
+
+/// Prepares the decode command without the method and the pivot, which are read from the stego text header
+/// or found with `--auto`.
+/// `extra_args` are passed after the stego text.
+pub fn header_decode_command(
+    stego_text: &PathBuf,
+    output_path: Option<&PathBuf>,
+    extra_args: &[&str],
+) -> Command {
+    let mut cmd = Command::cargo_bin("ptero_cli").unwrap();
+    if let Some(path) = output_path {
+        cmd.arg("-o").arg(path);
+    } else {
+        cmd.arg("--json");
+    }
+    cmd.arg("decode").arg("-t").arg(stego_text).args(extra_args);
+    cmd
+}
+
+pub fn run_header_decode_command(
+    stego_text: &PathBuf,
+    output_path: Option<&PathBuf>,
+    extra_args: &[&str],
+) -> Result<Value, Box<dyn Error>> {
+    let assert = header_decode_command(stego_text, output_path, extra_args)
         .assert()
         .success();
 